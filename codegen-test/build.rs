@@ -0,0 +1,21 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use radixtree::codegen::RouteTable;
+use radixtree::Method;
+
+fn main() {
+    let mut table = RouteTable::new();
+    table.route(Method::GET, "/", "crate::handlers::index").unwrap();
+    table.route(Method::GET, "/users", "crate::handlers::list_users").unwrap();
+    table.route(Method::POST, "/users", "crate::handlers::create_user").unwrap();
+    table.route(Method::GET, "/users/new", "crate::handlers::new_user").unwrap();
+    table.route(Method::GET, "/users/$id", "crate::handlers::show_user").unwrap();
+    table.route(Method::GET, "/users/$id/posts/$post", "crate::handlers::show_post").unwrap();
+    table.route(Method::ANY, "/files/$path*", "crate::handlers::files").unwrap();
+    table.route(Method::extension("PURGE").unwrap(), "/cache/*", "crate::handlers::purge").unwrap();
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("routes.rs");
+    fs::write(out, table.generate()).unwrap();
+    println!("cargo:rerun-if-changed=build.rs");
+}
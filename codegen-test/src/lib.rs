@@ -0,0 +1,18 @@
+//! A tree generated by `radixtree::codegen` in the build script.
+use radixtree::Node;
+
+pub mod handlers {
+    pub fn index() -> &'static str { "index" }
+    pub fn list_users() -> &'static str { "list_users" }
+    pub fn create_user() -> &'static str { "create_user" }
+    pub fn new_user() -> &'static str { "new_user" }
+    pub fn show_user() -> &'static str { "show_user" }
+    pub fn show_post() -> &'static str { "show_post" }
+    pub fn files() -> &'static str { "files" }
+    pub fn purge() -> &'static str { "purge" }
+}
+
+/// Builds the generated tree.
+pub fn routes() -> Node<fn() -> &'static str> {
+    include!(concat!(env!("OUT_DIR"), "/routes.rs"))
+}
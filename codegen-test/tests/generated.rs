@@ -0,0 +1,54 @@
+use radixtree::{Method, Node};
+use radixtree_codegen_test::routes;
+
+fn search(method: Method, path: &str) -> Option<(&'static str, Vec<(String, String)>)> {
+    let result = routes().search(method, path)?;
+    let params = result.params().iter().map(|param| (param.name().clone(), param.value().clone())).collect();
+    Some(((result.value())(), params))
+}
+
+fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs.iter().map(|&(name, value)| (name.to_string(), value.to_string())).collect()
+}
+
+#[test]
+fn the_generated_tree_finds_the_routes() {
+    assert_eq!(search(Method::GET, "/"), Some(("index", params(&[]))));
+    assert_eq!(search(Method::GET, "/users"), Some(("list_users", params(&[]))));
+    assert_eq!(search(Method::POST, "/users"), Some(("create_user", params(&[]))));
+    assert_eq!(search(Method::GET, "/users/new"), Some(("new_user", params(&[]))));
+    assert_eq!(search(Method::GET, "/users/7"), Some(("show_user", params(&[("id", "7")]))));
+    assert_eq!(search(Method::GET, "/users/7/posts/9"), Some(("show_post", params(&[("id", "7"), ("post", "9")]))));
+    assert_eq!(search(Method::DELETE, "/files/a/b.txt"), Some(("files", params(&[("path", "a/b.txt")]))));
+    assert_eq!(search("PURGE".parse().unwrap(), "/cache/x/y").map(|(value, _)| value), Some("purge"));
+}
+
+#[test]
+fn the_generated_tree_misses_like_the_tree() {
+    assert_eq!(search(Method::PUT, "/users"), None);
+    assert_eq!(search(Method::GET, "/users/7/posts"), None);
+    assert_eq!(search(Method::GET, "/cache/x"), None);
+    assert_eq!(search(Method::GET, "/missing"), None);
+}
+
+#[test]
+fn the_generated_tree_equals_the_inserted_tree() {
+    let mut tree: Node<&'static str> = Node::new();
+    let generated = routes();
+    for (method, pattern) in [
+        (Method::GET, "/"),
+        (Method::GET, "/users"),
+        (Method::POST, "/users"),
+        (Method::GET, "/users/new"),
+        (Method::GET, "/users/$id"),
+        (Method::GET, "/users/$id/posts/$post"),
+        (Method::ANY, "/files/$path*"),
+        ("PURGE".parse().unwrap(), "/cache/*"),
+    ] {
+        tree.insert(method, pattern, "");
+    }
+
+    let generated: Vec<(&Method, String)> = generated.iter().map(|(method, pattern, _)| (method, pattern)).collect();
+    let inserted: Vec<(&Method, String)> = tree.iter().map(|(method, pattern, _)| (method, pattern)).collect();
+    assert_eq!(generated, inserted);
+}
@@ -0,0 +1,138 @@
+use core::hash::BuildHasher;
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::string::{String, ToString};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::vec::Vec;
+use crate::method::Method;
+use crate::tree::Matched;
+
+/// The number of shards of a cache, so that concurrent misses rarely wait for
+/// each other.
+const SHARDS: usize = 16;
+
+/// A cache of the latest search matches of a router. Misses are cached too.
+///
+/// The searches are spread over shards by their method and path. A hit only
+/// takes the read lock of its shard and marks the match as used, and a miss
+/// takes the write lock to add its match, evicting the first match which
+/// wasn't used since the last eviction pass (the CLOCK approximation of
+/// LRU). The lock isn't held during the search itself.
+///
+/// The matches hold the matched leaf, its pattern and the offsets of the
+/// parameters in the path rather than whole results.
+pub(crate) struct SearchCache {
+    capacity: usize,
+    hasher: RandomState,
+    shards: Vec<RwLock<Shard>>,
+}
+
+/// A cached match, with whether it was used since the last eviction pass.
+struct Entry {
+    used: AtomicBool,
+    matched: Option<Arc<Matched>>,
+}
+
+#[derive(Default)]
+struct Shard {
+    /// The match of each path by method
+    entries: HashMap<Method, HashMap<String, Entry>>,
+    /// The method and path of the matches, in the order of the eviction pass
+    clock: VecDeque<(Method, String)>,
+}
+
+impl SearchCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            hasher: RandomState::new(),
+            shards: (0..SHARDS.min(capacity)).map(|_| RwLock::default()).collect(),
+        }
+    }
+
+    /// Returns the cached match of the search, or caches the match of `search`.
+    pub(crate) fn get_or_search<F>(&self, method: &Method, path: &str, search: F) -> Option<Arc<Matched>>
+    where
+        F: FnOnce() -> Option<Matched>,
+    {
+        let index = self.hasher.hash_one((method, path)) as usize % self.shards.len();
+        let shard = &self.shards[index];
+        {
+            let shard = shard.read().unwrap_or_else(PoisonError::into_inner);
+            if let Some(entry) = shard.entries.get(method).and_then(|paths| paths.get(path)) {
+                entry.used.store(true, Ordering::Relaxed);
+                return entry.matched.clone();
+            }
+        }
+
+        let matched = search().map(Arc::new);
+        let mut shard = shard.write().unwrap_or_else(PoisonError::into_inner);
+        // Another thread may have cached the same search meanwhile.
+        if shard.entries.get(method).is_some_and(|paths| paths.contains_key(path)) {
+            return matched;
+        }
+
+        // The capacity is split evenly, the first shards taking the rest.
+        let capacity = self.capacity / self.shards.len() + usize::from(index < self.capacity % self.shards.len());
+        if shard.clock.len() >= capacity {
+            shard.evict();
+        }
+        shard.clock.push_back((method.clone(), path.to_string()));
+        let entry = Entry {
+            used: AtomicBool::new(false),
+            matched: matched.clone(),
+        };
+        shard.entries.entry(method.clone()).or_default().insert(path.to_string(), entry);
+        matched
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Removes every cached match, after a change of the router.
+    pub(crate) fn clear(&mut self) {
+        for shard in self.shards.iter_mut() {
+            let shard = shard.get_mut().unwrap_or_else(PoisonError::into_inner);
+            shard.entries.clear();
+            shard.clock.clear();
+        }
+    }
+}
+
+impl Shard {
+    /// Removes the first match of the clock which wasn't used since the last
+    /// pass, giving the used ones a second chance.
+    fn evict(&mut self) {
+        while let Some((method, path)) = self.clock.pop_front() {
+            let Some(paths) = self.entries.get_mut(&method) else {
+                continue;
+            };
+            if paths.get_mut(&path).is_some_and(|entry| entry.used.swap(false, Ordering::Relaxed)) {
+                self.clock.push_back((method, path));
+                continue;
+            }
+
+            paths.remove(&path);
+            if paths.is_empty() {
+                self.entries.remove(&method);
+            }
+            return;
+        }
+    }
+}
+
+/// A clone starts with an empty cache of the same capacity.
+impl Clone for SearchCache {
+    fn clone(&self) -> Self {
+        Self::new(self.capacity)
+    }
+}
+
+impl fmt::Debug for SearchCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SearchCache({})", self.capacity)
+    }
+}
@@ -0,0 +1,55 @@
+//! Generates the Rust source of a radix tree, such as in a build script.
+//!
+//! The routes are split and checked for conflicts when the source is
+//! generated, so building the tree at startup doesn't insert routes or panic.
+//!
+//! ```text
+//! // build.rs
+//! let mut table = radixtree::codegen::RouteTable::new();
+//! table.route(Method::GET, "/users/$id", "handlers::show").unwrap();
+//! let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("routes.rs");
+//! std::fs::write(out, table.generate()).unwrap();
+//!
+//! // main.rs
+//! let tree: Node<fn() -> String> = include!(concat!(env!("OUT_DIR"), "/routes.rs"));
+//! ```
+use alloc::string::{String, ToString};
+use crate::error::ConflictKind;
+use crate::method::Method;
+use crate::tree::{ConflictPolicy, Node};
+
+/// A table of routes whose values are Rust expressions.
+#[derive(Debug, Clone)]
+pub struct RouteTable {
+    tree: Node<String>,
+}
+
+impl RouteTable {
+    pub fn new() -> Self {
+        Self {
+            tree: Node::new(),
+        }
+    }
+
+    /// Adds a route whose value is the Rust expression `value`, returning the
+    /// conflict with the routes already added, if any.
+    pub fn route(&mut self, method: Method, path: &str, value: &str) -> Result<(), ConflictKind> {
+        self.tree.insert_with(&[method], path, value.to_string(), ConflictPolicy::Error)?;
+        Ok(())
+    }
+
+    /// Returns the source of a Rust expression which evaluates to the `Node`
+    /// of the routes.
+    pub fn generate(&self) -> String {
+        let mut out = String::new();
+        self.tree.write_source(&mut out);
+        out.push('\n');
+        out
+    }
+}
+
+impl Default for RouteTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,238 @@
+use alloc::collections::BTreeMap;
+use alloc::{string::{String, ToString}, vec::Vec};
+use crate::method::Method;
+use crate::normalize::normalize;
+use crate::tree::{Node, Param, SearchOptions, SearchResult, ROOT};
+
+/// The index of a missing child node.
+const NONE: u32 = u32::MAX;
+
+/// A read-only router compiled from a radix tree with `Node::compile`.
+///
+/// The nodes are flattened into contiguous arrays, and children are referred
+/// to by index, so lookups don't chase pointers. Routes are matched exactly
+/// like `Node::search`, but can't be changed after compiling.
+#[derive(Debug, Clone)]
+pub struct CompiledRouter<V> {
+    pub(crate) nodes: Vec<CompiledNode>,
+    /// The paths of the nodes, back to back
+    pub(crate) paths: String,
+    /// The first letters of the static children of every node, sorted for
+    /// each node
+    pub(crate) static_indices: Vec<char>,
+    /// The static children of every node, as node indices
+    pub(crate) static_child: Vec<u32>,
+    /// The handlers of every node
+    pub(crate) handlers: Vec<(Method, V)>,
+    /// The parameter names of every leaf node
+    pub(crate) param_names: Vec<String>,
+    /// The patterns of the leaf nodes, back to back
+    pub(crate) patterns: String,
+    pub(crate) options: SearchOptions,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CompiledNode {
+    pub(crate) path: (u32, u32),
+    /// The range of the static children in `static_indices` and `static_child`
+    pub(crate) static_child: (u32, u32),
+    pub(crate) param_child: u32,
+    pub(crate) star_child: u32,
+    pub(crate) handlers: (u32, u32),
+    pub(crate) param_names: (u32, u32),
+    /// The range of the pattern in `patterns`, which is empty for inner nodes
+    pub(crate) pattern: (u32, u32),
+    /// Whether a param node is followed by static text in the same segment
+    pub(crate) in_segment: bool,
+    /// Whether the node has any child
+    pub(crate) has_children: bool,
+}
+
+impl<V: Clone> CompiledRouter<V> {
+    pub(crate) fn new(tree: &Node<V>, options: SearchOptions) -> Self {
+        let mut router = Self {
+            nodes: Vec::new(),
+            paths: String::new(),
+            static_indices: Vec::new(),
+            static_child: Vec::new(),
+            handlers: Vec::new(),
+            param_names: Vec::new(),
+            patterns: String::new(),
+            options,
+        };
+
+        let mut patterns = BTreeMap::new();
+        tree.walk_leaves(ROOT, "/".to_string(), "", &mut |leaf, pattern| {
+            patterns.insert(leaf, pattern.to_string());
+        });
+        router.push_node(tree, ROOT, &patterns);
+        router
+    }
+
+    /// Flattens the node and its children, returning the index of the node.
+    fn push_node(&mut self, tree: &Node<V>, id: usize, patterns: &BTreeMap<usize, String>) -> u32 {
+        let node = &tree.nodes[id];
+        let index = self.nodes.len();
+
+        let path = (self.paths.len() as u32, (self.paths.len() + node.path.len()) as u32);
+        self.paths.push_str(&node.path);
+
+        let handlers_start = self.handlers.len() as u32;
+        self.handlers.extend(node.leaf_handler.iter().map(|(method, value)| (method.clone(), value.clone())));
+
+        let names_start = self.param_names.len() as u32;
+        self.param_names.extend(node.leaf_param_names.iter().flatten().cloned());
+
+        let pattern = patterns.get(&id).map_or("", |pattern| pattern.as_str());
+        let pattern_range = (self.patterns.len() as u32, (self.patterns.len() + pattern.len()) as u32);
+        self.patterns.push_str(pattern);
+
+        self.nodes.push(CompiledNode {
+            path,
+            static_child: (0, 0),
+            param_child: NONE,
+            star_child: NONE,
+            handlers: (handlers_start, self.handlers.len() as u32),
+            param_names: (names_start, self.param_names.len() as u32),
+            pattern: pattern_range,
+            in_segment: node.static_child.iter().any(|&child| !tree.nodes[child].path.starts_with('/')),
+            has_children: node.has_children(),
+        });
+
+        // The children are flattened first, so that the static children of the
+        // node are contiguous.
+        let children: Vec<u32> = node.static_child.iter().map(|&child| self.push_node(tree, child, patterns)).collect();
+        let static_child = (self.static_child.len() as u32, (self.static_child.len() + children.len()) as u32);
+        self.static_child.extend(children);
+        self.static_indices.extend(node.static_indices.iter());
+
+        let param_child = node.param_child.map_or(NONE, |child| self.push_node(tree, child, patterns));
+        let star_child = node.star_child.map_or(NONE, |child| self.push_node(tree, child, patterns));
+
+        let compiled = &mut self.nodes[index];
+        compiled.static_child = static_child;
+        compiled.param_child = param_child;
+        compiled.star_child = star_child;
+        index as u32
+    }
+
+    /// Returns Some(SearchResult<V>), if successful. Otherwise returns None.
+    pub fn search(&self, method: Method, path: &str) -> Option<SearchResult<V>> {
+        let path = normalize(path, self.options.unicode, self.options.merge_slashes, self.options.dot_segments)?;
+        let path = path.strip_prefix('/').unwrap_or(&path);
+        let mut values = Vec::new();
+        let (node, handler) = self.find(0, &method, path, &mut values)?;
+
+        let (start, end) = self.nodes[node as usize].param_names;
+        let params = self.param_names[start as usize..end as usize].iter()
+            .zip(values)
+            .map(|(name, value)| Param::new(name.clone(), value.to_string()))
+            .collect();
+
+        let (start, end) = self.nodes[node as usize].pattern;
+        let pattern = self.patterns[start as usize..end as usize].to_string();
+        Some(SearchResult::new(self.handlers[handler].1.clone(), params, pattern))
+    }
+
+    /// Returns the matched node and handler, pushing the parameter values
+    /// along the way. It tries the static child, then the param child and
+    /// finally the wildcard child, like `Node::search`.
+    fn find<'p>(&self, index: u32, method: &Method, path: &'p str, values: &mut Vec<&'p str>) -> Option<(u32, usize)> {
+        let node = &self.nodes[index as usize];
+
+        if path.is_empty() {
+            if let Some(handler) = self.get_handler(index, method) {
+                return Some((index, handler));
+            }
+
+            // A `$path*` parameter also matches an empty path.
+            if node.star_child != NONE && self.node_path(node.star_child).ends_with('*') {
+                let handler = self.get_handler(node.star_child, method)?;
+                values.push(path);
+                return Some((node.star_child, handler));
+            }
+            return None;
+        }
+
+        // First see if this matches a static path
+        let first_char = path.chars().next().unwrap();
+        let (start, end) = node.static_child;
+        if let Ok(i) = self.static_indices[start as usize..end as usize].binary_search(&first_char) {
+            let child = self.static_child[start as usize + i];
+            if let Some(next_path) = path.strip_prefix(self.node_path(child)) {
+                if let Some(found) = self.find(child, method, next_path, values) {
+                    return Some(found);
+                }
+            }
+        }
+
+        // Didn't find a static path, so check for a path parameter.
+        if node.param_child != NONE {
+            let segment_end = path.find('/').unwrap_or(path.len());
+            let ends = path[..segment_end].char_indices()
+                .skip(1)
+                .map(|(i, _)| i)
+                .filter(|_| self.nodes[node.param_child as usize].in_segment)
+                .chain((segment_end > 0).then_some(segment_end));
+
+            for value_end in ends {
+                values.push(&path[..value_end]);
+                if let Some(found) = self.find(node.param_child, method, &path[value_end..], values) {
+                    return Some(found);
+                }
+                values.pop();
+            }
+        }
+
+        // Finally check for a wildcard *
+        if node.star_child != NONE {
+            let star_child = &self.nodes[node.star_child as usize];
+
+            // A wildcard in the middle of a pattern matches one or more
+            // segments, trying the fewest segments first.
+            if star_child.has_children {
+                for (next_slash, _) in path.match_indices('/').filter(|(i, _)| *i > 0) {
+                    if let Some(found) = self.find(node.star_child, method, &path[next_slash..], values) {
+                        return Some(found);
+                    }
+                }
+            }
+
+            // A wildcard at the end of a pattern matches the rest of the path,
+            // which is the value of a multi-segment parameter.
+            let handler = self.get_handler(node.star_child, method)?;
+            if !self.node_path(node.star_child).is_empty() {
+                values.push(path);
+            }
+            return Some((node.star_child, handler));
+        }
+
+        None
+    }
+
+    fn node_path(&self, index: u32) -> &str {
+        let (start, end) = self.nodes[index as usize].path;
+        &self.paths[start as usize..end as usize]
+    }
+
+    /// Returns the index of the handler of the method, falling back like
+    /// `Node::search`.
+    fn get_handler(&self, index: u32, method: &Method) -> Option<usize> {
+        let (start, end) = self.nodes[index as usize].handlers;
+        let position = |method: &Method| {
+            self.handlers[start as usize..end as usize].iter()
+                .position(|(m, _)| m == method)
+                .map(|i| start as usize + i)
+        };
+
+        position(method)
+            .or_else(|| {
+                if self.options.head_can_use_get && *method == Method::HEAD {
+                    position(&Method::GET)
+                } else {
+                    None
+                }
+            })
+            .or_else(|| position(&Method::ANY))
+    }
+}
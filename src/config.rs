@@ -0,0 +1,63 @@
+use std::io::Read;
+use std::string::{String, ToString};
+use serde_json::Value;
+use crate::error::ConfigError;
+use crate::method::Method;
+use crate::tree::{ConflictPolicy, Node};
+
+/// The format of a route table read by `Node::from_config`.
+///
+/// Only JSON is supported, as the crate doesn't depend on a TOML or YAML
+/// parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConfigFormat {
+    /// An object with a `routes` array, whose routes are objects with a
+    /// `method`, a `pattern` and a `key`, such as
+    /// `{"routes": [{"method": "GET", "pattern": "/users/$id", "key": "show_user"}]}`.
+    Json,
+}
+
+impl<V: Clone> Node<V> {
+    /// Creates a tree from a route table managed outside of the code, such as
+    /// the routes of a gateway. The key of each route is turned into its
+    /// value by `resolve`.
+    ///
+    /// Methods are case-sensitive, and `ANY` is `Method::ANY`. Patterns are
+    /// in the native syntax.
+    pub fn from_config<R, F>(mut reader: R, format: ConfigFormat, mut resolve: F) -> Result<Self, ConfigError>
+    where
+        R: Read,
+        F: FnMut(&str) -> Option<V>,
+    {
+        let mut text = String::new();
+        reader.read_to_string(&mut text).map_err(|error| ConfigError::Io(error.to_string()))?;
+
+        let document: Value = match format {
+            ConfigFormat::Json => serde_json::from_str(&text).map_err(|error| ConfigError::Parse(error.to_string()))?,
+        };
+        let routes = document.get("routes")
+            .and_then(Value::as_array)
+            .ok_or(ConfigError::MissingRoutes)?;
+
+        let mut tree = Node::new();
+        for (index, route) in routes.iter().enumerate() {
+            let field = |name: &str| {
+                route.get(name)
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| ConfigError::InvalidRoute(index, name.to_string()))
+            };
+
+            let method = Method::from_name(field("method")?)
+                .map_err(|_| ConfigError::InvalidRoute(index, "method".to_string()))?;
+            let pattern = field("pattern")?;
+            let key = field("key")?;
+            let value = resolve(key).ok_or_else(|| ConfigError::UnknownKey(key.to_string()))?;
+
+            tree.insert_with(&[method], pattern, value, ConflictPolicy::Error)
+                .map_err(|kind| ConfigError::Conflict(index, kind))?;
+        }
+
+        Ok(tree)
+    }
+}
@@ -0,0 +1,87 @@
+use core::fmt;
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use crate::pattern::param_name_len;
+use crate::tree::Params;
+
+/// A predicate on the value of a parameter.
+type Constraint = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// The constraints registered with `Router::register_constraint`, by name.
+#[derive(Clone, Default)]
+pub(crate) struct ConstraintRegistry(BTreeMap<String, Constraint>);
+
+impl ConstraintRegistry {
+    pub(crate) fn register<F: Fn(&str) -> bool + Send + Sync + 'static>(&mut self, name: &str, constraint: F) {
+        self.0.insert(name.to_string(), Arc::new(constraint));
+    }
+
+    /// Removes the references to registered constraints from a native
+    /// pattern, such as the `:uuid` of `$id:uuid`, returning the pattern and
+    /// the constraints of its parameters. A reference to a constraint which
+    /// isn't registered is kept as static text.
+    pub(crate) fn split<'a>(&self, pattern: &'a str) -> (Cow<'a, str>, ParamConstraints) {
+        let mut constraints = Vec::new();
+        if self.0.is_empty() || !pattern.contains(':') {
+            return (Cow::Borrowed(pattern), ParamConstraints(constraints));
+        }
+
+        let mut stripped = String::new();
+        let mut rest = pattern;
+        while let Some(c) = rest.chars().next() {
+            let len = match c {
+                '\\' => rest.chars().nth(1).map_or(1, |escaped| 1 + escaped.len_utf8()),
+                '$' => 1 + param_name_len(&rest[1..]),
+                _ => c.len_utf8(),
+            };
+            stripped.push_str(&rest[..len]);
+
+            let name = &rest[1..len];
+            rest = &rest[len..];
+            if c != '$' || name.is_empty() {
+                continue;
+            }
+
+            let Some(reference) = rest.strip_prefix(':') else {
+                continue;
+            };
+            let reference = &reference[..param_name_len(reference)];
+            if let Some(constraint) = self.0.get(reference) {
+                constraints.push((name.to_string(), constraint.clone()));
+                rest = &rest[1 + reference.len()..];
+            }
+        }
+
+        (Cow::Owned(stripped), ParamConstraints(constraints))
+    }
+}
+
+impl fmt::Debug for ConstraintRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+/// The constraints and validators of the parameters of a route, kept in its
+/// extensions.
+#[derive(Clone, Default)]
+pub(crate) struct ParamConstraints(Vec<(String, Constraint)>);
+
+impl ParamConstraints {
+    pub(crate) fn push<F: Fn(&str) -> bool + Send + Sync + 'static>(&mut self, name: &str, validator: F) {
+        self.0.push((name.to_string(), Arc::new(validator)));
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns true if the value of each constrained parameter satisfies its
+    /// constraint.
+    pub(crate) fn check(&self, params: &Params) -> bool {
+        self.0.iter().all(|(name, constraint)| params.get(name).is_some_and(|value| constraint(value)))
+    }
+}
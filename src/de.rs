@@ -0,0 +1,215 @@
+use core::fmt;
+use core::str::FromStr;
+use alloc::{format, string::ToString};
+use serde_core::de::{self, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde_core::forward_to_deserialize_any;
+use crate::error::ParamError;
+use crate::tree::Param;
+
+impl de::Error for ParamError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ParamError::Deserialize(msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        ParamError::Missing(field.to_string())
+    }
+}
+
+/// Deserializes the params as a map from their names to their values, or
+/// as a sequence of their values, such as a tuple. A single param can also
+/// be deserialized as its value.
+pub(crate) struct ParamsDeserializer<'de> {
+    params: &'de [Param],
+}
+
+impl<'de> ParamsDeserializer<'de> {
+    pub(crate) fn new(params: &'de [Param]) -> Self {
+        Self { params }
+    }
+
+    /// Returns the only param, for types which aren't a map or a sequence.
+    fn single(&self) -> Result<ValueDeserializer<'de>, ParamError> {
+        match self.params {
+            [param] => Ok(ValueDeserializer(param)),
+            _ => Err(ParamError::Deserialize(format!("expected a single path parameter, found {}", self.params.len()))),
+        }
+    }
+}
+
+macro_rules! forward_to_single {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+                self.single()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ParamsDeserializer<'de> {
+    type Error = ParamError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+        visitor.visit_map(ParamsAccess { params: self.params.iter(), value: None })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, ParamError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+        visitor.visit_seq(ParamsAccess { params: self.params.iter(), value: None })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, ParamError> {
+        if len != self.params.len() {
+            return Err(ParamError::Deserialize(format!("expected {} path parameters, found {}", len, self.params.len())));
+        }
+
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, len: usize, visitor: V) -> Result<V::Value, ParamError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, ParamError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, ParamError> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_single! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_option deserialize_identifier
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, ParamError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+        visitor.visit_unit()
+    }
+}
+
+/// Visits the params as map entries or as sequence elements.
+struct ParamsAccess<'de> {
+    params: core::slice::Iter<'de, Param>,
+    /// The param whose name was visited, but not its value yet
+    value: Option<&'de Param>,
+}
+
+impl<'de> MapAccess<'de> for ParamsAccess<'de> {
+    type Error = ParamError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, ParamError> {
+        let Some(param) = self.params.next() else {
+            return Ok(None);
+        };
+
+        self.value = Some(param);
+        seed.deserialize(param.name().as_str().into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, ParamError> {
+        let param = self.value.take().expect("next_value_seed is called after next_key_seed");
+        seed.deserialize(ValueDeserializer(param))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.params.len())
+    }
+}
+
+impl<'de> SeqAccess<'de> for ParamsAccess<'de> {
+    type Error = ParamError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, ParamError> {
+        self.params.next()
+            .map(|param| seed.deserialize(ValueDeserializer(param)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.params.len())
+    }
+}
+
+/// Deserializes the value of a param, parsing it for numbers, booleans and
+/// characters.
+struct ValueDeserializer<'de>(&'de Param);
+
+impl<'de> ValueDeserializer<'de> {
+    fn parse<T: FromStr>(&self) -> Result<T, ParamError>
+    where
+        T::Err: fmt::Display,
+    {
+        let (name, value) = (self.0.name(), self.0.value());
+        value.parse().map_err(|error| ParamError::invalid(name, value, error))
+    }
+}
+
+macro_rules! parse_value {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = ParamError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+        visitor.visit_borrowed_str(self.0.value())
+    }
+
+    parse_value! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ParamError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, ParamError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, ParamError> {
+        visitor.visit_enum(self.0.value().as_str().into_deserializer())
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
@@ -0,0 +1,395 @@
+use core::error::Error;
+use core::fmt;
+use alloc::{string::{String, ToString}, vec::Vec};
+use crate::method::Method;
+
+/// The error returned when a URL can't be generated for a named route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathForError {
+    /// No route is registered with the name.
+    UnknownRoute(String),
+    /// The route requires a parameter that wasn't given.
+    MissingParam(String),
+    /// A parameter was given that the route doesn't have.
+    UnexpectedParam(String),
+    /// The value of a single segment parameter contains a `/`, or the value of
+    /// a parameter other than `$path*` is empty.
+    InvalidParam(String),
+}
+
+impl fmt::Display for PathForError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathForError::UnknownRoute(name) => write!(f, "no route is named {:?}", name),
+            PathForError::MissingParam(name) => write!(f, "missing path parameter {:?}", name),
+            PathForError::UnexpectedParam(name) => write!(f, "unexpected path parameter {:?}", name),
+            PathForError::InvalidParam(name) => write!(f, "invalid value of path parameter {:?}", name),
+        }
+    }
+}
+
+impl Error for PathForError {}
+
+/// The error returned when a path parameter can't be extracted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    /// The route has no parameter with the name.
+    Missing(String),
+    /// The value of the parameter can't be parsed, with the parse error.
+    Invalid { name: String, value: String, message: String },
+    /// The parameters can't be deserialized into the type, with the reason.
+    Deserialize(String),
+}
+
+impl ParamError {
+    pub(crate) fn invalid(name: &str, value: &str, error: impl fmt::Display) -> Self {
+        ParamError::Invalid {
+            name: name.to_string(),
+            value: value.to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::Missing(name) => write!(f, "missing path parameter {:?}", name),
+            ParamError::Invalid { name, value, message } => {
+                write!(f, "invalid value {:?} of path parameter {:?}: {}", value, name, message)
+            }
+            ParamError::Deserialize(message) => write!(f, "can't deserialize the path parameters: {}", message),
+        }
+    }
+}
+
+impl Error for ParamError {}
+
+/// The error returned when a value can't be updated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateError<K = Method> {
+    /// The path isn't registered.
+    PathNotFound,
+    /// The path is registered, but not for the method.
+    MethodNotFound(K),
+    /// No route is registered with the name.
+    UnknownName(String),
+}
+
+impl<K: fmt::Display> fmt::Display for UpdateError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateError::PathNotFound => write!(f, "the path is not registered"),
+            UpdateError::MethodNotFound(method) => write!(f, "the method {} is not registered for the path", method),
+            UpdateError::UnknownName(name) => write!(f, "no route is named {:?}", name),
+        }
+    }
+}
+
+impl<K: fmt::Debug + fmt::Display> Error for UpdateError<K> {}
+
+/// The error returned when a route pattern is invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternError {
+    message: String,
+}
+
+impl PatternError {
+    pub(crate) fn new(message: String) -> Self {
+        Self { message }
+    }
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for PatternError {}
+
+/// The reason a route can't be inserted into the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// The pattern isn't valid.
+    InvalidPattern(PatternError),
+    /// The method is already registered for the pattern.
+    Duplicate,
+    /// The pattern only differs from a registered pattern in the names of its
+    /// parameters, which are given.
+    AmbiguousParams(Vec<String>),
+    /// A different wildcard is registered at the same position, such as
+    /// `/files/*` and `/files/$path+`, so one would shadow the other. The
+    /// registered wildcard is given.
+    Shadowing(String),
+    /// The pattern exceeds a limit of the router.
+    LimitExceeded(LimitError),
+    /// The route name is already used by another pattern, which is given.
+    NameTaken(String),
+}
+
+impl fmt::Display for ConflictKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConflictKind::InvalidPattern(error) => write!(f, "invalid pattern: {}", error),
+            ConflictKind::Duplicate => write!(f, "the method is already registered for the path"),
+            ConflictKind::AmbiguousParams(names) => write!(f, "the path parameters are ambiguous with {:?}", names),
+            ConflictKind::Shadowing(wildcard) => write!(f, "the wildcard is ambiguous with {:?}", wildcard),
+            ConflictKind::LimitExceeded(error) => write!(f, "{}", error),
+            ConflictKind::NameTaken(pattern) => write!(f, "the route name is already used by {:?}", pattern),
+        }
+    }
+}
+
+impl Error for ConflictKind {}
+
+/// The limit of `Limits` which a pattern or a path exceeds, with its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitError {
+    /// The pattern or the path is longer than the maximum length.
+    PathLength(usize),
+    /// The pattern or the path has more segments than the maximum.
+    Segments(usize),
+    /// The tree would be deeper than the maximum depth.
+    Depth(usize),
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitError::PathLength(max) => write!(f, "the path is longer than {} bytes", max),
+            LimitError::Segments(max) => write!(f, "the path has more than {} segments", max),
+            LimitError::Depth(max) => write!(f, "the tree would be deeper than {} nodes", max),
+        }
+    }
+}
+
+impl Error for LimitError {}
+
+/// A broken invariant of a tree, found by `Node::validate`. Each variant
+/// gives the pattern of the node, as far as it can be told.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// The indices of the static children don't match their first characters.
+    StaticIndices(String),
+    /// The static children aren't sorted by their first character, or two of
+    /// them start with the same character.
+    UnsortedStaticChildren(String),
+    /// A static node has no text.
+    EmptyStaticNode(String),
+    /// A node without handlers has a single static child, and should have
+    /// been merged with it.
+    UnmergedNode(String),
+    /// A node has neither handlers nor children.
+    UnusedNode(String),
+    /// The parameter names of a route don't match its parameters.
+    ParamNames(String),
+    /// A parameter node has an invalid name.
+    InvalidParamName(String),
+    /// A wildcard node doesn't start a segment or has an invalid name.
+    InvalidWildcard(String),
+    /// A child index is out of the arena.
+    InvalidIndex(String),
+    /// A node is the child of several nodes, or is both a child and free.
+    SharedNode(String),
+    /// The free list has an invalid or repeated index, or the root.
+    InvalidFreeList(usize),
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::StaticIndices(pattern) => write!(f, "the static indices of {:?} don't match its children", pattern),
+            Violation::UnsortedStaticChildren(pattern) => write!(f, "the static children of {:?} aren't sorted", pattern),
+            Violation::EmptyStaticNode(pattern) => write!(f, "the static node {:?} is empty", pattern),
+            Violation::UnmergedNode(pattern) => write!(f, "the node {:?} should be merged with its only child", pattern),
+            Violation::UnusedNode(pattern) => write!(f, "the node {:?} has neither handlers nor children", pattern),
+            Violation::ParamNames(pattern) => write!(f, "the parameter names of {:?} don't match its parameters", pattern),
+            Violation::InvalidParamName(pattern) => write!(f, "the parameter child of {:?} has an invalid name", pattern),
+            Violation::InvalidWildcard(pattern) => write!(f, "the wildcard child of {:?} is invalid", pattern),
+            Violation::InvalidIndex(pattern) => write!(f, "a child of {:?} is out of the arena", pattern),
+            Violation::SharedNode(pattern) => write!(f, "the node {:?} is reachable more than once", pattern),
+            Violation::InvalidFreeList(index) => write!(f, "the free list has the invalid index {}", index),
+        }
+    }
+}
+
+impl Error for Violation {}
+
+/// The error returned when a transaction can't be committed. Each variant
+/// gives the index of the failed change, in the order they were staged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionError {
+    /// The pattern of the change is invalid.
+    InvalidPattern(usize, PatternError),
+    /// The inserted route conflicts with another route.
+    Conflict(usize, ConflictKind),
+    /// The updated route isn't registered.
+    Update(usize, UpdateError),
+    /// The removed route isn't registered.
+    NotFound(usize),
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionError::InvalidPattern(index, error) => write!(f, "change {} has an invalid pattern: {}", index, error),
+            TransactionError::Conflict(index, kind) => write!(f, "change {} can't be inserted: {}", index, kind),
+            TransactionError::Update(index, error) => write!(f, "change {} can't be updated: {}", index, error),
+            TransactionError::NotFound(index) => write!(f, "change {} removes a route which is not registered", index),
+        }
+    }
+}
+
+impl Error for TransactionError {}
+
+/// The error returned when a route listing of `Node::dump` can't be parsed.
+/// Lines are numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DumpError {
+    /// The line isn't a method followed by a space and a pattern.
+    InvalidLine(usize),
+    /// The route of the line conflicts with a previous route.
+    Conflict(usize, ConflictKind),
+}
+
+impl fmt::Display for DumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DumpError::InvalidLine(line) => write!(f, "line {} is not a method and a pattern", line),
+            DumpError::Conflict(line, kind) => write!(f, "the route of line {} can't be inserted: {}", line, kind),
+        }
+    }
+}
+
+impl Error for DumpError {}
+
+/// The error returned when a binary snapshot of a tree can't be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// The bytes aren't a snapshot, or the snapshot is truncated or corrupted.
+    InvalidFormat,
+    /// The snapshot has another version of the format, which is given.
+    UnsupportedVersion(u8),
+    /// The codec can't decode a value.
+    InvalidValue,
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::InvalidFormat => write!(f, "invalid snapshot"),
+            SnapshotError::UnsupportedVersion(version) => write!(f, "unsupported snapshot version {}", version),
+            SnapshotError::InvalidValue => write!(f, "the snapshot has a value which can't be decoded"),
+        }
+    }
+}
+
+impl Error for SnapshotError {}
+
+/// The error returned when a string isn't a valid HTTP method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMethod {
+    _priv: (),
+}
+
+impl InvalidMethod {
+    pub(crate) fn new() -> Self {
+        Self { _priv: () }
+    }
+}
+
+impl fmt::Display for InvalidMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid HTTP method")
+    }
+}
+
+impl Error for InvalidMethod {}
+
+/// The error returned when an HTTP request line can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestLineError {
+    /// The line isn't a method, a target and a version separated by single
+    /// spaces.
+    Malformed,
+    /// The method isn't a valid HTTP token.
+    Method(InvalidMethod),
+    /// The target isn't in origin form, such as `/users?id=1`, or in absolute
+    /// form, such as `http://example.com/users`.
+    Target(String),
+    /// The version isn't `HTTP/` followed by a major and a minor digit.
+    Version(String),
+}
+
+impl fmt::Display for RequestLineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestLineError::Malformed => write!(f, "malformed request line"),
+            RequestLineError::Method(error) => write!(f, "{}", error),
+            RequestLineError::Target(target) => write!(f, "unsupported request target {:?}", target),
+            RequestLineError::Version(version) => write!(f, "invalid HTTP version {:?}", version),
+        }
+    }
+}
+
+impl Error for RequestLineError {}
+
+/// The error returned when routes can't be loaded from an OpenAPI document.
+#[cfg(feature = "openapi")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenApiError {
+    /// The document has no `paths` object.
+    MissingPaths,
+    /// The path template has a parameter which can't be converted.
+    UnsupportedTemplate(String),
+}
+
+#[cfg(feature = "openapi")]
+impl fmt::Display for OpenApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OpenApiError::MissingPaths => write!(f, "the document has no paths object"),
+            OpenApiError::UnsupportedTemplate(path) => write!(f, "unsupported path template {:?}", path),
+        }
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl Error for OpenApiError {}
+
+/// The error returned when a route table can't be loaded from a config file.
+/// The index of a route is its position in the table.
+#[cfg(feature = "config")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The file can't be read, with the reason.
+    Io(String),
+    /// The file isn't valid in its format, with the reason.
+    Parse(String),
+    /// The file has no `routes` array.
+    MissingRoutes,
+    /// The field of the route is missing or invalid.
+    InvalidRoute(usize, String),
+    /// The resolver has no value for the key.
+    UnknownKey(String),
+    /// The route conflicts with a previous route.
+    Conflict(usize, ConflictKind),
+}
+
+#[cfg(feature = "config")]
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(message) => write!(f, "can't read the config: {}", message),
+            ConfigError::Parse(message) => write!(f, "can't parse the config: {}", message),
+            ConfigError::MissingRoutes => write!(f, "the config has no routes array"),
+            ConfigError::InvalidRoute(index, field) => write!(f, "route {} has no valid {:?}", index, field),
+            ConfigError::UnknownKey(key) => write!(f, "no value for the key {:?}", key),
+            ConfigError::Conflict(index, kind) => write!(f, "route {} can't be inserted: {}", index, kind),
+        }
+    }
+}
+
+#[cfg(feature = "config")]
+impl Error for ConfigError {}
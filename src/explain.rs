@@ -0,0 +1,109 @@
+use core::fmt;
+use alloc::{string::String, vec::Vec};
+use crate::tree::SearchResult;
+
+/// The result of `Node::explain`: the result of the search and every decision
+/// taken to find it, in order.
+#[derive(Debug)]
+pub struct Explanation<V> {
+    result: Option<SearchResult<V>>,
+    steps: Vec<Step>,
+}
+
+/// A decision of a search. Nodes are given by their path fragment, which is
+/// the name of the parameter for a param node, and empty for a `*` wildcard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// The search entered the node with the rest of the path.
+    Enter { node: String, rest: String },
+    /// No static child starts with the first character of the rest of the path.
+    NoStaticChild { first: char },
+    /// The static child starts like the rest of the path, but isn't a prefix of it.
+    StaticMismatch { child: String, rest: String },
+    /// The value was tried for the parameter.
+    TryParam { name: String, value: String },
+    /// The wildcard was tried for the segments, before the rest of the pattern.
+    TryWildcard { value: String },
+    /// The wildcard at the end of the pattern takes the rest of the path.
+    Wildcard { value: String },
+    /// The path ends at the node, which has a handler for the method.
+    Matched { node: String },
+    /// The path ends at the node, which has no handler for the method.
+    NoHandler { node: String },
+    /// Nothing matched under the node, so the search backtracks.
+    Leave { node: String },
+}
+
+impl<V> Explanation<V> {
+    pub(crate) fn new(result: Option<SearchResult<V>>, steps: Vec<Step>) -> Self {
+        Self { result, steps }
+    }
+
+    /// Returns the result of the search, the same as `Node::search`.
+    pub fn result(&self) -> Option<&SearchResult<V>> {
+        self.result.as_ref()
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Consumes the explanation, returning the result of the search.
+    pub fn into_result(self) -> Option<SearchResult<V>> {
+        self.result
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Enter { node, rest } => write!(f, "enter {:?} with {:?}", node, rest),
+            Step::NoStaticChild { first } => write!(f, "no static child starts with {:?}", first),
+            Step::StaticMismatch { child, rest } => write!(f, "static child {:?} doesn't match {:?}", child, rest),
+            Step::TryParam { name, value } => write!(f, "try {:?} for parameter {:?}", value, name),
+            Step::TryWildcard { value } => write!(f, "try {:?} for the wildcard", value),
+            Step::Wildcard { value } => write!(f, "wildcard takes {:?}", value),
+            Step::Matched { node } => write!(f, "matched {:?}", node),
+            Step::NoHandler { node } => write!(f, "no handler for the method at {:?}", node),
+            Step::Leave { node } => write!(f, "leave {:?}", node),
+        }
+    }
+}
+
+impl<V> fmt::Display for Explanation<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in self.steps.iter() {
+            writeln!(f, "{}", step)?;
+        }
+
+        match self.result {
+            Some(ref result) => write!(f, "found {}", result.pattern()),
+            None => write!(f, "not found"),
+        }
+    }
+}
+
+/// The steps recorded by a search, when it is explained.
+pub(crate) struct Trace(Option<Vec<Step>>);
+
+impl Trace {
+    /// A trace which records nothing, for plain searches.
+    pub(crate) fn off() -> Self {
+        Self(None)
+    }
+
+    pub(crate) fn on() -> Self {
+        Self(Some(Vec::new()))
+    }
+
+    /// Records the step, which is only built when the trace is on.
+    pub(crate) fn record(&mut self, step: impl FnOnce() -> Step) {
+        if let Some(ref mut steps) = self.0 {
+            steps.push(step());
+        }
+    }
+
+    pub(crate) fn into_steps(self) -> Vec<Step> {
+        self.0.unwrap_or_default()
+    }
+}
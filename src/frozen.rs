@@ -0,0 +1,399 @@
+use alloc::{string::{String, ToString}, vec::Vec};
+use crate::compiled::CompiledRouter;
+use crate::error::SnapshotError;
+use crate::method::Method;
+use crate::normalize::{normalize, DotSegments};
+use crate::tree::{Param, SearchResult};
+
+/// The first bytes of a frozen router, followed by its version.
+const MAGIC: &[u8; 4] = b"RDXF";
+/// The version of the layout, which is bumped by every incompatible change.
+const VERSION: u32 = 1;
+/// The index of a missing child node.
+const NONE: u32 = u32::MAX;
+
+/// The tables of the layout, in the order of the header, which gives the
+/// offset and the number of records of each table.
+const NODES: usize = 0;
+const STATIC_INDICES: usize = 1;
+const STATIC_CHILD: usize = 2;
+const HANDLERS: usize = 3;
+const PARAM_NAMES: usize = 4;
+/// The text of the paths, patterns, methods and parameter names, in bytes
+const STRINGS: usize = 5;
+/// The encoded values, in bytes
+const VALUES: usize = 6;
+const TABLES: usize = 7;
+/// The magic, the version, the flags and the offset and length of each table
+const HEADER_LEN: usize = 12 + TABLES * 8;
+
+/// The words of a node record: the ranges of its path, static children,
+/// handlers, parameter names and pattern, its param and wildcard children,
+/// and its flags.
+const PATH: usize = 0;
+const STATIC: usize = 2;
+const PARAM_CHILD: usize = 4;
+const STAR_CHILD: usize = 5;
+const NODE_HANDLERS: usize = 6;
+const NODE_NAMES: usize = 8;
+const PATTERN: usize = 10;
+const FLAGS: usize = 12;
+const NODE_WORDS: usize = 13;
+/// The words of a handler record: the ranges of its method and its value
+const HANDLER_WORDS: usize = 4;
+
+const IN_SEGMENT: u32 = 1;
+const HAS_CHILDREN: u32 = 2;
+const HEAD_CAN_USE_GET: u32 = 1;
+const NORMALIZE_DOT_SEGMENTS: u32 = 2;
+const REJECT_DOT_SEGMENTS: u32 = 4;
+const MERGE_SLASHES: u32 = 8;
+
+impl<V: Clone> CompiledRouter<V> {
+    /// Writes the router in the layout of `FrozenRouter`, so that it can be
+    /// saved to a file and memory-mapped by other processes. Each value is
+    /// appended to the buffer by `encode`.
+    pub fn freeze<F: FnMut(&V, &mut Vec<u8>)>(&self, mut encode: F) -> Vec<u8> {
+        let mut strings = String::new();
+        strings.push_str(&self.paths);
+        strings.push_str(&self.patterns);
+
+        let names: Vec<[u32; 2]> = self.param_names.iter().map(|name| push_text(&mut strings, name)).collect();
+
+        let mut values = Vec::new();
+        let handlers: Vec<[u32; 4]> = self.handlers.iter()
+            .map(|(method, value)| {
+                let [method_start, method_end] = push_text(&mut strings, method.as_str());
+                let value_start = values.len() as u32;
+                encode(value, &mut values);
+                [method_start, method_end, value_start, values.len() as u32]
+            })
+            .collect();
+
+        let patterns_start = self.paths.len() as u32;
+        let nodes: Vec<[u32; NODE_WORDS]> = self.nodes.iter()
+            .map(|node| {
+                let flags = if node.in_segment { IN_SEGMENT } else { 0 } | if node.has_children { HAS_CHILDREN } else { 0 };
+                [
+                    node.path.0, node.path.1,
+                    node.static_child.0, node.static_child.1,
+                    node.param_child, node.star_child,
+                    node.handlers.0, node.handlers.1,
+                    node.param_names.0, node.param_names.1,
+                    node.pattern.0 + patterns_start, node.pattern.1 + patterns_start,
+                    flags,
+                ]
+            })
+            .collect();
+
+        // Each table is given with its number of records, or its length for
+        // the tables of bytes.
+        let words = |records: &[u32]| records.iter().flat_map(|word| word.to_le_bytes()).collect::<Vec<u8>>();
+        let (strings_len, values_len) = (strings.len(), values.len());
+        let tables: [(Vec<u8>, usize); TABLES] = [
+            (words(nodes.as_flattened()), nodes.len()),
+            (words(&self.static_indices.iter().map(|&c| c as u32).collect::<Vec<_>>()), self.static_indices.len()),
+            (words(&self.static_child), self.static_child.len()),
+            (words(handlers.as_flattened()), handlers.len()),
+            (words(names.as_flattened()), names.len()),
+            (strings.into_bytes(), strings_len),
+            (values, values_len),
+        ];
+
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        let flags = if self.options.head_can_use_get { HEAD_CAN_USE_GET } else { 0 } | match self.options.dot_segments {
+            DotSegments::Keep => 0,
+            DotSegments::Normalize => NORMALIZE_DOT_SEGMENTS,
+            DotSegments::Reject => REJECT_DOT_SEGMENTS,
+        } | if self.options.merge_slashes { MERGE_SLASHES } else { 0 };
+        out.extend_from_slice(&flags.to_le_bytes());
+
+        let mut offset = HEADER_LEN;
+        for (bytes, len) in tables.iter() {
+            out.extend_from_slice(&(offset as u32).to_le_bytes());
+            out.extend_from_slice(&(*len as u32).to_le_bytes());
+            offset += bytes.len();
+        }
+        for (bytes, _) in tables.iter() {
+            out.extend_from_slice(bytes);
+        }
+
+        out
+    }
+}
+
+/// A read-only router which searches the routes in the bytes written by
+/// `CompiledRouter::freeze`, without copying them.
+///
+/// The bytes are typically a file memory-mapped by several processes, which
+/// share one routing table. Routes are matched exactly like the compiled
+/// router, and the value of a route is the bytes its value was encoded into.
+#[derive(Debug, Clone, Copy)]
+pub struct FrozenRouter<'a> {
+    bytes: &'a [u8],
+    /// The offset and the length of each table
+    tables: [(usize, usize); TABLES],
+    strings: &'a str,
+    values: &'a [u8],
+    head_can_use_get: bool,
+    merge_slashes: bool,
+    dot_segments: DotSegments,
+}
+
+impl<'a> FrozenRouter<'a> {
+    /// Checks the layout of the bytes, so that searches can't read out of
+    /// bounds or loop.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, SnapshotError> {
+        if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+            return Err(SnapshotError::InvalidFormat);
+        }
+        let word = |offset: usize| u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+        let version = word(4);
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version.min(u8::MAX as u32) as u8));
+        }
+
+        let record_words = [NODE_WORDS, 1, 1, HANDLER_WORDS, 2, 0, 0];
+        let mut tables = [(0, 0); TABLES];
+        for (i, table) in tables.iter_mut().enumerate() {
+            let (offset, len) = (word(12 + i * 8) as usize, word(16 + i * 8) as usize);
+            let size = if record_words[i] > 0 { len.checked_mul(record_words[i] * 4) } else { Some(len) };
+            if size.and_then(|size| size.checked_add(offset)).is_none_or(|end| end > bytes.len()) {
+                return Err(SnapshotError::InvalidFormat);
+            }
+            *table = (offset, len);
+        }
+
+        let (offset, len) = tables[STRINGS];
+        let strings = core::str::from_utf8(&bytes[offset..offset + len]).map_err(|_| SnapshotError::InvalidFormat)?;
+        let (offset, len) = tables[VALUES];
+        let router = Self {
+            bytes,
+            tables,
+            strings,
+            values: &bytes[offset..offset + len],
+            head_can_use_get: word(8) & HEAD_CAN_USE_GET != 0,
+            merge_slashes: word(8) & MERGE_SLASHES != 0,
+            dot_segments: match word(8) & (NORMALIZE_DOT_SEGMENTS | REJECT_DOT_SEGMENTS) {
+                0 => DotSegments::Keep,
+                NORMALIZE_DOT_SEGMENTS => DotSegments::Normalize,
+                REJECT_DOT_SEGMENTS => DotSegments::Reject,
+                _ => return Err(SnapshotError::InvalidFormat),
+            },
+        };
+
+        if router.is_valid() {
+            Ok(router)
+        } else {
+            Err(SnapshotError::InvalidFormat)
+        }
+    }
+
+    /// Returns Some(SearchResult), whose value is the bytes of the encoded
+    /// value, if successful. Otherwise returns None.
+    pub fn search(&self, method: Method, path: &str) -> Option<SearchResult<&'a [u8]>> {
+        let path = normalize(path, None, self.merge_slashes, self.dot_segments)?;
+        let path = path.strip_prefix('/').unwrap_or(&path);
+        let mut values = Vec::new();
+        let (node, handler) = self.find(0, &method, path, &mut values)?;
+
+        let (start, end) = self.range(NODES, node, NODE_NAMES, NODE_WORDS);
+        let params = (start..end)
+            .zip(values)
+            .map(|(name, value)| Param::new(self.name(name).to_string(), value.to_string()))
+            .collect();
+
+        let (start, end) = self.range(NODES, node, PATTERN, NODE_WORDS);
+        let pattern = self.strings[start as usize..end as usize].to_string();
+        let (start, end) = self.range(HANDLERS, handler, 2, HANDLER_WORDS);
+        Some(SearchResult::new(&self.values[start as usize..end as usize], params, pattern))
+    }
+
+    /// Returns the matched node and handler, pushing the parameter values
+    /// along the way, like `CompiledRouter::search`.
+    fn find<'p>(&self, index: u32, method: &Method, path: &'p str, values: &mut Vec<&'p str>) -> Option<(u32, u32)> {
+        let star_child = self.field(NODES, index, STAR_CHILD, NODE_WORDS);
+
+        if path.is_empty() {
+            if let Some(handler) = self.get_handler(index, method) {
+                return Some((index, handler));
+            }
+
+            // A `$path*` parameter also matches an empty path.
+            if star_child != NONE && self.node_path(star_child).ends_with('*') {
+                let handler = self.get_handler(star_child, method)?;
+                values.push(path);
+                return Some((star_child, handler));
+            }
+            return None;
+        }
+
+        // First see if this matches a static path
+        let first_char = path.chars().next().unwrap();
+        let (start, end) = self.range(NODES, index, STATIC, NODE_WORDS);
+        let position = partition_point(start, end, |i| self.field(STATIC_INDICES, i, 0, 1) < first_char as u32);
+        if position < end && self.field(STATIC_INDICES, position, 0, 1) == first_char as u32 {
+            let child = self.field(STATIC_CHILD, position, 0, 1);
+            if let Some(next_path) = path.strip_prefix(self.node_path(child)) {
+                if let Some(found) = self.find(child, method, next_path, values) {
+                    return Some(found);
+                }
+            }
+        }
+
+        // Didn't find a static path, so check for a path parameter.
+        let param_child = self.field(NODES, index, PARAM_CHILD, NODE_WORDS);
+        if param_child != NONE {
+            let in_segment = self.field(NODES, param_child, FLAGS, NODE_WORDS) & IN_SEGMENT != 0;
+            let segment_end = path.find('/').unwrap_or(path.len());
+            let ends = path[..segment_end].char_indices()
+                .skip(1)
+                .map(|(i, _)| i)
+                .filter(|_| in_segment)
+                .chain((segment_end > 0).then_some(segment_end));
+
+            for value_end in ends {
+                values.push(&path[..value_end]);
+                if let Some(found) = self.find(param_child, method, &path[value_end..], values) {
+                    return Some(found);
+                }
+                values.pop();
+            }
+        }
+
+        // Finally check for a wildcard *
+        if star_child != NONE {
+            // A wildcard in the middle of a pattern matches one or more
+            // segments, trying the fewest segments first.
+            if self.field(NODES, star_child, FLAGS, NODE_WORDS) & HAS_CHILDREN != 0 {
+                for (next_slash, _) in path.match_indices('/').filter(|(i, _)| *i > 0) {
+                    if let Some(found) = self.find(star_child, method, &path[next_slash..], values) {
+                        return Some(found);
+                    }
+                }
+            }
+
+            // A wildcard at the end of a pattern matches the rest of the path,
+            // which is the value of a multi-segment parameter.
+            let handler = self.get_handler(star_child, method)?;
+            if !self.node_path(star_child).is_empty() {
+                values.push(path);
+            }
+            return Some((star_child, handler));
+        }
+
+        None
+    }
+
+    /// Returns the index of the handler of the method, falling back like
+    /// `CompiledRouter::search`.
+    fn get_handler(&self, index: u32, method: &Method) -> Option<u32> {
+        let (start, end) = self.range(NODES, index, NODE_HANDLERS, NODE_WORDS);
+        let position = |method: &Method| {
+            (start..end).find(|&handler| {
+                let (name_start, name_end) = self.range(HANDLERS, handler, 0, HANDLER_WORDS);
+                &self.strings[name_start as usize..name_end as usize] == method.as_str()
+            })
+        };
+
+        position(method)
+            .or_else(|| {
+                if self.head_can_use_get && *method == Method::HEAD {
+                    position(&Method::GET)
+                } else {
+                    None
+                }
+            })
+            .or_else(|| position(&Method::ANY))
+    }
+
+    /// Returns true if every range is in bounds and on character boundaries,
+    /// and every child comes after its parent, so the nodes can't form a
+    /// cycle.
+    fn is_valid(&self) -> bool {
+        let nodes = self.tables[NODES].1 as u32;
+        let text = |table: usize, index: u32, field: usize, words: usize| {
+            let (start, end) = self.range(table, index, field, words);
+            start <= end && self.strings.get(start as usize..end as usize).is_some()
+        };
+        let within = |table: usize, (start, end): (u32, u32)| start <= end && end as usize <= self.tables[table].1;
+        let child = |parent: u32, child: u32| child > parent && child < nodes;
+
+        if nodes == 0 || self.tables[STATIC_INDICES].1 != self.tables[STATIC_CHILD].1 {
+            return false;
+        }
+        let names_valid = (0..self.tables[PARAM_NAMES].1 as u32).all(|name| text(PARAM_NAMES, name, 0, 2));
+        let handlers_valid = (0..self.tables[HANDLERS].1 as u32).all(|handler| {
+            let (start, end) = self.range(HANDLERS, handler, 2, HANDLER_WORDS);
+            text(HANDLERS, handler, 0, HANDLER_WORDS) && start <= end && end as usize <= self.values.len()
+        });
+
+        names_valid && handlers_valid && (0..nodes).all(|index| {
+            let statics = self.range(NODES, index, STATIC, NODE_WORDS);
+            let mut previous = None;
+            let statics_valid = within(STATIC_CHILD, statics) && (statics.0..statics.1).all(|i| {
+                let static_child = self.field(STATIC_CHILD, i, 0, 1);
+                let first_char = self.field(STATIC_INDICES, i, 0, 1);
+                let sorted = previous.replace(first_char).is_none_or(|previous| previous < first_char);
+                sorted && child(index, static_child)
+                    && text(NODES, static_child, PATH, NODE_WORDS)
+                    && self.node_path(static_child).chars().next().map(|c| c as u32) == Some(first_char)
+            });
+
+            let children_valid = [PARAM_CHILD, STAR_CHILD].iter()
+                .map(|&field| self.field(NODES, index, field, NODE_WORDS))
+                .all(|other| other == NONE || child(index, other));
+
+            text(NODES, index, PATH, NODE_WORDS) && text(NODES, index, PATTERN, NODE_WORDS)
+                && statics_valid && children_valid
+                && within(HANDLERS, self.range(NODES, index, NODE_HANDLERS, NODE_WORDS))
+                && within(PARAM_NAMES, self.range(NODES, index, NODE_NAMES, NODE_WORDS))
+        })
+    }
+
+    /// Returns a word of a record of the table.
+    fn field(&self, table: usize, index: u32, field: usize, words: usize) -> u32 {
+        let offset = self.tables[table].0 + (index as usize * words + field) * 4;
+        let bytes = &self.bytes[offset..offset + 4];
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    /// Returns the range starting at a word of a record of the table.
+    fn range(&self, table: usize, index: u32, field: usize, words: usize) -> (u32, u32) {
+        (self.field(table, index, field, words), self.field(table, index, field + 1, words))
+    }
+
+    fn node_path(&self, index: u32) -> &'a str {
+        let (start, end) = self.range(NODES, index, PATH, NODE_WORDS);
+        &self.strings[start as usize..end as usize]
+    }
+
+    fn name(&self, index: u32) -> &'a str {
+        let (start, end) = self.range(PARAM_NAMES, index, 0, 2);
+        &self.strings[start as usize..end as usize]
+    }
+}
+
+/// Appends the text to the strings, returning its range.
+fn push_text(strings: &mut String, text: &str) -> [u32; 2] {
+    let start = strings.len() as u32;
+    strings.push_str(text);
+    [start, strings.len() as u32]
+}
+
+/// Returns the first index of `start..end` for which `pred` is false, like
+/// `slice::partition_point`.
+fn partition_point<F: Fn(u32) -> bool>(mut start: u32, mut end: u32, pred: F) -> u32 {
+    while start < end {
+        let middle = start + (end - start) / 2;
+        if pred(middle) {
+            start = middle + 1;
+        } else {
+            end = middle;
+        }
+    }
+
+    start
+}
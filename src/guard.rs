@@ -0,0 +1,73 @@
+use core::fmt;
+use alloc::sync::Arc;
+use crate::extensions::Extensions;
+use crate::method::Method;
+use crate::tree::Params;
+
+/// The request a guard is evaluated for, with the route it matched.
+#[derive(Debug, Clone, Copy)]
+pub struct MatchContext<'a> {
+    method: &'a Method,
+    path: &'a str,
+    pattern: &'a str,
+    params: &'a Params,
+    request: &'a Extensions,
+}
+
+impl<'a> MatchContext<'a> {
+    pub(crate) fn new(method: &'a Method, path: &'a str, pattern: &'a str, params: &'a Params, request: &'a Extensions) -> Self {
+        Self { method, path, pattern, params, request }
+    }
+
+    /// Returns the method of the request.
+    pub fn method(&self) -> &'a Method {
+        self.method
+    }
+
+    /// Returns the path being matched.
+    pub fn path(&self) -> &'a str {
+        self.path
+    }
+
+    /// Returns the pattern of the route the path matched.
+    pub fn pattern(&self) -> &'a str {
+        self.pattern
+    }
+
+    /// Returns the parameters of the route.
+    pub fn params(&self) -> &'a Params {
+        self.params
+    }
+
+    /// Returns the request data passed to `Router::search_with_context`, such
+    /// as the headers or the host of the request, which are empty for a plain
+    /// search.
+    pub fn request(&self) -> &'a Extensions {
+        self.request
+    }
+}
+
+/// A predicate set on a route with `Router::set_guard`, which is evaluated
+/// when the route matches a path. When it returns false, matching goes on
+/// with the next candidate, such as a parameter, a wildcard or a fallback.
+///
+/// The guard of a route is kept in its extensions.
+#[derive(Clone)]
+pub struct Guard(Arc<dyn Fn(&MatchContext) -> bool + Send + Sync>);
+
+impl Guard {
+    pub(crate) fn new<F: Fn(&MatchContext) -> bool + Send + Sync + 'static>(f: F) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Evaluates the guard.
+    pub fn check(&self, context: &MatchContext) -> bool {
+        (self.0)(context)
+    }
+}
+
+impl fmt::Debug for Guard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Guard")
+    }
+}
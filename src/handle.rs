@@ -0,0 +1,141 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering::SeqCst};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::vec::Vec;
+use crate::router::Router;
+
+/// A shared handle to a router, which can be replaced while it is used.
+///
+/// Readers `load` the current router as an immutable snapshot and search it
+/// without holding any lock, while a writer builds a new router and `store`s
+/// it. Searches in progress keep using the snapshot they loaded, and
+/// `update` changes a copy of the router, so searches see either the old or
+/// the new routes.
+///
+/// The `Arc` of the router is swapped atomically like `arc-swap` does, so
+/// `load` never takes a lock nor waits for a writer. Writers are serialized
+/// by a lock, which loads don't take.
+#[derive(Debug)]
+pub struct RouterHandle<V> {
+    current: ArcCell<Router<V>>,
+}
+
+impl<V> RouterHandle<V> {
+    pub fn new(router: Router<V>) -> Self {
+        Self {
+            current: ArcCell::new(Arc::new(router)),
+        }
+    }
+
+    /// Returns a snapshot of the current router.
+    pub fn load(&self) -> Arc<Router<V>> {
+        self.current.load()
+    }
+
+    /// Replaces the current router.
+    pub fn store(&self, router: Router<V>) {
+        self.swap(router);
+    }
+
+    /// Replaces the current router, returning the previous one.
+    pub fn swap(&self, router: Router<V>) -> Arc<Router<V>> {
+        let mut writer = self.current.writer();
+        self.current.replace(&mut writer, Arc::new(router))
+    }
+}
+
+impl<V: Clone> RouterHandle<V> {
+    /// Replaces the current router with a changed copy of it. Other writers
+    /// wait while `f` runs, so concurrent updates don't overwrite each other,
+    /// and readers keep loading the previous router until it returns.
+    ///
+    /// Returns the result of `f`. When `f` panics, the router isn't replaced.
+    pub fn update<R, F: FnOnce(&mut Router<V>) -> R>(&self, f: F) -> R {
+        let mut writer = self.current.writer();
+        let mut router = Router::clone(&self.load());
+        let result = f(&mut router);
+        self.current.replace(&mut writer, Arc::new(router));
+        result
+    }
+}
+
+impl<V> From<Router<V>> for RouterHandle<V> {
+    fn from(router: Router<V>) -> Self {
+        Self::new(router)
+    }
+}
+
+/// An `Arc` which is loaded without locking and replaced atomically.
+///
+/// A load counts itself in `readers` while it clones the current `Arc`. A
+/// replaced `Arc` is retired rather than released, since a load which read
+/// the old pointer may not have cloned it yet, and the retired ones are
+/// released by the next writer which sees no load in progress.
+pub(crate) struct ArcCell<T> {
+    /// The pointer of an `Arc` owned by the cell
+    current: AtomicPtr<T>,
+    /// The number of loads in progress
+    readers: AtomicUsize,
+    /// The replaced values, whose lock also serializes the writers
+    retired: Mutex<Vec<Arc<T>>>,
+    _owned: PhantomData<Arc<T>>,
+}
+
+impl<T> ArcCell<T> {
+    pub(crate) fn new(value: Arc<T>) -> Self {
+        Self {
+            current: AtomicPtr::new(Arc::into_raw(value).cast_mut()),
+            readers: AtomicUsize::new(0),
+            retired: Mutex::new(Vec::new()),
+            _owned: PhantomData,
+        }
+    }
+
+    /// Returns a clone of the current `Arc`.
+    pub(crate) fn load(&self) -> Arc<T> {
+        self.readers.fetch_add(1, SeqCst);
+        let current = self.current.load(SeqCst);
+        // SAFETY: the pointer comes from `Arc::into_raw`, and its value isn't
+        // released while the load is counted in `readers`.
+        let value = unsafe {
+            Arc::increment_strong_count(current);
+            Arc::from_raw(current)
+        };
+        self.readers.fetch_sub(1, SeqCst);
+        value
+    }
+
+    /// Locks the cell for a writer.
+    pub(crate) fn writer(&self) -> MutexGuard<'_, Vec<Arc<T>>> {
+        self.retired.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Replaces the current `Arc`, returning the previous one.
+    pub(crate) fn replace(&self, retired: &mut Vec<Arc<T>>, value: Arc<T>) -> Arc<T> {
+        let previous = self.current.swap(Arc::into_raw(value).cast_mut(), SeqCst);
+        // SAFETY: the cell owned the previous pointer, which it gives up.
+        let previous = unsafe { Arc::from_raw(previous) };
+        retired.push(previous.clone());
+
+        // The loads starting from now read the new pointer, so once none is
+        // in progress, no load can still be cloning a retired one.
+        if self.readers.load(SeqCst) == 0 {
+            retired.clear();
+        }
+        previous
+    }
+}
+
+impl<T> Drop for ArcCell<T> {
+    fn drop(&mut self) {
+        // SAFETY: the cell owns the pointer, and no load can be in progress.
+        drop(unsafe { Arc::from_raw(*self.current.get_mut()) });
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArcCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.load(), f)
+    }
+}
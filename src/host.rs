@@ -0,0 +1,176 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::error::{ConflictKind, PatternError};
+use crate::method::Method;
+use crate::path_tree::{decode, encode};
+use crate::pattern::{RoutePattern, Syntax};
+use crate::router::Router;
+use crate::tree::{Node, Param, Params, SearchOptions, SearchResult};
+
+/// A router which matches the host of a request before its path, such as
+/// `api.example.com`, and then searches the router of the host.
+///
+/// Host patterns are matched label by label, like a `PathTree` separated by
+/// `.`: `$tenant.example.com` captures a label as a parameter, and
+/// `*.example.com` matches one or more labels. The parameters of the host
+/// are returned with the parameters of the path. Hosts are matched without
+/// their port and case insensitively. A path missing in the router of the
+/// most specific host pattern is searched in the routers of the other
+/// matching patterns, and then in the router of `any_host`.
+#[derive(Debug, Clone)]
+pub struct HostRouter<V> {
+    /// The index of the router of each host pattern, whose labels are
+    /// encoded as the segments of a path
+    hosts: Node<usize>,
+    /// The host patterns and their routers
+    routers: Vec<(String, Router<V>)>,
+    any_host: Router<V>,
+}
+
+impl<V: Clone> HostRouter<V> {
+    pub fn new() -> Self {
+        Self {
+            hosts: Node::new(),
+            routers: Vec::new(),
+            any_host: Router::new(),
+        }
+    }
+
+    /// Returns the router of the host pattern, adding an empty router if the
+    /// pattern is new. It panics if the pattern is invalid or conflicts with
+    /// another one, like `Node::insert`.
+    pub fn host(&mut self, pattern: &str) -> &mut Router<V> {
+        let index = self.host_index(&pattern.to_ascii_lowercase()).unwrap_or_else(|error| panic!("{}", error));
+        &mut self.routers[index].1
+    }
+
+    /// Returns the index of the router of the lowercase host pattern, adding
+    /// an empty router if the pattern is new and doesn't conflict.
+    fn host_index(&mut self, pattern: &str) -> Result<usize, ConflictKind> {
+        if let Some(index) = self.routers.iter().position(|(host, _)| host == pattern) {
+            return Ok(index);
+        }
+
+        let encoded = encode_host(pattern);
+        if let Some(conflict) = self.hosts.would_conflict(Method::ANY, &encoded) {
+            return Err(conflict);
+        }
+
+        self.hosts.insert(Method::ANY, &encoded, self.routers.len());
+        self.routers.push((pattern.into(), Router::new()));
+        Ok(self.routers.len() - 1)
+    }
+
+    /// Inserts a value for a pattern of a host followed by a path, such as
+    /// `$tenant.example.com/dashboard`, whose host parameters are returned
+    /// with the path parameters. A pattern starting with `/` is inserted for
+    /// any host.
+    ///
+    /// The path is in the syntax of the router of the host. An invalid host
+    /// or path, or a host and a path with a parameter of the same name, is
+    /// returned as `ConflictKind::InvalidPattern`, and a host conflicting
+    /// with another host pattern as its conflict, before anything is added.
+    /// Conflicts of the path are handled by the router of the host.
+    pub fn insert(&mut self, method: Method, pattern: &str, value: V) -> Result<(), ConflictKind> {
+        let (host, path) = pattern.split_at(pattern.find('/').unwrap_or(pattern.len()));
+        let path = if path.is_empty() { "/" } else { path };
+        if host.is_empty() {
+            return self.any_host.insert(method, path, value);
+        }
+
+        let host = host.to_ascii_lowercase();
+        let encoded = encode_host(&host);
+        let host_pattern = RoutePattern::parse(&encoded).map_err(ConflictKind::InvalidPattern)?;
+        let syntax = self.routers.iter().find(|(pattern, _)| *pattern == host).map_or_else(Syntax::default, |(_, router)| router.syntax());
+        let native = syntax.translate(path).map_err(ConflictKind::InvalidPattern)?;
+        let path_pattern = RoutePattern::parse(&native).map_err(ConflictKind::InvalidPattern)?;
+        let host_names = host_pattern.param_names();
+        if let Some(name) = path_pattern.param_names().into_iter().find(|name| host_names.contains(name)) {
+            let message = format!("The host and the path of {:?} both have a parameter {:?}.", pattern, name);
+            return Err(ConflictKind::InvalidPattern(PatternError::new(message)));
+        }
+
+        let added = !self.routers.iter().any(|(pattern, _)| *pattern == host);
+        let index = self.host_index(&host)?;
+        let inserted = self.routers[index].1.insert(method, path, value);
+        if inserted.is_err() && added {
+            self.remove_host(&host);
+        }
+        inserted
+    }
+
+    /// Returns the router of the hosts which match no pattern.
+    pub fn any_host(&mut self) -> &mut Router<V> {
+        &mut self.any_host
+    }
+
+    /// Removes the host pattern, returning its router if any.
+    pub fn remove_host(&mut self, pattern: &str) -> Option<Router<V>> {
+        let pattern = pattern.to_ascii_lowercase();
+        let index = self.routers.iter().position(|(host, _)| *host == pattern)?;
+        self.hosts.remove(&encode_host(&pattern));
+
+        let (_, router) = self.routers.swap_remove(index);
+        if let Some((moved, _)) = self.routers.get(index) {
+            self.hosts.insert_replace(Method::ANY, &encode_host(moved), index);
+        }
+        Some(router)
+    }
+
+    /// Returns the host patterns, in the order they were added.
+    pub fn hosts(&self) -> impl Iterator<Item = &str> {
+        self.routers.iter().map(|(host, _)| host.as_str())
+    }
+
+    /// Searches the path in the routers of the host patterns matching the
+    /// host, from the most specific pattern to the least, and then in the
+    /// router of `any_host`. The parameters of the host pattern come before
+    /// the parameters of the path.
+    pub fn search(&self, host: &str, method: Method, path: &str) -> Option<SearchResult<V>> {
+        let host = encode_host(&normalize_host(host));
+        let options = SearchOptions::default();
+        let mut missed: Vec<String> = Vec::new();
+        loop {
+            let accept = |pattern: &str, _: &Method, _: &Params| !missed.iter().any(|missed| missed == pattern);
+            let Some(found) = self.hosts.search_filtered(Method::ANY, &host, &options, &accept) else {
+                return self.any_host.search(method, path);
+            };
+
+            let host_pattern = String::from(found.pattern());
+            let (index, host_params) = found.into_parts();
+            if let Some(result) = self.routers[index].1.search(method.clone(), path) {
+                let host_params = host_params.into_iter()
+                    .map(|(name, value)| Param::new(name, decode(".", &value)))
+                    .collect::<Vec<_>>();
+                return Some(result.prepend_params(host_params.into()));
+            }
+            missed.push(host_pattern);
+        }
+    }
+}
+
+impl<V: Clone> Default for HostRouter<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rewrites a host or host pattern into a path whose segments are its
+/// labels, as a `PathTree` separated by `.` does.
+fn encode_host(host: &str) -> String {
+    let mut path = String::from("/");
+    path.push_str(&encode(Some("."), host));
+    path
+}
+
+/// Returns the host without its port, in lowercase. The brackets of an IPv6
+/// address are kept.
+fn normalize_host(host: &str) -> String {
+    let host = match host.strip_prefix('[') {
+        Some(rest) => rest.find(']').map_or(host, |end| &host[..end + 2]),
+        None => host.split(':').next().unwrap_or(host),
+    };
+
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
@@ -1,5 +1,89 @@
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 mod tree;
+mod map;
 mod method;
+mod normalize;
+mod router;
+mod error;
+mod pattern;
+mod limits;
+mod macros;
+mod compiled;
+mod constraint;
+mod frozen;
+mod explain;
+mod host;
+mod extensions;
+mod guard;
+#[cfg(feature = "std")]
+mod cache;
+#[cfg(feature = "std")]
+mod handle;
+#[cfg(feature = "std")]
+mod sync;
+mod persistent;
+mod query;
+mod redirect;
+mod path_tree;
+mod scope;
+mod shadow;
+mod tenant;
+mod snapshot;
+mod transaction;
+mod weighted;
+#[cfg(feature = "tower")]
+mod service;
+#[cfg(feature = "serde")]
+mod de;
+#[cfg(feature = "openapi")]
+pub mod openapi;
+#[cfg(feature = "config")]
+mod config;
+pub mod codegen;
+
+#[cfg(doctest)]
+#[doc = include_str!("../README.md")]
+struct ReadmeDoctests;
 
-pub use tree::Node;
-pub use method::Method;
+pub use tree::{Node, ConflictPolicy, Entry, OccupiedEntry, VacantEntry, MemoryStats, Resolution, RouteChange, SearchResult, Param, Params};
+pub use map::RadixMap;
+pub use method::{Discriminator, Method, MethodSet};
+pub use normalize::DotSegments;
+pub use router::{Metrics, Router, RouteEvent};
+pub use compiled::CompiledRouter;
+pub use limits::Limits;
+pub use frozen::FrozenRouter;
+pub use explain::{Explanation, Step};
+pub use host::HostRouter;
+pub use extensions::Extensions;
+pub use guard::{Guard, MatchContext};
+#[cfg(feature = "std")]
+pub use handle::RouterHandle;
+#[cfg(feature = "std")]
+pub use sync::SyncRouter;
+pub use persistent::PersistentNode;
+pub use query::Query;
+pub use redirect::{Redirect, RedirectKind, Target};
+pub use path_tree::PathTree;
+pub use scope::Scope;
+pub use shadow::{ShadowedRoute, Shadowing};
+pub use tenant::TenantRouter;
+pub use transaction::Transaction;
+pub use weighted::Weighted;
+pub use pattern::{RoutePattern, Segment, Syntax};
+#[doc(hidden)]
+pub use macros::__assert_unique_routes;
+pub use error::{ConflictKind, DumpError, InvalidMethod, LimitError, ParamError, PathForError, PatternError, RequestLineError, SnapshotError, TransactionError, UpdateError, Violation};
+#[cfg(feature = "tower")]
+pub use service::RouterService;
+#[cfg(feature = "openapi")]
+pub use error::OpenApiError;
+#[cfg(feature = "config")]
+pub use config::ConfigFormat;
+#[cfg(feature = "config")]
+pub use error::ConfigError;
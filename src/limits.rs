@@ -0,0 +1,75 @@
+use crate::error::LimitError;
+
+/// The limits of a router on its patterns and on the searched paths, which
+/// protect a server from pathological patterns and abusive request paths.
+/// Every limit is unbounded by default.
+///
+/// Patterns exceeding a limit are rejected with `ConflictKind::LimitExceeded`,
+/// and paths exceeding a limit match nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    max_path_len: usize,
+    max_segments: usize,
+    max_depth: usize,
+}
+
+impl Limits {
+    pub fn new() -> Self {
+        Self {
+            max_path_len: usize::MAX,
+            max_segments: usize::MAX,
+            max_depth: usize::MAX,
+        }
+    }
+
+    /// Sets the maximum length in bytes of a pattern or a path.
+    pub fn max_path_len(mut self, max: usize) -> Self {
+        self.max_path_len = max;
+        self
+    }
+
+    /// Sets the maximum number of segments of a pattern or a path, which is
+    /// its number of `/`.
+    pub fn max_segments(mut self, max: usize) -> Self {
+        self.max_segments = max;
+        self
+    }
+
+    /// Sets the maximum depth of the tree, as returned by `Node::depth`,
+    /// which bounds how deep a search recurses. Checking it walks the tree on
+    /// every insert of a new pattern.
+    pub fn max_depth(mut self, max: usize) -> Self {
+        self.max_depth = max;
+        self
+    }
+
+    /// Checks the length and the segments of a pattern or a path.
+    pub(crate) fn check_path(&self, path: &str) -> Result<(), LimitError> {
+        if path.len() > self.max_path_len {
+            return Err(LimitError::PathLength(self.max_path_len));
+        }
+        if path.len() > self.max_segments && path.bytes().filter(|&b| b == b'/').count() > self.max_segments {
+            return Err(LimitError::Segments(self.max_segments));
+        }
+
+        Ok(())
+    }
+
+    /// Checks the depth of a tree.
+    pub(crate) fn check_depth(&self, depth: usize) -> Result<(), LimitError> {
+        match depth > self.max_depth {
+            true => Err(LimitError::Depth(self.max_depth)),
+            false => Ok(()),
+        }
+    }
+
+    pub(crate) fn has_max_depth(&self) -> bool {
+        self.max_depth != usize::MAX
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
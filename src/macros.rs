@@ -0,0 +1,78 @@
+/// Builds a `Node` from a table of routes.
+///
+/// Each route is a method constant of `Method`, a pattern literal and a value.
+/// Routes registered twice for the same method and pattern fail to compile:
+///
+/// ```compile_fail
+/// let tree = radixtree::routes![
+///     GET "/users" => 1,
+///     GET "users" => 2,
+/// ];
+/// ```
+///
+/// Only the text of the patterns is compared at compile time, ignoring the
+/// start slash. Other conflicts, such as patterns which only differ in the
+/// names of their parameters or wildcards shadowing each other, panic when
+/// the tree is built, like `Node::insert`.
+#[macro_export]
+macro_rules! routes {
+    ($($method:ident $path:literal => $value:expr),* $(,)?) => {{
+        const _: () = $crate::__assert_unique_routes(&[$((stringify!($method), $path)),*]);
+
+        // The values are collected in an array, so that functions are coerced
+        // to the same function pointer type.
+        let methods = [$($crate::Method::$method),*];
+        let paths = [$($path),*];
+        let values = [$($value),*];
+
+        let mut tree = $crate::Node::new();
+        for ((method, path), value) in methods.into_iter().zip(paths).zip(values) {
+            tree.insert(method, path, value);
+        }
+        tree
+    }};
+}
+
+/// Panics at compile time when a method and pattern appear twice in `routes`.
+#[doc(hidden)]
+pub const fn __assert_unique_routes(routes: &[(&str, &str)]) {
+    let mut i = 0;
+    while i < routes.len() {
+        let mut j = i + 1;
+        while j < routes.len() {
+            if str_eq(routes[i].0, routes[j].0) && pattern_eq(routes[i].1, routes[j].1) {
+                panic!("A method of a path only appear once.");
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    bytes_eq(a.as_bytes(), b.as_bytes())
+}
+
+/// Compares two patterns, ignoring the start slash.
+const fn pattern_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    match (a, b) {
+        ([b'/', a @ ..], [b'/', b @ ..]) | ([b'/', a @ ..], b) | (a, [b'/', b @ ..]) => bytes_eq(a, b),
+        _ => bytes_eq(a, b),
+    }
+}
+
+const fn bytes_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
@@ -0,0 +1,282 @@
+use alloc::{boxed::Box, vec, vec::Vec};
+
+/// The index of the root node in the arena.
+const ROOT: usize = 0;
+
+/// A compressed prefix tree keyed by bytes, without any routing semantics.
+///
+/// Unlike `Node`, keys are compared byte by byte: `$`, `*` and `/` have no
+/// special meaning and there are no methods. Like `Node`, the nodes live in
+/// an arena and refer to their children by index, but the two trees are
+/// separate: routers don't use a `RadixMap`.
+#[derive(Debug, Clone)]
+pub struct RadixMap<V> {
+    nodes: Vec<MapNode<V>>,
+    /// The indices of the removed nodes, which are reused by new nodes
+    free: Vec<usize>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct MapNode<V> {
+    /// The key fragment of the node
+    key: Box<[u8]>,
+    /// The first bytes of the children, sorted for binary search
+    indices: Vec<u8>,
+    children: Vec<usize>,
+    value: Option<V>,
+}
+
+impl<V> RadixMap<V> {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![MapNode::default()],
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the map has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a value, returning the replaced value, if any.
+    pub fn insert(&mut self, key: &[u8], value: V) -> Option<V> {
+        let mut id = ROOT;
+        let mut key = key;
+
+        while let Some(&first) = key.first() {
+            let i = match self.nodes[id].indices.binary_search(&first) {
+                Ok(i) => i,
+                Err(i) => {
+                    // No child starts with the byte, so the rest of the key is a new leaf.
+                    let child = self.alloc(MapNode {
+                        key: key.into(),
+                        value: Some(value),
+                        ..Default::default()
+                    });
+                    self.nodes[id].indices.insert(i, first);
+                    self.nodes[id].children.insert(i, child);
+                    self.len += 1;
+                    return None;
+                }
+            };
+
+            let child = self.split_child(id, i, key);
+            key = &key[self.nodes[child].key.len()..];
+            id = child;
+        }
+
+        let replaced = self.nodes[id].value.replace(value);
+        if replaced.is_none() {
+            self.len += 1;
+        }
+        replaced
+    }
+
+    /// Returns the value of the key.
+    pub fn get(&self, key: &[u8]) -> Option<&V> {
+        self.find(key).and_then(|id| self.nodes[id].value.as_ref())
+    }
+
+    /// Returns a mutable reference to the value of the key.
+    pub fn get_mut(&mut self, key: &[u8]) -> Option<&mut V> {
+        let id = self.find(key)?;
+        self.nodes[id].value.as_mut()
+    }
+
+    /// Returns true if the map has a value for the key.
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes the key, merging the nodes left with a single child.
+    ///
+    /// Returns the removed value, if any.
+    pub fn remove(&mut self, key: &[u8]) -> Option<V> {
+        let removed = self.remove_key(ROOT, key);
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Returns the longest key of the map which is a prefix of `key`, with
+    /// its value.
+    pub fn longest_prefix<'k>(&self, key: &'k [u8]) -> Option<(&'k [u8], &V)> {
+        let mut id = ROOT;
+        let mut depth = 0;
+        let mut found = self.nodes[ROOT].value.as_ref().map(|value| (0, value));
+
+        while let Some(&first) = key.get(depth) {
+            let node = &self.nodes[id];
+            let Ok(i) = node.indices.binary_search(&first) else {
+                break;
+            };
+
+            let child = node.children[i];
+            if !key[depth..].starts_with(&self.nodes[child].key) {
+                break;
+            }
+
+            depth += self.nodes[child].key.len();
+            id = child;
+            if let Some(ref value) = self.nodes[id].value {
+                found = Some((depth, value));
+            }
+        }
+
+        found.map(|(len, value)| (&key[..len], value))
+    }
+
+    /// Returns every key and its value, in the byte order of the keys.
+    pub fn iter(&self) -> impl Iterator<Item = (Vec<u8>, &V)> {
+        self.into_iter()
+    }
+
+    /// Returns every key, in byte order.
+    pub fn keys(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Removes every key.
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Returns the node of the key, which may have no value.
+    fn find(&self, key: &[u8]) -> Option<usize> {
+        let mut id = ROOT;
+        let mut key = key;
+
+        while let Some(&first) = key.first() {
+            let node = &self.nodes[id];
+            let i = node.indices.binary_search(&first).ok()?;
+            let child = node.children[i];
+            key = key.strip_prefix(&*self.nodes[child].key)?;
+            id = child;
+        }
+
+        Some(id)
+    }
+
+    /// Pushes the entries of the node and its children, whose keys start with
+    /// `key`.
+    fn walk<'a>(&'a self, id: usize, key: &mut Vec<u8>, entries: &mut Vec<(Vec<u8>, &'a V)>) {
+        let node = &self.nodes[id];
+        if let Some(ref value) = node.value {
+            entries.push((key.clone(), value));
+        }
+
+        for &child in node.children.iter() {
+            let len = key.len();
+            key.extend_from_slice(&self.nodes[child].key);
+            self.walk(child, key, entries);
+            key.truncate(len);
+        }
+    }
+
+    /// Splits the child at the common prefix of its key and `key` when the
+    /// child isn't a prefix of `key`, returning the child to follow.
+    fn split_child(&mut self, id: usize, i: usize, key: &[u8]) -> usize {
+        let child = self.nodes[id].children[i];
+        let child_key = &self.nodes[child].key;
+        let len = child_key.iter().zip(key).take_while(|(l, r)| l == r).count();
+        if len == child_key.len() {
+            return child;
+        }
+
+        // The new node replaces the child, which becomes its only child.
+        let rest: Box<[u8]> = child_key[len..].into();
+        let node = self.alloc(MapNode {
+            key: key[..len].into(),
+            indices: vec![rest[0]],
+            children: vec![child],
+            value: None,
+        });
+        self.nodes[child].key = rest;
+        self.nodes[id].children[i] = node;
+        node
+    }
+
+    fn remove_key(&mut self, id: usize, key: &[u8]) -> Option<V> {
+        let Some(&first) = key.first() else {
+            return self.nodes[id].value.take();
+        };
+
+        let node = &self.nodes[id];
+        let i = node.indices.binary_search(&first).ok()?;
+        let child = node.children[i];
+        let rest = key.strip_prefix(&*self.nodes[child].key)?;
+        let removed = self.remove_key(child, rest)?;
+
+        let child_node = &self.nodes[child];
+        if child_node.value.is_none() && child_node.children.is_empty() { // Remove child node
+            self.nodes[id].indices.remove(i);
+            self.nodes[id].children.remove(i);
+            self.dealloc(child);
+        } else if child_node.value.is_none() && child_node.children.len() == 1 { // Merge nodes
+            let grandchild = child_node.children[0];
+            let merged = [&*child_node.key, &*self.nodes[grandchild].key].concat();
+            self.nodes[grandchild].key = merged.into();
+            self.nodes[id].children[i] = grandchild;
+            self.dealloc(child);
+        }
+
+        Some(removed)
+    }
+
+    /// Adds the node to the arena, reusing the slot of a removed node if any.
+    fn alloc(&mut self, node: MapNode<V>) -> usize {
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = node;
+                id
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Releases the slot of a removed node.
+    fn dealloc(&mut self, id: usize) {
+        self.nodes[id] = MapNode::default();
+        self.free.push(id);
+    }
+}
+
+impl<'a, V> IntoIterator for &'a RadixMap<V> {
+    type Item = (Vec<u8>, &'a V);
+    type IntoIter = vec::IntoIter<(Vec<u8>, &'a V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut entries = Vec::with_capacity(self.len);
+        self.walk(ROOT, &mut Vec::new(), &mut entries);
+        entries.into_iter()
+    }
+}
+
+impl<V> Default for RadixMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Default for MapNode<V> {
+    fn default() -> Self {
+        Self {
+            key: Box::default(),
+            indices: Vec::new(),
+            children: Vec::new(),
+            value: None,
+        }
+    }
+}
@@ -1,3 +1,10 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::Hash;
+use core::ops::{BitOr, BitOrAssign};
+use core::str::FromStr;
+use alloc::{boxed::Box, vec::{self, Vec}};
+use crate::error::InvalidMethod;
 use self::Inner::*;
 
 /// The Request Method
@@ -15,6 +22,16 @@ enum Inner {
     Options,
     Connect,
     Trace,
+    Propfind,
+    Proppatch,
+    Mkcol,
+    Copy,
+    Move,
+    Lock,
+    Unlock,
+    Any,
+    /// A method that isn't one of the standard methods
+    Extension(Box<str>),
 }
 
 impl Method {
@@ -45,6 +62,30 @@ impl Method {
     /// TRACE
     pub const TRACE: Method = Method(Trace);
 
+    /// PROPFIND (WebDAV)
+    pub const PROPFIND: Method = Method(Propfind);
+
+    /// PROPPATCH (WebDAV)
+    pub const PROPPATCH: Method = Method(Proppatch);
+
+    /// MKCOL (WebDAV)
+    pub const MKCOL: Method = Method(Mkcol);
+
+    /// COPY (WebDAV)
+    pub const COPY: Method = Method(Copy);
+
+    /// MOVE (WebDAV)
+    pub const MOVE: Method = Method(Move);
+
+    /// LOCK (WebDAV)
+    pub const LOCK: Method = Method(Lock);
+
+    /// UNLOCK (WebDAV)
+    pub const UNLOCK: Method = Method(Unlock);
+
+    /// Matches every method that has no handler of its own
+    pub const ANY: Method = Method(Any);
+
     /// Return a &str representation of the HTTP method
     pub fn as_str(&self) -> &str {
         match self.0 {
@@ -57,7 +98,97 @@ impl Method {
             Options => "OPTIONS",
             Connect => "CONNECT",
             Trace => "TRACE",
+            Propfind => "PROPFIND",
+            Proppatch => "PROPPATCH",
+            Mkcol => "MKCOL",
+            Copy => "COPY",
+            Move => "MOVE",
+            Lock => "LOCK",
+            Unlock => "UNLOCK",
+            Any => "ANY",
+            Extension(ref s) => s,
+        }
+    }
+
+    /// Creates a method that isn't one of the standard methods, such as `PURGE`.
+    ///
+    /// The name must be a valid HTTP token. Standard method names return the
+    /// standard method.
+    pub fn extension(name: &str) -> Result<Method, InvalidMethod> {
+        if let Some(method) = Method::standard(name) {
+            return Ok(method);
+        }
+
+        let is_token = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c);
+        if name.is_empty() || !name.chars().all(is_token) {
+            return Err(InvalidMethod::new());
         }
+
+        Ok(Method(Extension(name.into())))
+    }
+
+    /// Parses a method as printed by `as_str`, including `ANY` and the
+    /// extension methods.
+    pub(crate) fn from_name(name: &str) -> Result<Method, InvalidMethod> {
+        match name {
+            "ANY" => Ok(Method::ANY),
+            name => Method::extension(name),
+        }
+    }
+
+    /// Returns the position of the method among the standard methods and ANY.
+    pub(crate) fn index(&self) -> Option<usize> {
+        let index = match self.0 {
+            Get => 0,
+            Post => 1,
+            Head => 2,
+            Put => 3,
+            Patch => 4,
+            Delete => 5,
+            Options => 6,
+            Connect => 7,
+            Trace => 8,
+            Propfind => 9,
+            Proppatch => 10,
+            Mkcol => 11,
+            Copy => 12,
+            Move => 13,
+            Lock => 14,
+            Unlock => 15,
+            Any => 16,
+            Extension(_) => return None,
+        };
+
+        Some(index)
+    }
+
+    /// Returns the standard method with exactly this name.
+    fn standard(name: &str) -> Option<Method> {
+        match name {
+            "GET" => Some(Method::GET),
+            "POST" => Some(Method::POST),
+            "HEAD" => Some(Method::HEAD),
+            "PUT" => Some(Method::PUT),
+            "PATCH" => Some(Method::PATCH),
+            "DELETE" => Some(Method::DELETE),
+            "OPTIONS" => Some(Method::OPTIONS),
+            "CONNECT" => Some(Method::CONNECT),
+            "TRACE" => Some(Method::TRACE),
+            "PROPFIND" => Some(Method::PROPFIND),
+            "PROPPATCH" => Some(Method::PROPPATCH),
+            "MKCOL" => Some(Method::MKCOL),
+            "COPY" => Some(Method::COPY),
+            "MOVE" => Some(Method::MOVE),
+            "LOCK" => Some(Method::LOCK),
+            "UNLOCK" => Some(Method::UNLOCK),
+            _ => None,
+        }
+    }
+
+    /// Parses a method like `parse`, falling back to GET when the string isn't
+    /// a valid method name.
+    pub fn from_str_lossy(s: &str) -> Method {
+        s.parse().unwrap_or_default()
     }
 }
 
@@ -67,20 +198,37 @@ impl AsRef<str> for Method {
     }
 }
 
-impl From<&str> for Method {
-    fn from(s: &str) -> Self {
-        match s.to_uppercase().as_str() {
-            "GET" => Method::GET,
-            "POST" => Method::POST,
-            "HEAD" => Method::HEAD,
-            "PUT" => Method::PUT,
-            "PATCH" => Method::PATCH,
-            "DELETE" => Method::DELETE,
-            "OPTIONS" => Method::OPTIONS,
-            "CONNECT" => Method::CONNECT,
-            "TRACE" => Method::TRACE,
-            _ => Method::default(),
-        }
+impl FromStr for Method {
+    type Err = InvalidMethod;
+
+    /// Parses a method as printed by `Display`, case-sensitively, so that
+    /// `ANY` and the extension methods round-trip.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Method::from_name(s)
+    }
+}
+
+impl TryFrom<&[u8]> for Method {
+    type Error = InvalidMethod;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        core::str::from_utf8(bytes)
+            .map_err(|_| InvalidMethod::new())
+            .and_then(str::parse)
+    }
+}
+
+impl TryFrom<&str> for Method {
+    type Error = InvalidMethod;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
@@ -89,3 +237,472 @@ impl Default for Method {
         Method::GET
     }
 }
+
+#[cfg(feature = "http")]
+impl From<http::Method> for Method {
+    fn from(method: http::Method) -> Self {
+        match method {
+            http::Method::GET => Method::GET,
+            http::Method::POST => Method::POST,
+            http::Method::HEAD => Method::HEAD,
+            http::Method::PUT => Method::PUT,
+            http::Method::PATCH => Method::PATCH,
+            http::Method::DELETE => Method::DELETE,
+            http::Method::OPTIONS => Method::OPTIONS,
+            http::Method::CONNECT => Method::CONNECT,
+            http::Method::TRACE => Method::TRACE,
+            method => Method::standard(method.as_str())
+                .unwrap_or_else(|| Method(Extension(method.as_str().into()))),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl From<&http::Method> for Method {
+    fn from(method: &http::Method) -> Self {
+        method.clone().into()
+    }
+}
+
+#[cfg(feature = "http")]
+impl From<Method> for http::Method {
+    fn from(method: Method) -> Self {
+        match method.0 {
+            Get => http::Method::GET,
+            Post => http::Method::POST,
+            Head => http::Method::HEAD,
+            Put => http::Method::PUT,
+            Patch => http::Method::PATCH,
+            Delete => http::Method::DELETE,
+            Options => http::Method::OPTIONS,
+            Connect => http::Method::CONNECT,
+            Trace => http::Method::TRACE,
+            // The WebDAV methods, ANY and the extension methods are valid
+            // tokens, so they are kept as extension methods.
+            _ => http::Method::from_bytes(method.as_str().as_bytes()).unwrap(),
+        }
+    }
+}
+
+/// The methods in the order of `Method::index`.
+static INDEXED: [Method; 17] = [
+    Method::GET,
+    Method::POST,
+    Method::HEAD,
+    Method::PUT,
+    Method::PATCH,
+    Method::DELETE,
+    Method::OPTIONS,
+    Method::CONNECT,
+    Method::TRACE,
+    Method::PROPFIND,
+    Method::PROPPATCH,
+    Method::MKCOL,
+    Method::COPY,
+    Method::MOVE,
+    Method::LOCK,
+    Method::UNLOCK,
+    Method::ANY,
+];
+
+/// A compact set of the standard methods and ANY, stored as a bitset.
+///
+/// Extension methods can't be stored in a set, and are ignored when
+/// converting methods into a set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MethodSet(u32);
+
+impl MethodSet {
+    /// Returns an empty set.
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Returns the set of every standard method, without ANY.
+    pub const fn all() -> Self {
+        Self((1 << 16) - 1)
+    }
+
+    /// Adds the method, returning false if it is an extension method.
+    pub fn insert(&mut self, method: Method) -> bool {
+        match method.index() {
+            Some(index) => {
+                self.0 |= 1 << index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the method, returning true if it was in the set.
+    pub fn remove(&mut self, method: &Method) -> bool {
+        let contains = self.contains(method);
+        if let Some(index) = method.index() {
+            self.0 &= !(1 << index);
+        }
+
+        contains
+    }
+
+    pub fn contains(&self, method: &Method) -> bool {
+        method.index().is_some_and(|index| self.0 & (1 << index) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Iterates over the methods in the order of their declaration.
+    pub fn iter(&self) -> impl Iterator<Item = Method> + '_ {
+        INDEXED.iter()
+            .filter(|method| self.contains(method))
+            .cloned()
+    }
+
+    pub fn union(&self, other: MethodSet) -> MethodSet {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(&self, other: MethodSet) -> MethodSet {
+        Self(self.0 & other.0)
+    }
+
+    pub fn difference(&self, other: MethodSet) -> MethodSet {
+        Self(self.0 & !other.0)
+    }
+}
+
+impl From<Method> for MethodSet {
+    fn from(method: Method) -> Self {
+        let mut set = MethodSet::new();
+        set.insert(method);
+        set
+    }
+}
+
+impl From<&[Method]> for MethodSet {
+    fn from(methods: &[Method]) -> Self {
+        methods.iter().cloned().collect()
+    }
+}
+
+impl<const N: usize> From<[Method; N]> for MethodSet {
+    fn from(methods: [Method; N]) -> Self {
+        methods.into_iter().collect()
+    }
+}
+
+impl<const N: usize> From<&[Method; N]> for MethodSet {
+    fn from(methods: &[Method; N]) -> Self {
+        methods.iter().cloned().collect()
+    }
+}
+
+impl FromIterator<Method> for MethodSet {
+    fn from_iter<I: IntoIterator<Item = Method>>(iter: I) -> Self {
+        let mut set = MethodSet::new();
+        for method in iter {
+            set.insert(method);
+        }
+        set
+    }
+}
+
+impl Extend<Method> for MethodSet {
+    fn extend<I: IntoIterator<Item = Method>>(&mut self, iter: I) {
+        for method in iter {
+            self.insert(method);
+        }
+    }
+}
+
+impl BitOr for MethodSet {
+    type Output = MethodSet;
+
+    fn bitor(self, rhs: MethodSet) -> MethodSet {
+        self.union(rhs)
+    }
+}
+
+impl BitOr<Method> for MethodSet {
+    type Output = MethodSet;
+
+    fn bitor(mut self, rhs: Method) -> MethodSet {
+        self.insert(rhs);
+        self
+    }
+}
+
+impl BitOr for Method {
+    type Output = MethodSet;
+
+    fn bitor(self, rhs: Method) -> MethodSet {
+        MethodSet::from(self) | rhs
+    }
+}
+
+impl BitOrAssign<Method> for MethodSet {
+    fn bitor_assign(&mut self, rhs: Method) {
+        self.insert(rhs);
+    }
+}
+
+impl IntoIterator for MethodSet {
+    type Item = Method;
+    type IntoIter = vec::IntoIter<Method>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter().collect::<Vec<_>>().into_iter()
+    }
+}
+
+/// Formats the methods as the value of an `Allow` header, such as `GET, POST`.
+impl fmt::Display for MethodSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, method) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(method.as_str())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A key which tells apart the values registered for the same pattern.
+///
+/// Trees are keyed by `Method` by default, but can be keyed by anything else,
+/// such as a tenant, a protocol version or a content type. Only `compile` and
+/// `allowed_methods`, which are built on `Method`, need the default key.
+pub trait Discriminator: Clone + Eq + Hash + 'static {
+    /// Returns the key whose value is used when the searched key has no value
+    /// of its own, such as `Method::ANY`.
+    fn fallback() -> Option<Self> {
+        None
+    }
+
+    /// Returns the keys whose values are found by position rather than by
+    /// comparison. Only the first 32 are, and the values of the other keys
+    /// are kept in a list.
+    fn indexed() -> &'static [Self] {
+        &[]
+    }
+
+    /// Returns the position of the key in `indexed`.
+    fn index(&self) -> Option<usize> {
+        Self::indexed().iter().position(|key| key == self)
+    }
+
+    /// Returns the bytes allocated by the key, which `Node::memory_usage` counts.
+    fn heap_size(&self) -> usize {
+        0
+    }
+
+    /// Orders the keys which aren't indexed, whose values are kept in this
+    /// order rather than in the order of their insertion. All keys compare
+    /// equal by default, which keeps the insertion order.
+    fn cmp_unindexed(&self, _other: &Self) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+impl Discriminator for Method {
+    fn fallback() -> Option<Self> {
+        Some(Method::ANY)
+    }
+
+    fn indexed() -> &'static [Self] {
+        &INDEXED
+    }
+
+    fn index(&self) -> Option<usize> {
+        Method::index(self)
+    }
+
+    fn heap_size(&self) -> usize {
+        match self.0 {
+            Extension(ref name) => name.len(),
+            _ => 0,
+        }
+    }
+
+    /// Orders the extension methods by name.
+    fn cmp_unindexed(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+/// The number of indexed keys which a `MethodMap` finds by position.
+const INDEX_BITS: usize = u32::BITS as usize;
+
+/// A map from keys to values, which stores the handlers of a leaf.
+///
+/// The values of the first 32 indexed keys, such as the standard methods and
+/// ANY, are kept in the order of `Discriminator::index`, with a bitset telling
+/// which are present. Other keys, such as extension methods, go to a small
+/// overflow list in the order of `Discriminator::cmp_unindexed`.
+#[derive(Debug, Clone)]
+pub(crate) struct MethodMap<V, K = Method> {
+    indexed: u32,
+    values: Vec<V>,
+    extensions: Vec<(K, V)>,
+}
+
+impl<V, K: Discriminator> MethodMap<V, K> {
+    pub(crate) fn new() -> Self {
+        Self {
+            indexed: 0,
+            values: Vec::new(),
+            extensions: Vec::new(),
+        }
+    }
+
+    /// Returns the index of the key in the bitset, or None if it goes to the
+    /// overflow list, as the keys past the first 32 indexed keys do.
+    fn index(key: &K) -> Option<usize> {
+        key.index().filter(|&index| index < INDEX_BITS)
+    }
+
+    /// Returns the position of the value of the key index in `values`.
+    fn position(&self, index: usize) -> usize {
+        (self.indexed & ((1 << index) - 1)).count_ones() as usize
+    }
+
+    fn has_index(&self, index: usize) -> bool {
+        self.indexed & (1 << index) != 0
+    }
+
+    pub(crate) fn get(&self, key: &K) -> Option<&V> {
+        match Self::index(key) {
+            Some(index) if self.has_index(index) => Some(&self.values[self.position(index)]),
+            Some(_) => None,
+            None => self.extensions.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        match Self::index(key) {
+            Some(index) if self.has_index(index) => {
+                let position = self.position(index);
+                Some(&mut self.values[position])
+            }
+            Some(_) => None,
+            None => self.extensions.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v),
+        }
+    }
+
+    pub(crate) fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts the value, returning the replaced value, if any.
+    pub(crate) fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(old) = self.get_mut(&key) {
+            return Some(core::mem::replace(old, value));
+        }
+
+        match Self::index(&key) {
+            Some(index) => {
+                let position = self.position(index);
+                self.values.insert(position, value);
+                self.indexed |= 1 << index;
+            }
+            None => {
+                let position = self.extensions.partition_point(|(k, _)| k.cmp_unindexed(&key) != Ordering::Greater);
+                self.extensions.insert(position, (key, value));
+            }
+        }
+
+        None
+    }
+
+    /// Returns the value of the key, inserting `value` if it is missing.
+    pub(crate) fn get_or_insert(&mut self, key: K, value: V) -> &mut V {
+        if !self.contains_key(&key) {
+            self.insert(key.clone(), value);
+        }
+
+        self.get_mut(&key).unwrap()
+    }
+
+    pub(crate) fn remove(&mut self, key: &K) -> Option<V> {
+        match Self::index(key) {
+            Some(index) if self.has_index(index) => {
+                let position = self.position(index);
+                self.indexed &= !(1 << index);
+                Some(self.values.remove(position))
+            }
+            Some(_) => None,
+            None => {
+                let position = self.extensions.iter().position(|(k, _)| k == key)?;
+                Some(self.extensions.remove(position).1)
+            }
+        }
+    }
+
+    /// Removes every value, returning them with their keys.
+    pub(crate) fn drain(&mut self) -> Vec<(K, V)> {
+        let indexed = core::mem::take(&mut self.indexed);
+        K::indexed().iter()
+            .take(INDEX_BITS)
+            .enumerate()
+            .filter(|(index, _)| indexed & (1 << index) != 0)
+            .map(|(_, key)| key.clone())
+            .zip(core::mem::take(&mut self.values))
+            .chain(core::mem::take(&mut self.extensions))
+            .collect()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.indexed.count_ones() as usize + self.extensions.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.indexed == 0 && self.extensions.is_empty()
+    }
+
+    /// Iterates over the keys and values, in the order of the indexed keys
+    /// and then the other keys in the order of `Discriminator::cmp_unindexed`.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        K::indexed().iter()
+            .take(INDEX_BITS)
+            .enumerate()
+            .filter(|(index, _)| self.has_index(*index))
+            .map(|(_, key)| key)
+            .zip(self.values.iter())
+            .chain(self.extensions.iter().map(|(k, v)| (k, v)))
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns the bytes allocated by the map, not counting the heap memory
+    /// of the values.
+    pub(crate) fn heap_size(&self) -> usize {
+        self.values.capacity() * core::mem::size_of::<V>()
+            + self.extensions.capacity() * core::mem::size_of::<(K, V)>()
+            + self.extensions.iter().map(|(key, _)| key.heap_size()).sum::<usize>()
+    }
+}
+
+impl<V, K: Discriminator> Default for MethodMap<V, K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, K: Discriminator> FromIterator<(K, V)> for MethodMap<V, K> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
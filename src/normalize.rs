@@ -0,0 +1,137 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::pattern::{RoutePattern, Segment};
+use crate::shadow::Shadowing;
+
+/// How a router searches paths with `.` and `..` segments, such as
+/// `/static/../secret`. The dots may be percent-encoded, as in `%2e%2e`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DotSegments {
+    /// Search the path as it is, which is the default.
+    #[default]
+    Keep,
+    /// Remove the dot segments like RFC 3986, so `/static/../secret` is
+    /// searched as `/secret`. A `..` at the root is dropped.
+    Normalize,
+    /// Match nothing.
+    Reject,
+}
+
+impl DotSegments {
+    /// Returns the path to search, or None if it is rejected.
+    fn apply(self, path: &str) -> Option<Cow<'_, str>> {
+        if self == DotSegments::Keep || !path.split('/').any(|segment| dots(segment) > 0) {
+            return Some(Cow::Borrowed(path));
+        }
+
+        match self {
+            DotSegments::Normalize => Some(Cow::Owned(remove_dot_segments(path))),
+            _ => None,
+        }
+    }
+}
+
+/// Returns a path in a Unicode normalization form, such as NFC.
+pub(crate) type UnicodeNormalizer = fn(&str) -> Cow<'_, str>;
+
+/// Applies the normalizations of a router to a searched path, returning
+/// None if it is rejected. The Unicode form is normalized first, and slashes
+/// are merged before the dot segments are handled.
+pub(crate) fn normalize(path: &str, unicode: Option<UnicodeNormalizer>, merge_slashes: bool, dot_segments: DotSegments) -> Option<Cow<'_, str>> {
+    let path = match unicode {
+        Some(normalizer) => normalizer(path),
+        None => Cow::Borrowed(path),
+    };
+    let path = match merge_slashes && path.contains("//") {
+        true => Cow::Owned(merge_duplicate_slashes(&path)),
+        false => path,
+    };
+
+    if let Cow::Owned(normalized) = dot_segments.apply(&path)? {
+        return Some(Cow::Owned(normalized));
+    }
+    Some(path)
+}
+
+/// Returns why the normalizations change every path matching the pattern,
+/// when its static text has a `//` or a dot segment.
+pub(crate) fn shadowing(pattern: &RoutePattern, merge_slashes: bool, dot_segments: DotSegments) -> Option<Shadowing> {
+    // Parameters and wildcards stand for text which isn't a dot segment.
+    let text: String = pattern.segments().iter()
+        .map(|segment| match segment {
+            Segment::Static(text) => text.as_str(),
+            _ => "x",
+        })
+        .collect();
+
+    if merge_slashes && text.contains("//") {
+        return Some(Shadowing::MergedSlashes);
+    }
+    if dot_segments != DotSegments::Keep && text.split('/').any(|segment| dots(segment) > 0) {
+        return Some(Shadowing::DotSegments);
+    }
+    None
+}
+
+/// Replaces each run of `/` with a single `/`, so `//users///42` becomes
+/// `/users/42`.
+fn merge_duplicate_slashes(path: &str) -> String {
+    let mut merged = String::with_capacity(path.len());
+    for c in path.chars() {
+        if c != '/' || !merged.ends_with('/') {
+            merged.push(c);
+        }
+    }
+    merged
+}
+
+/// Returns the number of dots of a `.` or `..` segment, or 0 for the other
+/// segments.
+fn dots(segment: &str) -> usize {
+    let bytes = segment.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'.' => i += 1,
+            b'%' if bytes[i + 1..].get(..2).is_some_and(|hex| hex.eq_ignore_ascii_case(b"2e")) => i += 3,
+            _ => return 0,
+        }
+        count += 1;
+    }
+
+    if count <= 2 { count } else { 0 }
+}
+
+/// Removes the `.` segments, and the `..` segments with the segments before
+/// them. A path ending with a dot segment keeps its trailing `/`.
+fn remove_dot_segments(path: &str) -> String {
+    let (root, rest) = match path.strip_prefix('/') {
+        Some(rest) => ("/", rest),
+        None => ("", path),
+    };
+
+    let mut segments: Vec<&str> = Vec::new();
+    let mut trailing = false;
+    for segment in rest.split('/') {
+        trailing = true;
+        match dots(segment) {
+            1 => {}
+            2 => {
+                segments.pop();
+            }
+            _ => {
+                segments.push(segment);
+                trailing = false;
+            }
+        }
+    }
+
+    let mut normalized = String::from(root);
+    normalized.push_str(&segments.join("/"));
+    if trailing && !segments.is_empty() {
+        normalized.push('/');
+    }
+    normalized
+}
@@ -0,0 +1,259 @@
+//! Generates an OpenAPI 3 document from the routes registered in a tree.
+//!
+//! Path parameters such as `$id` become `{id}` templates with a matching
+//! path parameter, and the `*` wildcard becomes a `{wildcard}` parameter.
+//! Routes registered with `Method::ANY` are listed under every operation that
+//! has no handler of its own.
+//!
+//! The other way round, `Node::from_openapi` registers the operations of a
+//! document, converting `{id}` templates into `$id` parameters.
+//!
+//! A template parameter only matches one segment, so the operations of the
+//! patterns with wildcards carry their pattern in an `x-radixtree-pattern`
+//! field, which `Node::from_openapi` registers instead of the template.
+
+use alloc::{format, string::{String, ToString}, vec, vec::Vec};
+use serde_json::{json, Map, Value};
+use crate::error::OpenApiError;
+use crate::method::Method;
+use crate::pattern::{self, RoutePattern, Segment};
+use crate::tree::Node;
+
+/// The operation field holding a pattern which a path template can't express.
+const PATTERN_FIELD: &str = "x-radixtree-pattern";
+
+/// The methods which can be described by an OpenAPI path item.
+const OPERATIONS: [Method; 8] = [
+    Method::GET,
+    Method::PUT,
+    Method::POST,
+    Method::DELETE,
+    Method::OPTIONS,
+    Method::HEAD,
+    Method::PATCH,
+    Method::TRACE,
+];
+
+/// The info object of the document.
+#[derive(Debug, Clone)]
+pub struct Info {
+    pub title: String,
+    pub version: String,
+}
+
+impl Info {
+    pub fn new(title: &str, version: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            version: version.to_string(),
+        }
+    }
+}
+
+/// An operation of an OpenAPI document, which is turned into a route value.
+#[derive(Debug)]
+pub struct Operation<'a> {
+    pub method: &'a Method,
+    /// The path template, such as `/users/{id}`
+    pub path: &'a str,
+    /// The route pattern, such as `/users/$id`
+    pub pattern: &'a str,
+    /// The operation object
+    pub operation: &'a Value,
+}
+
+impl<V: Clone> Node<V> {
+    /// Creates a tree with a route for every operation of an OpenAPI document.
+    /// The value of each route is returned by `f`.
+    ///
+    /// The pattern of an operation is its `x-radixtree-pattern` field when its
+    /// template is the path of the operation, and the converted template
+    /// otherwise.
+    pub fn from_openapi<F>(spec: &Value, mut f: F) -> Result<Self, OpenApiError>
+    where
+        F: FnMut(&Operation) -> V,
+    {
+        let paths = spec.get("paths")
+            .and_then(Value::as_object)
+            .ok_or(OpenApiError::MissingPaths)?;
+
+        let mut tree = Node::new();
+        for (path, item) in paths {
+            let pattern = route_pattern(path)?;
+            let Some(item) = item.as_object() else {
+                continue;
+            };
+
+            for method in OPERATIONS.iter() {
+                if let Some(operation) = item.get(&method.as_str().to_lowercase()) {
+                    let pattern = operation.get(PATTERN_FIELD)
+                        .and_then(Value::as_str)
+                        .filter(|pattern| RoutePattern::parse(pattern).is_ok() && path_template(pattern) == *path)
+                        .unwrap_or(&pattern);
+                    let value = f(&Operation {
+                        method,
+                        path,
+                        pattern,
+                        operation,
+                    });
+
+                    tree.insert(method.clone(), pattern, value);
+                }
+            }
+        }
+
+        Ok(tree)
+    }
+}
+
+/// Returns the OpenAPI document of every route in the tree.
+pub fn document<V: Clone>(tree: &Node<V>, info: &Info) -> Value {
+    document_with(tree, info, |_, _, _| None)
+}
+
+/// Returns the OpenAPI document of every route in the tree, merging the
+/// fields of the object returned by `metadata` into the operation of each
+/// route, such as `summary`, `tags` or `responses`.
+pub fn document_with<V, F>(tree: &Node<V>, info: &Info, mut metadata: F) -> Value
+where
+    V: Clone,
+    F: FnMut(&Method, &str, &V) -> Option<Value>,
+{
+    let mut routes = Vec::new();
+    tree.for_each_route(&mut |method, pattern, value| {
+        routes.push((method.clone(), pattern.to_string(), value.clone()));
+    });
+
+    let mut paths = Map::new();
+    for (method, pattern, value) in routes.iter() {
+        let methods: Vec<&Method> = if *method == Method::ANY {
+            OPERATIONS.iter()
+                .filter(|m| !routes.iter().any(|(other, p, _)| other == *m && p == pattern))
+                .collect()
+        } else if OPERATIONS.contains(method) {
+            vec![method]
+        } else {
+            // CONNECT and the extension methods can't be described.
+            continue;
+        };
+
+        let mut operation = operation(pattern);
+        if let Some(Value::Object(fields)) = metadata(method, pattern, value) {
+            operation.extend(fields);
+        }
+
+        let item = paths.entry(path_template(pattern))
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .unwrap();
+
+        for method in methods {
+            item.insert(method.as_str().to_lowercase(), Value::Object(operation.clone()));
+        }
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": info.title,
+            "version": info.version,
+        },
+        "paths": paths,
+    })
+}
+
+/// Converts a route pattern into an OpenAPI path template.
+fn path_template(pattern: &str) -> String {
+    RoutePattern::parse(pattern)
+        .unwrap_or_default()
+        .segments()
+        .iter()
+        .map(|segment| match segment {
+            Segment::Static(text) => text.clone(),
+            Segment::Param(name) | Segment::Greedy { name, .. } => format!("{{{}}}", name),
+            Segment::Wildcard => "{wildcard}".to_string(),
+        })
+        .collect()
+}
+
+/// Returns the skeleton operation of a route pattern.
+fn operation(pattern: &str) -> Map<String, Value> {
+    let parameters: Vec<Value> = RoutePattern::parse(pattern)
+        .unwrap_or_default()
+        .segments()
+        .iter()
+        .filter_map(|segment| match segment {
+            Segment::Static(_) => None,
+            Segment::Param(name) => Some(json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            })),
+            Segment::Greedy { name, .. } => Some(json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "description": "The rest of the path",
+                "schema": { "type": "string" },
+            })),
+            Segment::Wildcard => Some(json!({
+                "name": "wildcard",
+                "in": "path",
+                "required": true,
+                "description": "The rest of the path",
+                "schema": { "type": "string" },
+            })),
+        })
+        .collect();
+
+    let mut operation = Map::new();
+    if route_pattern(&path_template(pattern)).ok().as_deref() != Some(pattern) {
+        operation.insert(PATTERN_FIELD.to_string(), Value::String(pattern.to_string()));
+    }
+    if !parameters.is_empty() {
+        operation.insert("parameters".to_string(), Value::Array(parameters));
+    }
+    operation.insert("responses".to_string(), json!({
+        "default": { "description": "" },
+    }));
+
+    operation
+}
+
+/// Converts an OpenAPI path template into a route pattern.
+fn route_pattern(path: &str) -> Result<String, OpenApiError> {
+    let unsupported = || OpenApiError::UnsupportedTemplate(path.to_string());
+
+    let mut pattern = String::new();
+    let mut rest = path;
+    while let Some(start) = rest.find(['{', '}']) {
+        push_static(&mut pattern, &rest[..start]);
+        rest = &rest[start..];
+
+        let end = rest.find('}').filter(|_| rest.starts_with('{')).ok_or_else(unsupported)?;
+        let name = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        // The name must be a valid parameter name, and the text after it must
+        // not continue the name.
+        if name.is_empty()
+            || !name.chars().all(pattern::is_param_char)
+            || rest.starts_with(pattern::is_param_char)
+            || rest.starts_with('{') {
+            return Err(unsupported());
+        }
+
+        pattern.push('$');
+        pattern.push_str(name);
+    }
+    push_static(&mut pattern, rest);
+
+    Ok(pattern)
+}
+
+/// Appends static text of a path template to a route pattern.
+fn push_static(pattern: &mut String, text: &str) {
+    let segment_start = pattern.is_empty() || pattern.ends_with('/');
+    pattern.push_str(&pattern::escape(text, segment_start));
+}
@@ -0,0 +1,183 @@
+use alloc::{borrow::Cow, boxed::Box, string::String, sync::Arc, vec::Vec};
+use crate::method::Method;
+use crate::pattern::RoutePattern;
+use crate::persistent::{Leaf, SharedNode};
+use crate::tree::strip_start_slash;
+use crate::tree::{Param, SearchResult};
+
+/// Escapes a literal `/` and itself in the text of a tree with a custom
+/// separator, so that only the separator is turned into `/`.
+const ESCAPE: char = '\u{E000}';
+
+/// A radix tree with a single value per pattern, for dispatching paths
+/// without HTTP methods, such as topics or commands.
+///
+/// Patterns and matching are the same as `Node`, but the leaves hold an
+/// `Option<V>` instead of a map of methods. The segments are separated by
+/// `/`, unless the tree is built with `with_separator`.
+#[derive(Debug, Clone)]
+pub struct PathTree<V> {
+    root: Arc<SharedNode<Option<V>>>,
+    /// The separator of the segments, when it isn't `/`
+    separator: Option<Box<str>>,
+}
+
+impl<V> Leaf for Option<V> {
+    type Value = V;
+
+    fn lookup(&self, _method: &Method) -> Option<&V> {
+        self.as_ref()
+    }
+
+    fn remove(&mut self, _methods: Option<&[Method]>) -> Vec<V> {
+        self.take().into_iter().collect()
+    }
+
+    fn len(&self) -> usize {
+        self.is_some() as usize
+    }
+}
+
+impl<V: Clone> PathTree<V> {
+    pub fn new() -> Self {
+        Self {
+            root: Arc::new(SharedNode::new("/")),
+            separator: None,
+        }
+    }
+
+    /// Creates a tree whose segments are separated by `separator`, such as
+    /// `.` for domain labels or `::` for command namespaces.
+    ///
+    /// Params stop at the separator instead of `/`, which becomes an ordinary
+    /// character of the patterns and paths. It panics if the
+    /// separator is empty or contains `$`, `*` or `\`.
+    pub fn with_separator(separator: &str) -> Self {
+        if separator.is_empty() || separator.contains(['$', '*', '\\']) {
+            panic!("Invalid separator `{}`.", separator);
+        }
+
+        Self {
+            separator: (separator != "/").then(|| separator.into()),
+            ..Self::new()
+        }
+    }
+
+    /// Inserts the value of the pattern. It panics on conflicts like `Node::insert`.
+    pub fn insert(&mut self, path: &str, value: V) {
+        let leaf = self.insert_leaf(path);
+        if leaf.leaf.is_some() {
+            panic!("A path only appear once.");
+        }
+
+        leaf.leaf = Some(value);
+    }
+
+    /// Inserts a value, replacing the existing value instead of panicking.
+    ///
+    /// Returns the replaced value, if any.
+    pub fn insert_replace(&mut self, path: &str, value: V) -> Option<V> {
+        self.insert_leaf(path).leaf.replace(value)
+    }
+
+    fn insert_leaf(&mut self, path: &str) -> &mut SharedNode<Option<V>> {
+        let path = encode(self.separator.as_deref(), path);
+        if let Err(error) = RoutePattern::parse(&path) {
+            panic!("{}", error);
+        }
+
+        Arc::make_mut(&mut self.root).insert_path(strip_start_slash(&path), None).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Removes the value of the pattern, returning it if any.
+    pub fn remove(&mut self, path: &str) -> Option<V> {
+        let path = encode(self.separator.as_deref(), path);
+        Arc::make_mut(&mut self.root).remove_path(None, strip_start_slash(&path)).pop()
+    }
+
+    /// Returns Some(SearchResult<V>), if successful. Otherwise returns None.
+    pub fn search(&self, path: &str) -> Option<SearchResult<V>> {
+        let path = encode(self.separator.as_deref(), path);
+        let (mut values, mut route) = (Vec::new(), Vec::new());
+        let leaf = self.root.find(&Method::ANY, strip_start_slash(&path), &mut values, &mut route)?;
+        route.reverse();
+        let mut pattern = SharedNode::route_pattern(&route);
+        let mut params = leaf.params(values);
+        if let Some(ref separator) = self.separator {
+            // The pattern is given without the leading separator.
+            pattern = decode(separator, &pattern[1..]);
+            params = params.into_iter()
+                .map(|param| {
+                    let value = decode(separator, param.value());
+                    Param::new(param.name().clone(), value)
+                })
+                .collect();
+        }
+
+        Some(SearchResult::new(leaf.leaf.clone()?, params, pattern))
+    }
+}
+
+/// Rewrites the text of a tree with a custom separator into the `/`
+/// separated form of the matcher. `/` and `ESCAPE` are escaped so that
+/// `decode` can restore the text.
+pub(crate) fn encode<'a>(separator: Option<&str>, text: &'a str) -> Cow<'a, str> {
+    let Some(separator) = separator else {
+        return Cow::Borrowed(text);
+    };
+
+    let mut encoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix(separator) {
+            encoded.push('/');
+            rest = after;
+            continue;
+        }
+
+        match c {
+            '/' => encoded.extend([ESCAPE, '0']),
+            ESCAPE => encoded.extend([ESCAPE, '1']),
+            _ => encoded.push(c),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+
+    Cow::Owned(encoded)
+}
+
+/// Restores the text rewritten by `encode`.
+pub(crate) fn decode(separator: &str, text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' => decoded.push_str(separator),
+            ESCAPE => match chars.next() {
+                Some('0') => decoded.push('/'),
+                _ => decoded.push(ESCAPE),
+            },
+            _ => decoded.push(c),
+        }
+    }
+
+    decoded
+}
+
+impl<V> PathTree<V> {
+    /// Returns the number of patterns.
+    pub fn len(&self) -> usize {
+        self.root.len()
+    }
+
+    /// Returns true if the tree has no patterns.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<V: Clone> Default for PathTree<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
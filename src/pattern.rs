@@ -0,0 +1,596 @@
+use alloc::borrow::Cow;
+use alloc::collections::BTreeSet;
+use core::fmt;
+use core::str::FromStr;
+use alloc::{format, string::{String, ToString}, vec::Vec};
+use crate::error::PatternError;
+
+/// A parsed route pattern, such as `/users/$id/posts/*`.
+///
+/// Patterns are parsed the same way when inserting routes, so `parse` can
+/// validate a pattern up front. `Display` prints the pattern back with the
+/// literal `$`, `*` and `\` escaped.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RoutePattern {
+    segments: Vec<Segment>,
+}
+
+/// A part of a route pattern.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Segment {
+    /// Static text without escapes, which may contain slashes
+    Static(String),
+    /// A path parameter and its name
+    Param(String),
+    /// The * wildcard
+    Wildcard,
+    /// A parameter spanning several segments, such as `$path+`, which matches
+    /// one or more segments, or `$path*`, which also matches an empty path
+    Greedy { name: String, optional: bool },
+}
+
+impl RoutePattern {
+    /// Parses a route pattern, returning an error describing the problem when
+    /// the pattern is invalid.
+    pub fn parse(pattern: &str) -> Result<Self, PatternError> {
+        let mut segments = Vec::new();
+        let mut rest = pattern;
+
+        while let Some(first_char) = rest.chars().next() {
+            let segment_start = pattern.len() == rest.len() || pattern[..pattern.len() - rest.len()].ends_with('/');
+
+            if first_char == '$' {
+                let len = param_name_len(&rest[1..]);
+                if len == 0 {
+                    return Err(PatternError::new(format!("Path parameters must have a name in {:?}.", pattern)));
+                }
+
+                if let Some(Segment::Param(_) | Segment::Wildcard) = segments.last() {
+                    return Err(PatternError::new(format!("Path parameters must be separated from the previous parameter in {:?}.", pattern)));
+                }
+
+                let name = rest[1..=len].to_string();
+                rest = &rest[len + 1..];
+
+                if let Some(quantifier) = rest.chars().next().filter(|c| *c == '+' || *c == '*') {
+                    if !segment_start || rest.len() > 1 {
+                        return Err(PatternError::new(format!("Multi-segment parameters must be the whole last segment of {:?}.", pattern)));
+                    }
+
+                    segments.push(Segment::Greedy { name, optional: quantifier == '*' });
+                    rest = "";
+                } else {
+                    segments.push(Segment::Param(name));
+                }
+            } else if first_char == '*' && segment_start {
+                if !rest[1..].is_empty() && !rest[1..].starts_with('/') {
+                    return Err(PatternError::new("Other characters were found after *".to_string()));
+                }
+
+                segments.push(Segment::Wildcard);
+                rest = &rest[1..];
+            } else {
+                // Static text ends at a parameter, or at a wildcard starting a segment.
+                let end = static_len(rest, segment_start);
+                segments.push(Segment::Static(unescape(&rest[..end]).into_owned()));
+                rest = &rest[end..];
+            }
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Returns the parts of the pattern in order.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Returns the names of the parameters in order, not including the `*`
+    /// wildcards.
+    pub fn param_names(&self) -> Vec<&str> {
+        self.segments.iter()
+            .filter_map(|segment| match segment {
+                Segment::Param(name) | Segment::Greedy { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Renames the parameters in order, leaving the `*` wildcards as they are.
+    pub(crate) fn rename_params(&mut self, names: &[String]) {
+        let params = self.segments.iter_mut().filter_map(|segment| match segment {
+            Segment::Param(name) | Segment::Greedy { name, .. } => Some(name),
+            _ => None,
+        });
+
+        for (name, new_name) in params.zip(names) {
+            name.clone_from(new_name);
+        }
+    }
+
+    /// Returns true if every path matching `other` matches the pattern too,
+    /// such as `/users/*` for `/users/$id/posts`. The priorities of routes
+    /// are not taken into account, and neither are the paths with a `//`.
+    pub fn covers(&self, other: &RoutePattern) -> bool {
+        Automaton::new(self, false).covers(&Automaton::new(other, false), false)
+    }
+
+    /// Returns true if some path without a `//` matches both patterns, such
+    /// as `/users/$id` and `/*/posts`.
+    pub fn overlaps(&self, other: &RoutePattern) -> bool {
+        Automaton::new(self, false).reaches(&Automaton::new(other, false), false, |a, b| a && b)
+    }
+
+    /// Returns true if filling the pattern, as the target of a rule, may give
+    /// a path matching `other`. Multi-segment parameters may be filled with
+    /// any text, including none.
+    pub(crate) fn may_fill(&self, other: &RoutePattern) -> bool {
+        Automaton::new(self, true).reaches(&Automaton::new(other, false), false, |a, b| a && b)
+    }
+}
+
+impl FromStr for RoutePattern {
+    type Err = PatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for RoutePattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut pattern = String::new();
+        for segment in self.segments.iter() {
+            match segment {
+                Segment::Static(text) => {
+                    let segment_start = pattern.is_empty() || pattern.ends_with('/');
+                    pattern.push_str(&escape(text, segment_start));
+                }
+                Segment::Param(name) => {
+                    pattern.push('$');
+                    pattern.push_str(name);
+                }
+                Segment::Wildcard => pattern.push('*'),
+                Segment::Greedy { name, optional } => {
+                    pattern.push('$');
+                    pattern.push_str(name);
+                    pattern.push(if *optional { '*' } else { '+' });
+                }
+            }
+        }
+
+        f.write_str(&pattern)
+    }
+}
+
+/// The syntax of the params and wildcards of route patterns.
+///
+/// Patterns in another syntax are translated into the native syntax, so
+/// static text is literal and needs no escapes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Syntax {
+    /// `$id` params, `$path+` and `$path*` multi-segment params and `*` wildcards
+    #[default]
+    Native,
+    /// A param is its marker followed by the name, such as `:id`. The wildcard
+    /// marker starting a segment is a `*` wildcard, or a multi-segment param
+    /// when followed by a name, such as `*path`.
+    Sigils { param: char, wildcard: char },
+    /// Params are enclosed in braces, such as `{id}`, and `{*path}` is a
+    /// multi-segment param. `{{` and `}}` are literal braces.
+    Braces,
+    /// The syntax of axum 0.8, which is the same as `Braces`
+    Axum,
+    /// The syntax of actix-web, where params are enclosed in braces and may
+    /// have a regex, such as `{id:\d+}`. The regexes of a segment `[^/]+`,
+    /// of one or more segments `.+` and of any path `.*` are supported, and
+    /// the others are rejected, as the tree doesn't match regexes.
+    Actix,
+}
+
+impl Syntax {
+    /// Translates a pattern written in this syntax into the native syntax.
+    pub fn translate<'a>(&self, pattern: &'a str) -> Result<Cow<'a, str>, PatternError> {
+        let segments = match *self {
+            Self::Native => return Ok(Cow::Borrowed(pattern)),
+            Self::Sigils { param, wildcard } => {
+                if param == wildcard || [param, wildcard].iter().any(|&c| c == '/' || is_param_char(c)) {
+                    return Err(PatternError::new(format!("Invalid sigils {:?} and {:?}.", param, wildcard)));
+                }
+
+                translate_sigils(pattern, param, wildcard)?
+            }
+            Self::Braces | Self::Axum => translate_braces(pattern)?,
+            Self::Actix => translate_actix(pattern)?,
+        };
+
+        // Parse the translation to validate it like a native pattern.
+        let native = RoutePattern { segments }.to_string();
+        RoutePattern::parse(&native)?;
+        Ok(Cow::Owned(native))
+    }
+}
+
+/// A part of a pattern, as matched by an `Automaton`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Char(char),
+    /// One or more characters of a segment
+    Segment,
+    /// One or more characters
+    Text,
+    /// Any text, including none
+    Any,
+}
+
+impl Token {
+    /// Returns the priority of the token in the tree, which tries static
+    /// text first, then parameters, then wildcards.
+    fn rank(&self) -> u8 {
+        match self {
+            Token::Char(_) => 0,
+            Token::Segment => 1,
+            Token::Text | Token::Any => 2,
+        }
+    }
+}
+
+/// Matches paths with the tokens of a pattern. The state `2 * i` is before
+/// the token `i`, and `2 * i + 1` is inside it, after one character at least.
+pub(crate) struct Automaton {
+    tokens: Vec<Token>,
+}
+
+impl Automaton {
+    /// With `loose`, the multi-segment parameters match any text.
+    pub(crate) fn new(pattern: &RoutePattern, loose: bool) -> Self {
+        let mut tokens = Vec::new();
+        for segment in pattern.segments() {
+            match segment {
+                Segment::Static(text) => tokens.extend(text.chars().map(Token::Char)),
+                Segment::Param(_) => tokens.push(Token::Segment),
+                Segment::Greedy { optional: false, .. } | Segment::Wildcard if !loose => tokens.push(Token::Text),
+                _ => tokens.push(Token::Any),
+            }
+        }
+
+        Self { tokens }
+    }
+
+    /// Returns true if the route of the automaton takes precedence over the
+    /// route of `other` for every path matching `other`, so that `other`
+    /// never matches. That is when they first differ where the route has
+    /// static text and `other` a parameter or a wildcard, or the route a
+    /// parameter and `other` a wildcard, and the rest of the route matches
+    /// every rest of `other`, such as `/$name/*` for `/*/posts`.
+    pub(crate) fn shadows(&self, other: &Automaton) -> bool {
+        let common = self.tokens.iter().zip(other.tokens.iter()).take_while(|(a, b)| a == b).count();
+        let (Some(first), Some(other_first)) = (self.tokens.get(common), other.tokens.get(common)) else {
+            return false;
+        };
+        if first.rank() >= other_first.rank() {
+            return false;
+        }
+
+        let after_slash = common > 0 && self.tokens[common - 1] == Token::Char('/');
+        let rest = Automaton { tokens: self.tokens[common..].to_vec() };
+        rest.covers(&Automaton { tokens: other.tokens[common..].to_vec() }, after_slash)
+    }
+
+    fn start(&self) -> BTreeSet<usize> {
+        let mut states = BTreeSet::new();
+        self.close(0, &mut states);
+        states
+    }
+
+    fn accepts(&self, states: &BTreeSet<usize>) -> bool {
+        states.contains(&(2 * self.tokens.len()))
+    }
+
+    /// Adds the state and the states reached from it without a character.
+    fn close(&self, state: usize, states: &mut BTreeSet<usize>) {
+        if !states.insert(state) {
+            return;
+        }
+
+        let index = state / 2;
+        match self.tokens.get(index) {
+            Some(Token::Segment | Token::Text) if state % 2 == 1 => self.close(2 * index + 2, states),
+            Some(Token::Any) => self.close(2 * index + 2, states),
+            _ => {}
+        }
+    }
+
+    /// Returns the states reached from the states with the character, None
+    /// standing for the characters which neither pattern has.
+    fn step(&self, states: &BTreeSet<usize>, c: Option<char>) -> BTreeSet<usize> {
+        let mut next = BTreeSet::new();
+        for &state in states {
+            let index = state / 2;
+            let target = match self.tokens.get(index) {
+                Some(&Token::Char(expected)) if c == Some(expected) => 2 * index + 2,
+                Some(Token::Segment) if c != Some('/') => 2 * index + 1,
+                Some(Token::Text) => 2 * index + 1,
+                Some(Token::Any) => 2 * index,
+                _ => continue,
+            };
+            self.close(target, &mut next);
+        }
+
+        next
+    }
+
+    /// Returns true if every path matched by `other` is matched too.
+    fn covers(&self, other: &Automaton, after_slash: bool) -> bool {
+        !self.reaches(other, after_slash, |matched, other_matched| other_matched && !matched)
+    }
+
+    /// Returns true if a path takes both automatons to states for which
+    /// `found` returns true, given whether each one matches the path. The
+    /// paths with a `//`, counting a `/` before them with `after_slash`, and
+    /// the paths which `other` can't match are skipped.
+    fn reaches<F: Fn(bool, bool) -> bool>(&self, other: &Automaton, after_slash: bool, found: F) -> bool {
+        let mut alphabet: Vec<Option<char>> = self.tokens.iter().chain(other.tokens.iter())
+            .filter_map(|token| match token {
+                Token::Char(c) => Some(Some(*c)),
+                _ => None,
+            })
+            .collect();
+        alphabet.extend([Some('/'), None]);
+        alphabet.sort();
+        alphabet.dedup();
+
+        let mut seen = BTreeSet::new();
+        let mut pending = Vec::from([(self.start(), other.start(), after_slash)]);
+        while let Some((a, b, after_slash)) = pending.pop() {
+            if !seen.insert((a.clone(), b.clone(), after_slash)) {
+                continue;
+            }
+            if found(self.accepts(&a), other.accepts(&b)) {
+                return true;
+            }
+
+            for &c in alphabet.iter() {
+                let slash = c == Some('/');
+                if slash && after_slash {
+                    continue;
+                }
+
+                let next = other.step(&b, c);
+                if !next.is_empty() {
+                    pending.push((self.step(&a, c), next, slash));
+                }
+            }
+        }
+
+        false
+    }
+}
+
+/// Appends a literal character to the segments.
+fn push_literal(segments: &mut Vec<Segment>, c: char) {
+    match segments.last_mut() {
+        Some(Segment::Static(text)) => text.push(c),
+        _ => segments.push(Segment::Static(c.to_string())),
+    }
+}
+
+fn translate_sigils(pattern: &str, param: char, wildcard: char) -> Result<Vec<Segment>, PatternError> {
+    let mut segments = Vec::new();
+    let mut segment_start = true;
+    let mut rest = pattern;
+
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+        if c == param {
+            let len = param_name_len(rest);
+            if len == 0 {
+                return Err(PatternError::new(format!("Path parameters must have a name in {:?}.", pattern)));
+            }
+
+            segments.push(Segment::Param(rest[..len].to_string()));
+            rest = &rest[len..];
+        } else if c == wildcard && segment_start {
+            let len = param_name_len(rest);
+            segments.push(match len {
+                0 => Segment::Wildcard,
+                _ => Segment::Greedy { name: rest[..len].to_string(), optional: false },
+            });
+            rest = &rest[len..];
+        } else {
+            push_literal(&mut segments, c);
+        }
+
+        segment_start = c == '/';
+    }
+
+    Ok(segments)
+}
+
+fn translate_braces(pattern: &str) -> Result<Vec<Segment>, PatternError> {
+    let mut segments = Vec::new();
+    let mut rest = pattern;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("{{").or_else(|| rest.strip_prefix("}}")) {
+            push_literal(&mut segments, c);
+            rest = after;
+            continue;
+        }
+
+        rest = &rest[c.len_utf8()..];
+        match c {
+            '{' => {
+                let Some(end) = rest.find('}') else {
+                    return Err(PatternError::new(format!("Unclosed brace in {:?}.", pattern)));
+                };
+
+                let (name, greedy) = match rest[..end].strip_prefix('*') {
+                    Some(name) => (name, true),
+                    None => (&rest[..end], false),
+                };
+                if name.is_empty() || param_name_len(name) != name.len() {
+                    return Err(PatternError::new(format!("Invalid parameter {:?} in {:?}.", &rest[..end], pattern)));
+                }
+
+                segments.push(match greedy {
+                    true => Segment::Greedy { name: name.to_string(), optional: false },
+                    false => Segment::Param(name.to_string()),
+                });
+                rest = &rest[end + 1..];
+            }
+            '}' => return Err(PatternError::new(format!("Unmatched closing brace in {:?}.", pattern))),
+            _ => push_literal(&mut segments, c),
+        }
+    }
+
+    Ok(segments)
+}
+
+fn translate_actix(pattern: &str) -> Result<Vec<Segment>, PatternError> {
+    let mut segments = Vec::new();
+    let mut rest = pattern;
+
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+        if c != '{' {
+            push_literal(&mut segments, c);
+            continue;
+        }
+
+        // A regex may contain braces, such as `{id:\d{4}}`, so the param ends
+        // at the matching closing brace.
+        let mut depth = 1;
+        let Some(end) = rest.find(|c| {
+            depth += match c {
+                '{' => 1,
+                '}' => -1,
+                _ => 0,
+            };
+            depth == 0
+        }) else {
+            return Err(PatternError::new(format!("Unclosed brace in {:?}.", pattern)));
+        };
+
+        let (name, regex) = rest[..end].split_once(':').unwrap_or((&rest[..end], "[^/]+"));
+        if name.is_empty() || param_name_len(name) != name.len() {
+            return Err(PatternError::new(format!("Invalid parameter {:?} in {:?}.", &rest[..end], pattern)));
+        }
+
+        segments.push(match regex {
+            "[^/]+" => Segment::Param(name.to_string()),
+            ".+" => Segment::Greedy { name: name.to_string(), optional: false },
+            ".*" => Segment::Greedy { name: name.to_string(), optional: true },
+            _ => return Err(PatternError::new(format!("Unsupported regex {:?} of parameter {:?} in {:?}.", regex, name, pattern))),
+        });
+        rest = &rest[end + 1..];
+    }
+
+    Ok(segments)
+}
+
+/// Returns true if the character can be part of a parameter name.
+pub(crate) fn is_param_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Returns the length of the parameter name at the start of the path.
+pub(crate) fn param_name_len(path: &str) -> usize {
+    path.find(|c| !is_param_char(c)).unwrap_or(path.len())
+}
+
+/// Returns the wildcard at the start of the path, such as `*` or `$path+`,
+/// as the path of its node and the length of the wildcard.
+pub(crate) fn wildcard(path: &str) -> Option<(&str, usize)> {
+    if path.starts_with('*') {
+        return Some(("", 1));
+    }
+
+    let name = path.strip_prefix('$')?;
+    let len = param_name_len(name);
+    match name[len..].chars().next() {
+        Some('+' | '*') if len > 0 => Some((&name[..=len], len + 2)),
+        _ => None,
+    }
+}
+
+/// Returns the first character of the pattern and the length of its text
+/// when it is a literal character, resolving the escapes `\$`, `\*` and `\\`.
+///
+/// Returns None for a parameter, or a wildcard at the start of a segment.
+fn literal_char(pattern: &str, segment_start: bool) -> Option<(char, usize)> {
+    let mut chars = pattern.chars();
+    match chars.next()? {
+        '\\' => match chars.next() {
+            Some(c @ ('$' | '*' | '\\')) => Some((c, 2)),
+            _ => Some(('\\', 1)),
+        },
+        '$' => None,
+        '*' if segment_start => None,
+        c => Some((c, c.len_utf8())),
+    }
+}
+
+/// Returns the length of the static text at the start of the pattern, which
+/// ends at a parameter or a wildcard.
+pub(crate) fn static_len(pattern: &str, mut segment_start: bool) -> usize {
+    let mut len = 0;
+    while let Some((c, char_len)) = literal_char(&pattern[len..], segment_start) {
+        len += char_len;
+        segment_start = c == '/';
+    }
+
+    len
+}
+
+/// Returns the pattern without the literal text at its start, or None if
+/// the pattern doesn't start with it.
+pub(crate) fn strip_literal<'a>(pattern: &'a str, literal: &str, mut segment_start: bool) -> Option<&'a str> {
+    let mut rest = pattern;
+    for c in literal.chars() {
+        let (next, len) = literal_char(rest, segment_start)?;
+        if next != c {
+            return None;
+        }
+
+        rest = &rest[len..];
+        segment_start = c == '/';
+    }
+
+    Some(rest)
+}
+
+/// Resolves the escapes of static text.
+pub(crate) fn unescape(text: &str) -> Cow<'_, str> {
+    if !text.contains('\\') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut literal = String::new();
+    let mut rest = text;
+    while let Some((c, len)) = literal_char(rest, false) {
+        literal.push(c);
+        rest = &rest[len..];
+    }
+
+    Cow::Owned(literal)
+}
+
+/// Escapes literal text, so that it can be used in a pattern.
+pub(crate) fn escape(literal: &str, mut segment_start: bool) -> Cow<'_, str> {
+    if !literal.contains(['$', '*', '\\']) {
+        return Cow::Borrowed(literal);
+    }
+
+    let mut text = String::new();
+    for c in literal.chars() {
+        if c == '$' || c == '\\' || c == '*' && segment_start {
+            text.push('\\');
+        }
+
+        text.push(c);
+        segment_start = c == '/';
+    }
+
+    Cow::Owned(text)
+}
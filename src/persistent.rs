@@ -0,0 +1,471 @@
+use alloc::sync::Arc;
+use alloc::{boxed::Box, format, string::{String, ToString}, vec::Vec};
+use crate::error::ConflictKind;
+use crate::method::{Method, MethodMap};
+use crate::pattern::{self, RoutePattern};
+use crate::tree::{common_prefix_len, push_name, strip_start_slash, wildcard_pattern, ConflictPolicy, Param, SearchResult};
+
+/// A radix tree whose changes return a new tree, which shares the unchanged
+/// nodes with the old one.
+///
+/// Cloning the tree only clones an `Arc`, so snapshots are cheap and can be
+/// sent to other threads. A change copies the nodes on the path of the route,
+/// and routes are matched exactly like `Node::search`.
+#[derive(Debug)]
+pub struct PersistentNode<V> {
+    root: Arc<SharedNode<MethodMap<V>>>,
+}
+
+/// A node whose children are shared with `Arc`, and which copies them on
+/// write. `L` holds the values of a leaf.
+#[derive(Debug, Clone)]
+pub(crate) struct SharedNode<L> {
+    path: Box<str>,
+    /// The first letters of the static children, sorted for binary search
+    static_indices: Vec<char>,
+    static_child: Vec<Arc<Self>>,
+    param_child: Option<Arc<Self>>,
+    star_child: Option<Arc<Self>>,
+    pub(crate) leaf: L,
+    pub(crate) leaf_param_names: Option<Vec<String>>,
+}
+
+/// The values of a leaf of a `SharedNode`.
+pub(crate) trait Leaf: Default {
+    type Value;
+
+    /// Returns the value which handles the method.
+    fn lookup(&self, method: &Method) -> Option<&Self::Value>;
+
+    /// Removes the values of the methods, or every value when methods is None.
+    fn remove(&mut self, methods: Option<&[Method]>) -> Vec<Self::Value>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<V> Leaf for MethodMap<V> {
+    type Value = V;
+
+    /// Falls back to the handler of `Method::ANY`, like `Node::search`.
+    fn lookup(&self, method: &Method) -> Option<&V> {
+        self.get(method).or_else(|| self.get(&Method::ANY))
+    }
+
+    fn remove(&mut self, methods: Option<&[Method]>) -> Vec<V> {
+        match methods {
+            Some(methods) => methods.iter().filter_map(|method| MethodMap::remove(self, method)).collect(),
+            None => self.drain().into_iter().map(|(_, value)| value).collect(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        MethodMap::len(self)
+    }
+}
+
+impl<V: Clone> PersistentNode<V> {
+    pub fn new() -> Self {
+        Self {
+            root: Arc::new(SharedNode::new("/")),
+        }
+    }
+
+    /// Returns a new tree with the route inserted. It panics on conflicts
+    /// like `Node::insert`.
+    pub fn insert(&self, method: Method, path: &str, value: V) -> Self {
+        self.try_insert(method, path, value).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Returns a new tree with the route inserted, or the conflict of the
+    /// route or its invalid pattern, instead of panicking.
+    pub fn try_insert(&self, method: Method, path: &str, value: V) -> Result<Self, ConflictKind> {
+        self.insert_with(method, path, value, ConflictPolicy::Error)
+    }
+
+    /// Returns a new tree with the route inserted. `ConflictPolicy::Overwrite`
+    /// replaces the value of a registered route, and the other conflicts are
+    /// returned.
+    pub(crate) fn insert_with(&self, method: Method, path: &str, value: V, policy: ConflictPolicy) -> Result<Self, ConflictKind> {
+        if let Err(error) = RoutePattern::parse(path) {
+            return Err(ConflictKind::InvalidPattern(error));
+        }
+
+        let mut tree = self.clone();
+        let leaf = Arc::make_mut(&mut tree.root).insert_path(strip_start_slash(path), None)?;
+        if policy != ConflictPolicy::Overwrite && leaf.leaf.contains_key(&method) {
+            return Err(ConflictKind::Duplicate);
+        }
+
+        leaf.leaf.insert(method, value);
+        Ok(tree)
+    }
+
+    /// Returns a new tree without any method of the path.
+    pub fn remove(&self, path: &str) -> Self {
+        self.remove_methods(None, path)
+    }
+
+    /// Returns a new tree without the handler of the method, keeping the other
+    /// methods of the path.
+    pub fn remove_method(&self, method: Method, path: &str) -> Self {
+        self.remove_methods(Some(&[method]), path)
+    }
+
+    fn remove_methods(&self, methods: Option<&[Method]>, path: &str) -> Self {
+        let mut tree = self.clone();
+        Arc::make_mut(&mut tree.root).remove_path(methods, strip_start_slash(path));
+        tree
+    }
+
+    /// Returns Some(SearchResult<V>), if successful. Otherwise returns None.
+    pub fn search(&self, method: Method, path: &str) -> Option<SearchResult<V>> {
+        let (mut values, mut route) = (Vec::new(), Vec::new());
+        let leaf = self.root.find(&method, strip_start_slash(path), &mut values, &mut route)?;
+        route.reverse();
+        let pattern = SharedNode::route_pattern(&route);
+        Some(SearchResult::new(leaf.leaf.lookup(&method)?.clone(), leaf.params(values), pattern))
+    }
+}
+
+impl<V> PersistentNode<V> {
+    /// Returns the number of routes, counting each method of a path.
+    pub fn len(&self) -> usize {
+        self.root.len()
+    }
+
+    /// Returns true if the tree has no routes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if both trees are the same snapshot.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.root, &other.root)
+    }
+}
+
+impl<L: Leaf + Clone> SharedNode<L> {
+    pub(crate) fn new(path: &str) -> Self {
+        Self {
+            path: path.into(),
+            static_indices: Vec::new(),
+            static_child: Vec::new(),
+            param_child: None,
+            star_child: None,
+            leaf: L::default(),
+            leaf_param_names: None,
+        }
+    }
+
+    /// Returns the leaf node of the path, copying the shared nodes along the
+    /// way and creating the missing ones, like `Node::insert_path`.
+    ///
+    /// Returns the conflict of ambiguous parameters or wildcards instead, in
+    /// which case the copied nodes are partly changed and must be dropped.
+    pub(crate) fn insert_path(&mut self, path: &str, param_names: Option<Vec<String>>) -> Result<&mut Self, ConflictKind> {
+        if path.is_empty() {
+            if let Some(param_names) = param_names {
+                match self.leaf_param_names {
+                    // Ambiguous path, such as /hello/$a and /hello/$b.
+                    Some(ref leaf_param_names) if *leaf_param_names != param_names => {
+                        return Err(ConflictKind::AmbiguousParams(leaf_param_names.clone()));
+                    }
+                    Some(_) => {}
+                    None => self.leaf_param_names = Some(param_names),
+                }
+            }
+
+            return Ok(self);
+        }
+
+        // Handle the * wildcard and multi-segment parameters at the start of a segment
+        if let Some((wildcard_path, len)) = pattern::wildcard(path).filter(|_| self.path.ends_with('/')) {
+            let node = Arc::make_mut(self.star_child.get_or_insert_with(|| Arc::new(Self::new(wildcard_path))));
+
+            // Ambiguous wildcards, such as /files/* and /files/$path+.
+            if *node.path != *wildcard_path {
+                return Err(ConflictKind::Shadowing(wildcard_pattern(&node.path)));
+            }
+
+            let param_names = if wildcard_path.is_empty() {
+                param_names
+            } else {
+                Some(push_name(param_names, &wildcard_path[..wildcard_path.len() - 1]))
+            };
+
+            return node.insert_path(&path[len..], param_names);
+        }
+
+        let segment_start = self.path.ends_with('/');
+        if let Some(name) = path.strip_prefix('$') { // Handle path parameters
+            let len = pattern::param_name_len(name);
+            let node = Arc::make_mut(self.param_child.get_or_insert_with(|| Arc::new(Self::new(&name[..len]))));
+            return node.insert_path(&name[len..], Some(push_name(param_names, &name[..len])));
+        }
+
+        // Handle static path. A static token ends at the next slash or parameter,
+        // and the node stores it without escapes.
+        let token_end = if path.starts_with('/') {
+            1
+        } else {
+            pattern::static_len(path, segment_start).min(path.find('/').unwrap_or(path.len()))
+        };
+        let token = pattern::unescape(&path[..token_end]).into_owned();
+        let first_char = token.chars().next().unwrap();
+
+        let i = match self.static_indices.binary_search(&first_char) {
+            Ok(i) => {
+                self.split_common_prefix(i, &token);
+                i
+            }
+            Err(i) => {
+                self.static_indices.insert(i, first_char);
+                self.static_child.insert(i, Arc::new(Self::new(&token)));
+                i
+            }
+        };
+
+        let node = Arc::make_mut(&mut self.static_child[i]);
+        let next_path = pattern::strip_literal(path, &node.path, segment_start).unwrap();
+        node.insert_path(next_path, param_names)
+    }
+
+    /// Splits the static child at the common prefix of its path and `token`.
+    fn split_common_prefix(&mut self, i: usize, token: &str) {
+        let child = &self.static_child[i];
+        if token.starts_with(&*child.path) {
+            return;
+        }
+
+        let len = common_prefix_len(token, &child.path);
+
+        let mut old_node = self.static_child[i].clone();
+        let old = Arc::make_mut(&mut old_node);
+        old.path = old.path[len..].into();
+
+        let mut new_node = Self::new(&token[..len]);
+        new_node.static_indices.push(old.path.chars().next().unwrap());
+        new_node.static_child.push(old_node);
+        self.static_child[i] = Arc::new(new_node);
+    }
+
+    /// Removes the values of the methods, or every value when methods is None.
+    ///
+    /// Returns the removed values.
+    pub(crate) fn remove_path(&mut self, methods: Option<&[Method]>, path: &str) -> Vec<L::Value> {
+        if path.is_empty() {
+            let removed = self.leaf.remove(methods);
+            if self.leaf.is_empty() {
+                self.leaf_param_names = None;
+            }
+            return removed;
+        }
+
+        // First see if this matches a static path
+        let segment_start = self.path.ends_with('/');
+        let found = self.static_child.iter().enumerate().find_map(|(i, child)| {
+            pattern::strip_literal(path, &child.path, segment_start).map(|next_path| (i, next_path))
+        });
+        if let Some((i, next_path)) = found {
+            let node = Arc::make_mut(&mut self.static_child[i]);
+            let removed = node.remove_path(methods, next_path);
+
+            if node.is_unused() { // Remove static child node
+                self.static_child.remove(i);
+                self.static_indices.remove(i);
+            } else if node.leaf.is_empty()
+                && node.static_child.len() == 1
+                && node.param_child.is_none()
+                && node.star_child.is_none()
+                && &*node.path != "/"
+                && &*node.static_child[0].path != "/" { // Merge nodes
+                let path = node.path.clone();
+                let mut child = node.static_child.pop().unwrap();
+                let merged = Arc::make_mut(&mut child);
+                merged.path = format!("{}{}", path, merged.path).into();
+                self.static_child[i] = child;
+            }
+            return removed;
+        }
+
+        // Check for a wildcard * or a multi-segment parameter.
+        if let Some((wildcard_path, len)) = pattern::wildcard(path) {
+            if let Some(ref mut star_child) = self.star_child {
+                if *star_child.path == *wildcard_path {
+                    let removed = Arc::make_mut(star_child).remove_path(methods, &path[len..]);
+                    if star_child.is_unused() {
+                        self.star_child = None;
+                    }
+                    return removed;
+                }
+            }
+        }
+
+        // Didn't find a static path, so check for a path parameter.
+        if let Some(name) = path.strip_prefix('$') {
+            if let Some(ref mut param_child) = self.param_child {
+                let removed = Arc::make_mut(param_child).remove_path(methods, &name[pattern::param_name_len(name)..]);
+                if param_child.is_unused() {
+                    self.param_child = None;
+                }
+                return removed;
+            }
+        }
+
+        Vec::new()
+    }
+}
+
+impl<L: Leaf> SharedNode<L> {
+    /// Returns the matched leaf, pushing the parameter values along the way,
+    /// and the matched nodes from the leaf up to this node once found. It
+    /// tries the static child, then the param child and finally the wildcard
+    /// child, like `Node::search`.
+    pub(crate) fn find<'a, 'p>(&'a self, method: &Method, path: &'p str, values: &mut Vec<&'p str>, route: &mut Vec<&'a Self>) -> Option<&'a Self> {
+        let found = self.find_children(method, path, values, route)?;
+        route.push(self);
+        Some(found)
+    }
+
+    fn find_children<'a, 'p>(&'a self, method: &Method, path: &'p str, values: &mut Vec<&'p str>, route: &mut Vec<&'a Self>) -> Option<&'a Self> {
+        if path.is_empty() {
+            if self.leaf.lookup(method).is_some() {
+                return Some(self);
+            }
+
+            // A `$path*` parameter also matches an empty path.
+            let star_child = self.star_child.as_ref().filter(|child| child.path.ends_with('*'))?;
+            star_child.leaf.lookup(method)?;
+            values.push(path);
+            route.push(star_child);
+            return Some(star_child);
+        }
+
+        // First see if this matches a static path
+        let first_char = path.chars().next().unwrap();
+        if let Ok(i) = self.static_indices.binary_search(&first_char) {
+            let static_child = &self.static_child[i];
+            if let Some(next_path) = path.strip_prefix(&*static_child.path) {
+                if let Some(found) = static_child.find(method, next_path, values, route) {
+                    return Some(found);
+                }
+            }
+        }
+
+        // Didn't find a static path, so check for a path parameter.
+        if let Some(ref param_child) = self.param_child {
+            let segment_end = path.find('/').unwrap_or(path.len());
+            let in_segment = param_child.static_child.iter().any(|child| !child.path.starts_with('/'));
+            let ends = path[..segment_end].char_indices()
+                .skip(1)
+                .map(|(i, _)| i)
+                .filter(|_| in_segment)
+                .chain((segment_end > 0).then_some(segment_end));
+
+            for value_end in ends {
+                values.push(&path[..value_end]);
+                if let Some(found) = param_child.find(method, &path[value_end..], values, route) {
+                    return Some(found);
+                }
+                values.pop();
+            }
+        }
+
+        // Finally check for a wildcard *
+        if let Some(ref star_child) = self.star_child {
+            // A wildcard in the middle of a pattern matches one or more
+            // segments, trying the fewest segments first.
+            if !star_child.static_child.is_empty() || star_child.param_child.is_some() || star_child.star_child.is_some() {
+                for (next_slash, _) in path.match_indices('/').filter(|(i, _)| *i > 0) {
+                    if let Some(found) = star_child.find(method, &path[next_slash..], values, route) {
+                        return Some(found);
+                    }
+                }
+            }
+
+            // A wildcard at the end of a pattern matches the rest of the path.
+            star_child.leaf.lookup(method)?;
+            if !star_child.path.is_empty() {
+                values.push(path);
+            }
+            route.push(star_child);
+            return Some(star_child);
+        }
+
+        None
+    }
+
+    /// Returns the pattern of the leaf at the end of the route, given as the
+    /// nodes from the root, with the parameter names of the leaf.
+    pub(crate) fn route_pattern(route: &[&Self]) -> String {
+        let leaf = route[route.len() - 1];
+        let mut names = leaf.leaf_param_names.iter().flatten();
+        let mut pattern = String::from("/");
+
+        for pair in route.windows(2) {
+            let (parent, child) = (pair[0], pair[1]);
+            let path = &*child.path;
+            if parent.param_child.as_deref().is_some_and(|param_child| core::ptr::eq(param_child, child)) {
+                pattern.push('$');
+                pattern.push_str(names.next().map_or(path, |name| name));
+            } else if parent.star_child.as_deref().is_some_and(|star_child| core::ptr::eq(star_child, child)) {
+                // A multi-segment parameter keeps its `+` or `*` suffix.
+                match path.char_indices().last() {
+                    Some((suffix, _)) => {
+                        pattern.push('$');
+                        pattern.push_str(names.next().map_or(&path[..suffix], |name| name));
+                        pattern.push_str(&path[suffix..]);
+                    }
+                    None => pattern.push('*'),
+                }
+            } else {
+                let segment_start = pattern.ends_with('/');
+                pattern.push_str(&pattern::escape(path, segment_start));
+            }
+        }
+
+        pattern
+    }
+
+    /// Returns the parameters of the leaf with the values pushed by `find`.
+    pub(crate) fn params(&self, values: Vec<&str>) -> Vec<Param> {
+        self.leaf_param_names.iter()
+            .flatten()
+            .zip(values)
+            .map(|(name, value)| Param::new(name.clone(), value.to_string()))
+            .collect()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.leaf.len()
+            + self.static_child.iter().map(|child| child.len()).sum::<usize>()
+            + self.param_child.as_ref().map_or(0, |child| child.len())
+            + self.star_child.as_ref().map_or(0, |child| child.len())
+    }
+
+    /// Returns true if the node has neither handlers nor child nodes.
+    fn is_unused(&self) -> bool {
+        self.leaf.is_empty()
+            && self.static_child.is_empty()
+            && self.param_child.is_none()
+            && self.star_child.is_none()
+    }
+}
+
+impl<V> Clone for PersistentNode<V> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+        }
+    }
+}
+
+impl<V: Clone> Default for PersistentNode<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,126 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::error::RequestLineError;
+use crate::method::Method;
+
+/// The pairs of a query string, in the order they appear, returned by
+/// `Router::search_uri`.
+///
+/// Names and values are percent-decoded, and `+` is decoded as a space. A
+/// name without `=` has an empty value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query(Vec<(String, String)>);
+
+impl Query {
+    /// Parses a query string, without its leading `?`.
+    pub fn parse(query: &str) -> Self {
+        let pairs = query.split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (decode(name), decode(value))
+            })
+            .collect();
+
+        Self(pairs)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the value of the first pair with the name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.iter().find(|(key, _)| *key == name).map(|(_, value)| value)
+    }
+
+    /// Returns the values of every pair with the name, such as the values of
+    /// `tag` in `?tag=a&tag=b`.
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.iter().filter(move |(key, _)| *key == name).map(|(_, value)| value)
+    }
+}
+
+/// Splits a URI into its path and its query string, dropping the fragment.
+pub(crate) fn split_uri(uri: &str) -> (&str, &str) {
+    let uri = uri.split('#').next().unwrap_or(uri);
+    uri.split_once('?').unwrap_or((uri, ""))
+}
+
+/// Parses an HTTP request line such as `GET /users/42 HTTP/1.1`, returning
+/// its method and the path and query of its target. A trailing CRLF is
+/// ignored.
+pub(crate) fn parse_request_line(line: &str) -> Result<(Method, &str), RequestLineError> {
+    let line = line.strip_suffix("\r\n").or_else(|| line.strip_suffix('\n')).unwrap_or(line);
+    let mut parts = line.split(' ');
+    let (Some(method), Some(target), Some(version), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+        return Err(RequestLineError::Malformed);
+    };
+
+    let method = Method::extension(method).map_err(RequestLineError::Method)?;
+    let digits = version.strip_prefix("HTTP/").map(str::as_bytes);
+    if !matches!(digits, Some([major, b'.', minor]) if major.is_ascii_digit() && minor.is_ascii_digit()) {
+        return Err(RequestLineError::Version(version.to_string()));
+    }
+
+    Ok((method, origin_form(target).ok_or_else(|| RequestLineError::Target(target.to_string()))?))
+}
+
+/// Returns the origin form of a request target, which is the target itself
+/// or the part of an absolute `http` or `https` URI after its authority. The
+/// path of the latter may be empty.
+fn origin_form(target: &str) -> Option<&str> {
+    if target.starts_with('/') {
+        return Some(target);
+    }
+
+    let (scheme, rest) = target.split_once("://")?;
+    if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+        return None;
+    }
+
+    let start = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    (start > 0).then(|| &rest[start..])
+}
+
+/// Decodes the percent-encoded bytes and the `+` of a query component.
+/// Invalid escapes are kept as they are.
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = match bytes[i] {
+            b'%' => bytes.get(i + 1..i + 3)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|hex| core::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+
+        match (escaped, bytes[i]) {
+            (Some(byte), _) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (None, b'+') => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (None, byte) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,28 @@
+use alloc::string::String;
+use crate::tree::SearchResult;
+
+/// Whether a redirect route moved its paths for good, which decides the
+/// status code of the redirect response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectKind {
+    Permanent,
+    Temporary,
+}
+
+/// A redirect found by `Router::resolve`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    /// The target of the redirect route, with the parameters of the path
+    /// substituted
+    pub location: String,
+    pub permanent: bool,
+}
+
+/// What `Router::resolve` found for a path.
+#[derive(Debug, Clone)]
+pub enum Target<V> {
+    /// A route or a fallback, as returned by `Router::search`.
+    Route(SearchResult<V>),
+    /// A redirect route.
+    Redirect(Redirect),
+}
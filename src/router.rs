@@ -0,0 +1,1712 @@
+use alloc::collections::BTreeMap;
+use core::fmt;
+use core::ops::RangeBounds;
+use alloc::borrow::Cow;
+use alloc::sync::Arc;
+use alloc::{format, string::{String, ToString}, vec::Vec};
+#[cfg(feature = "std")]
+use crate::cache::SearchCache;
+use crate::compiled::CompiledRouter;
+use crate::constraint::{ConstraintRegistry, ParamConstraints};
+use crate::explain::Explanation;
+use crate::extensions::Extensions;
+use crate::guard::{Guard, MatchContext};
+use crate::error::{ConflictKind, LimitError, PathForError, PatternError, RequestLineError, TransactionError, UpdateError};
+use crate::limits::Limits;
+use crate::method::{Method, MethodMap, MethodSet};
+use crate::normalize::{normalize, shadowing, DotSegments};
+use crate::pattern::{param_name_len, Automaton, RoutePattern, Segment, Syntax};
+use crate::redirect::{Redirect, RedirectKind, Target};
+use crate::scope::Scope;
+use crate::shadow::{ShadowedRoute, Shadowing};
+use crate::transaction::{Change, Transaction};
+use crate::query::{parse_request_line, split_uri, Query};
+use crate::weighted::Weighted;
+use crate::tree::{ConflictPolicy, Entry, Node, Params, Resolution, RouteChange, SearchOptions, SearchResult};
+
+/// A high-level router on top of the radix tree.
+///
+/// Routers can be composed from smaller routers with `nest`, which grafts
+/// every route of a sub router under a path prefix. Routes registered with a
+/// name can be turned back into URLs with `path_for`.
+#[derive(Debug, Clone)]
+pub struct Router<V> {
+    tree: Node<V>,
+    /// The patterns of the named routes
+    names: BTreeMap<String, String>,
+    /// The extensions of the routes, by pattern and method
+    extensions: BTreeMap<String, MethodMap<Arc<Extensions>>>,
+    /// The fallbacks of the prefixes, as routes of their methods
+    fallbacks: Node<V>,
+    /// The first pattern of the alias group of each other pattern, by method
+    aliases: BTreeMap<String, MethodMap<String>>,
+    /// The redirect routes, as `Method::ANY` routes to their target
+    redirects: Node<(RoutePattern, RedirectKind)>,
+    /// The rewrite rules, as `Method::ANY` routes to their target
+    rewrites: Node<RoutePattern>,
+    options: SearchOptions,
+    conflict_policy: ConflictPolicy,
+    limits: Limits,
+    /// The syntax of the patterns passed to the router
+    syntax: Syntax,
+    hooks: Hooks,
+    /// The constraints which patterns can reference, by name
+    constraints: ConstraintRegistry,
+    /// Whether a route has a guard or constrained parameters, so that
+    /// searches evaluate them
+    guarded: bool,
+    #[cfg(feature = "std")]
+    cache: Option<SearchCache>,
+}
+
+/// The kind of change of a route, which is passed to the hooks of a router.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteEvent {
+    Insert,
+    Remove,
+    Update,
+}
+
+type Hook = Arc<dyn Fn(RouteEvent, &Method, &str) + Send + Sync>;
+
+/// Receives the outcome of every search of a router, such as to count the
+/// hits of each route.
+pub trait Metrics: Send + Sync {
+    /// Called when a search matches a route, with the pattern of the route.
+    fn on_match(&self, method: &Method, pattern: &str);
+
+    /// Called when a search matches no route, with the searched path.
+    fn on_miss(&self, method: &Method, path: &str) {
+        let _ = (method, path);
+    }
+}
+
+/// The hooks of a router, called after every change of a route, and the
+/// metrics called after every search.
+#[derive(Clone, Default)]
+struct Hooks {
+    changes: Vec<Hook>,
+    metrics: Option<Arc<dyn Metrics>>,
+}
+
+impl Hooks {
+    fn notify(&self, event: RouteEvent, method: &Method, path: &str) {
+        if self.changes.is_empty() {
+            return;
+        }
+
+        let pattern = with_start_slash(path);
+        for hook in self.changes.iter() {
+            hook(event, method, &pattern);
+        }
+    }
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Hooks({}, metrics: {})", self.changes.len(), self.metrics.is_some())
+    }
+}
+
+impl<V: Clone> Router<V> {
+    pub fn new() -> Self {
+        Self {
+            tree: Node::new(),
+            names: BTreeMap::new(),
+            extensions: BTreeMap::new(),
+            fallbacks: Node::default(),
+            aliases: BTreeMap::new(),
+            redirects: Node::default(),
+            rewrites: Node::default(),
+            options: SearchOptions::default(),
+            conflict_policy: ConflictPolicy::default(),
+            limits: Limits::default(),
+            syntax: Syntax::default(),
+            hooks: Hooks::default(),
+            constraints: ConstraintRegistry::default(),
+            guarded: false,
+            #[cfg(feature = "std")]
+            cache: None,
+        }
+    }
+
+    /// Sets whether a HEAD search falls back to the GET handler when the path
+    /// has no HEAD handler. It is disabled by default.
+    pub fn set_head_can_use_get(&mut self, enabled: bool) {
+        self.invalidate();
+        self.options.head_can_use_get = enabled;
+    }
+
+    /// Sets how paths with `.` and `..` segments are searched, which is
+    /// `DotSegments::Keep` by default. Normalizing or rejecting them keeps
+    /// paths such as `/static/../secret` from matching a wildcard route of
+    /// static files.
+    pub fn set_dot_segments(&mut self, dot_segments: DotSegments) {
+        self.invalidate();
+        self.options.dot_segments = dot_segments;
+    }
+
+    /// Sets whether each run of slashes in a searched path is merged into a
+    /// single slash, so `//users///42` matches `/users/42`, as proxies often
+    /// double slashes. It is disabled by default.
+    pub fn set_merge_slashes(&mut self, enabled: bool) {
+        self.invalidate();
+        self.options.merge_slashes = enabled;
+    }
+
+    /// Sets the function normalizing patterns when they are inserted and paths
+    /// when they are searched to a Unicode normalization form, so that paths
+    /// which only differ in the composition of their characters, such as `é`
+    /// and `e` followed by a combining accent, match the same route. It may be
+    /// the NFC function of a crate such as `unicode-normalization`, which
+    /// should return the text borrowed when it is already normalized.
+    ///
+    /// The routes registered before aren't normalized, and frozen routers
+    /// don't normalize paths.
+    pub fn set_unicode_normalizer(&mut self, normalizer: fn(&str) -> Cow<'_, str>) {
+        self.invalidate();
+        self.options.unicode = Some(normalizer);
+    }
+
+    /// Sets what happens when a route conflicts with a registered route. It
+    /// is `ConflictPolicy::Panic` by default.
+    pub fn set_conflict_policy(&mut self, policy: ConflictPolicy) {
+        self.conflict_policy = policy;
+    }
+
+    /// Sets the limits on the inserted patterns and the searched paths. The
+    /// registered routes aren't checked again.
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Sets the syntax of the patterns passed to the router, such as
+    /// `Syntax::Sigils { param: ':', wildcard: '*' }` for `/users/:id`. It is
+    /// `Syntax::Native` by default.
+    ///
+    /// The prefixes of `remove_prefix`, `split_off` and `iter_prefix`, and the
+    /// patterns returned by the router or passed to its hooks, are in the
+    /// native syntax.
+    pub fn set_syntax(&mut self, syntax: Syntax) {
+        self.syntax = syntax;
+    }
+
+    /// Caches the results of up to `capacity` searches, evicting a result which
+    /// wasn't used recently when the cache is full. The cache is emptied by
+    /// every change of the router, and a capacity of 0 disables it, which is
+    /// the default.
+    ///
+    /// It speeds up workloads where a few paths make most of the searches.
+    /// The cache is sharded, and searches served from it only take a read
+    /// lock, so concurrent searches don't wait for each other.
+    #[cfg(feature = "std")]
+    pub fn set_cache_capacity(&mut self, capacity: usize) {
+        self.cache = (capacity > 0).then(|| SearchCache::new(capacity));
+    }
+
+    /// Registers a hook which is called with the method and pattern of every
+    /// route inserted, removed or updated through the router afterwards.
+    ///
+    /// Changes made through `entry` aren't reported.
+    pub fn on_change<F: Fn(RouteEvent, &Method, &str) + Send + Sync + 'static>(&mut self, hook: F) {
+        self.hooks.changes.push(Arc::new(hook));
+    }
+
+    /// Sets the metrics, which are called with the outcome of every search
+    /// through the router afterwards. Searches made by `explain` or through a
+    /// compiled router aren't reported.
+    pub fn set_metrics(&mut self, metrics: Arc<dyn Metrics>) {
+        self.hooks.metrics = Some(metrics);
+    }
+
+    /// Inserts a value, resolving conflicts with the conflict policy.
+    pub fn insert(&mut self, method: Method, path: &str, value: V) -> Result<(), ConflictKind> {
+        let path = self.native(path);
+        self.insert_with(&[method], &path, value)?;
+        Ok(())
+    }
+
+    /// Inserts a value for a pattern in another syntax than the syntax of the
+    /// router, such as an axum or actix-web route, which eases migrating
+    /// routes from other frameworks. A pattern which can't be translated is
+    /// an `InvalidPattern` error.
+    pub fn insert_compat(&mut self, syntax: Syntax, method: Method, path: &str, value: V) -> Result<(), ConflictKind> {
+        let path = syntax.translate(path).map_err(ConflictKind::InvalidPattern)?;
+        let path = self.normalize_unicode(path);
+        self.insert_with(&[method], &path, value)?;
+        Ok(())
+    }
+
+    /// Inserts the same value for each of the methods, such as `Method::GET | Method::POST`.
+    pub fn insert_methods(&mut self, methods: impl Into<MethodSet>, path: &str, value: V) -> Result<(), ConflictKind> {
+        let methods: Vec<Method> = methods.into().iter().collect();
+        let path = self.native(path);
+        self.insert_with(&methods, &path, value)?;
+        Ok(())
+    }
+
+    /// Inserts the value under a native path, adding the extensions to those
+    /// of the routes whose value was set.
+    pub(crate) fn insert_extended(&mut self, methods: &[Method], path: &str, value: V, extensions: &Extensions) -> Result<(), ConflictKind> {
+        let set_methods = self.insert_with(methods, path, value)?;
+        if extensions.is_empty() {
+            return Ok(());
+        }
+
+        self.guarded |= extensions.contains::<Guard>();
+        let (path, _) = self.constraints.split(path);
+        for method in set_methods {
+            if let Some(route) = self.native_extensions_mut(method, &path) {
+                route.extend(extensions.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Inserts the value, returning the methods whose value was set. The
+    /// constraints referenced by the path are set on those routes.
+    fn insert_with(&mut self, methods: &[Method], path: &str, value: V) -> Result<Vec<Method>, ConflictKind> {
+        let (path, constraints) = self.constraints.split(path);
+        self.limits.check_path(&path).map_err(ConflictKind::LimitExceeded)?;
+        let is_new = self.limits.has_max_depth() && !self.tree.contains_path(&path);
+        self.invalidate();
+        let set_methods = self.tree.insert_with(methods, &path, value, self.conflict_policy)?;
+        if is_new {
+            if let Err(error) = self.limits.check_depth(self.tree.depth()) {
+                self.tree.remove(&path);
+                return Err(ConflictKind::LimitExceeded(error));
+            }
+        }
+
+        for method in set_methods.iter() {
+            self.hooks.notify(RouteEvent::Insert, method, &path);
+            if !constraints.is_empty() {
+                if let Some(extensions) = self.native_extensions_mut(method.clone(), &path) {
+                    extensions.insert(constraints.clone());
+                }
+                self.guarded = true;
+            }
+        }
+
+        Ok(set_methods)
+    }
+
+    /// Registers a constraint which patterns can reference after a parameter,
+    /// such as `uuid` in `/users/$id:uuid`. A route with constrained
+    /// parameters matches a path only when the value of each of them
+    /// satisfies its constraint, and matching goes on with the next candidate
+    /// otherwise, like it does for guards. Compiled routers ignore constraints.
+    ///
+    /// Only the patterns inserted afterwards can reference the constraint,
+    /// and a reference to a constraint which isn't registered is static text.
+    /// The other methods take patterns without the references.
+    pub fn register_constraint<F>(&mut self, name: &str, constraint: F)
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        assert!(!name.is_empty() && param_name_len(name) == name.len(), "Invalid constraint name {:?}.", name);
+        self.constraints.register(name, constraint);
+    }
+
+    /// Inserts a value, replacing the existing value of the method instead of panicking.
+    ///
+    /// Returns the replaced value, if any.
+    pub fn insert_replace(&mut self, method: Method, path: &str, value: V) -> Option<V> {
+        let path = self.native(path);
+        self.invalidate();
+        let replaced = self.tree.insert_replace(method.clone(), &path, value);
+        let event = if replaced.is_some() { RouteEvent::Update } else { RouteEvent::Insert };
+        self.hooks.notify(event, &method, &path);
+        replaced
+    }
+
+    /// Returns whether inserting the method and path would conflict with an
+    /// existing route, without changing the router.
+    pub fn would_conflict(&self, method: Method, path: &str) -> Option<ConflictKind> {
+        self.tree.would_conflict(method, &self.native(path))
+    }
+
+    /// Gets the entry of the method and path for in-place manipulation, or
+    /// the conflict of the pattern like `Node::entry`.
+    pub fn entry(&mut self, method: Method, path: &str) -> Result<Entry<'_, V>, ConflictKind> {
+        let path = self.try_native(path).map_err(ConflictKind::InvalidPattern)?;
+        self.invalidate();
+        self.tree.entry(method, &path)
+    }
+
+    /// Inserts a route and registers its pattern under `name`.
+    ///
+    /// Returns `ConflictKind::NameTaken` if the name is already used by
+    /// another pattern, without inserting the route.
+    pub fn insert_named(&mut self, method: Method, name: &str, path: &str, value: V) -> Result<(), ConflictKind> {
+        let path = with_start_slash(&self.native(path));
+        let pattern = self.constraints.split(&path).0.into_owned();
+        self.check_name(name, &pattern)?;
+
+        self.insert_with(&[method], &path, value)?;
+        self.names.insert(name.to_string(), pattern);
+        Ok(())
+    }
+
+    /// Returns `ConflictKind::NameTaken` if the name is used by a pattern
+    /// other than `pattern`.
+    fn check_name(&self, name: &str, pattern: &str) -> Result<(), ConflictKind> {
+        match self.names.get(name) {
+            Some(existing) if existing != pattern => Err(ConflictKind::NameTaken(existing.clone())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the pattern of the route named `name`, with the method and
+    /// value of each of its handlers.
+    pub fn get_by_name(&self, name: &str) -> Option<(&str, Vec<(&Method, &V)>)> {
+        let pattern = self.names.get(name)?;
+        let handlers = self.tree.handlers(pattern)?;
+        Some((pattern.as_str(), handlers.iter().collect()))
+    }
+
+    /// Removes every method of the path.
+    ///
+    /// Returns the removed handlers, or None if the path isn't registered.
+    pub fn remove(&mut self, path: &str) -> Option<Vec<(Method, V)>> {
+        let path = self.native(path);
+        self.remove_native(&path)
+    }
+
+    /// Inserts the same value for the method under each of the patterns, as a
+    /// single route listed once by `iter`, under the first pattern. Removing
+    /// the route under any of the patterns removes it under the others too.
+    ///
+    /// The patterns are checked together: nothing is inserted when any of
+    /// them conflicts with another one, or with a registered route unless the
+    /// conflict policy overwrites or ignores it.
+    pub fn insert_alias(&mut self, patterns: &[&str], method: Method, value: V) -> Result<(), ConflictKind> {
+        let paths: Vec<String> = patterns.iter().map(|pattern| with_start_slash(&self.native(pattern))).collect();
+        let patterns: Vec<String> = paths.iter().map(|path| self.constraints.split(path).0.into_owned()).collect();
+        let Some((primary, aliases)) = patterns.split_first() else {
+            return Ok(());
+        };
+
+        let mut group = Node::new();
+        for pattern in patterns.iter() {
+            if let Some(kind) = group.would_conflict(method.clone(), pattern) {
+                return Err(kind);
+            }
+            group.insert(method.clone(), pattern, ());
+
+            match self.tree.would_conflict(method.clone(), pattern) {
+                Some(ConflictKind::Duplicate | ConflictKind::AmbiguousParams(_))
+                    if matches!(self.conflict_policy, ConflictPolicy::Overwrite | ConflictPolicy::Ignore) => {}
+                Some(kind) => return Err(kind),
+                None => {}
+            }
+        }
+
+        for path in paths.iter() {
+            self.insert_with(core::slice::from_ref(&method), path, value.clone())?;
+        }
+
+        let Some(primary) = self.tree.registered_pattern(&method, primary) else {
+            return Ok(());
+        };
+        for alias in aliases {
+            if let Some(alias) = self.tree.registered_pattern(&method, alias) {
+                self.aliases.entry(alias).or_default().insert(method.clone(), primary.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Registers the aliases of a nested or merged router under a native
+    /// prefix, for the routes registered under both patterns.
+    fn adopt_aliases(&mut self, prefix: &str, aliases: BTreeMap<String, MethodMap<String>>) {
+        for (alias, primaries) in aliases {
+            for (method, primary) in primaries.iter() {
+                let alias = self.tree.registered_pattern(method, &join_prefix(prefix, &alias));
+                let primary = self.tree.registered_pattern(method, &join_prefix(prefix, primary));
+                if let (Some(alias), Some(primary)) = (alias, primary) {
+                    self.aliases.entry(alias).or_default().insert(method.clone(), primary);
+                }
+            }
+        }
+    }
+
+    /// Returns true if the route is listed under another pattern of its alias
+    /// group.
+    fn is_alias(&self, method: &Method, pattern: &str) -> bool {
+        self.aliases.get(pattern).is_some_and(|primaries| primaries.contains_key(method))
+    }
+
+    /// Returns the other patterns of the alias groups of the methods of a
+    /// native path, by method.
+    fn alias_siblings(&self, path: &str) -> Vec<(Method, String)> {
+        let mut siblings = Vec::new();
+        if self.aliases.is_empty() {
+            return siblings;
+        }
+
+        let methods = self.tree.handlers(path).into_iter().flat_map(|handlers| handlers.keys());
+        for method in methods {
+            let Some(pattern) = self.tree.registered_pattern(method, path) else {
+                continue;
+            };
+
+            let primary = self.aliases.get(&pattern).and_then(|primaries| primaries.get(method)).unwrap_or(&pattern);
+            if *primary != pattern {
+                siblings.push((method.clone(), primary.clone()));
+            }
+
+            for (alias, primaries) in self.aliases.iter() {
+                if *alias != pattern && primaries.get(method) == Some(primary) {
+                    siblings.push((method.clone(), alias.clone()));
+                }
+            }
+        }
+
+        siblings
+    }
+
+    /// Removes the alias siblings of the removed routes, and then drops what
+    /// belonged to the removed routes.
+    fn remove_siblings<T>(&mut self, siblings: Vec<(Method, String)>, removed: &[(Method, T)]) {
+        for (method, sibling) in siblings {
+            if removed.iter().any(|(removed, _)| *removed == method) && self.tree.remove_method(method.clone(), &sibling).is_some() {
+                self.hooks.notify(RouteEvent::Remove, &method, &sibling);
+            }
+        }
+
+        self.prune_removed();
+    }
+
+    /// Removes every method of the route named `name`, and its name.
+    ///
+    /// Returns the removed handlers, or None if no route has the name.
+    pub fn remove_by_name(&mut self, name: &str) -> Option<Vec<(Method, V)>> {
+        let pattern = self.names.get(name)?.clone();
+        self.remove_native(&pattern)
+    }
+
+    fn remove_native(&mut self, path: &str) -> Option<Vec<(Method, V)>> {
+        self.invalidate();
+        let siblings = self.alias_siblings(path);
+        let removed = self.tree.remove(path);
+
+        if let Some(ref removed) = removed {
+            let pattern = with_start_slash(path);
+            self.names.retain(|_, v| *v != pattern);
+
+            for (method, _) in removed.iter() {
+                self.hooks.notify(RouteEvent::Remove, method, path);
+            }
+            self.remove_siblings(siblings, removed);
+        }
+
+        removed
+    }
+
+    /// Removes every route whose pattern starts with `prefix`, and the names
+    /// and extensions of the removed routes.
+    ///
+    /// Returns the method, pattern and value of the removed routes.
+    pub fn remove_prefix(&mut self, prefix: &str) -> Vec<(Method, String, V)> {
+        self.invalidate();
+        let removed = self.tree.remove_prefix(prefix);
+
+        let tree = &self.tree;
+        self.names.retain(|_, pattern| tree.contains_path(pattern));
+        self.prune_removed();
+
+        for (method, pattern, _) in removed.iter() {
+            self.hooks.notify(RouteEvent::Remove, method, pattern);
+        }
+        removed
+    }
+
+    /// Moves every route whose pattern starts with `prefix` into a new router
+    /// with the same options. The names and extensions of the moved routes move
+    /// with them.
+    pub fn split_off(&mut self, prefix: &str) -> Self {
+        self.invalidate();
+        let tree = self.tree.split_off(prefix);
+        let (names, kept) = core::mem::take(&mut self.names)
+            .into_iter()
+            .partition(|(_, pattern)| tree.contains_path(pattern));
+        self.names = kept;
+        let (extensions, kept) = core::mem::take(&mut self.extensions)
+            .into_iter()
+            .partition(|(pattern, _)| tree.contains_path(pattern));
+        self.extensions = kept;
+        let (aliases, kept) = core::mem::take(&mut self.aliases)
+            .into_iter()
+            .partition(|(pattern, _)| tree.contains_path(pattern));
+        self.aliases = kept;
+        self.prune_removed();
+
+        for (method, pattern, _) in tree.iter_prefix("") {
+            self.hooks.notify(RouteEvent::Remove, method, &pattern);
+        }
+
+        let mut router = Self {
+            tree,
+            names,
+            extensions,
+            fallbacks: Node::default(),
+            aliases,
+            redirects: Node::default(),
+            rewrites: Node::default(),
+            options: self.options.clone(),
+            conflict_policy: self.conflict_policy,
+            limits: self.limits,
+            syntax: self.syntax,
+            hooks: Hooks::default(),
+            constraints: self.constraints.clone(),
+            guarded: self.guarded,
+            #[cfg(feature = "std")]
+            cache: self.cache.clone(),
+        };
+        router.prune_removed();
+        router
+    }
+
+    /// Removes the handlers of the methods, keeping the other methods of the path.
+    ///
+    /// Returns the removed handlers, or None if none of the methods is registered.
+    pub fn remove_methods(&mut self, methods: impl Into<MethodSet>, path: &str) -> Option<Vec<(Method, V)>> {
+        let path = self.native(path);
+        self.invalidate();
+        let siblings = self.alias_siblings(&path);
+        let removed = self.tree.remove_methods(methods.into(), &path);
+
+        if let Some(ref removed) = removed {
+            for (method, _) in removed.iter() {
+                self.hooks.notify(RouteEvent::Remove, method, &path);
+            }
+            self.remove_siblings(siblings, removed);
+        }
+
+        removed
+    }
+
+    /// Removes the handler of the method, keeping the other methods of the path.
+    ///
+    /// Returns the removed value, if any.
+    pub fn remove_method(&mut self, method: Method, path: &str) -> Option<V> {
+        let path = self.native(path);
+        self.invalidate();
+        let siblings = self.alias_siblings(&path);
+        let removed = self.tree.remove_method(method.clone(), &path);
+
+        if let Some(ref value) = removed {
+            self.hooks.notify(RouteEvent::Remove, &method, &path);
+            self.remove_siblings(siblings, &[(method, value)]);
+        }
+
+        removed
+    }
+
+    /// Keeps only the routes for which `f` returns true, given the method,
+    /// pattern and value of each route. Names and extensions of removed routes
+    /// are dropped.
+    pub fn retain<F: FnMut(&Method, &str, &V) -> bool>(&mut self, mut f: F) {
+        self.invalidate();
+        let mut removed = Vec::new();
+        self.tree.retain(|method, pattern, value| {
+            let keep = f(method, pattern, value);
+            if !keep {
+                removed.push((method.clone(), pattern.to_string()));
+            }
+            keep
+        });
+
+        let tree = &self.tree;
+        self.names.retain(|_, pattern| tree.contains_path(pattern));
+        self.prune_removed();
+
+        for (method, pattern) in removed {
+            self.hooks.notify(RouteEvent::Remove, &method, &pattern);
+        }
+    }
+
+    /// Removes every route, with its name and extensions.
+    pub fn clear(&mut self) {
+        let removed: Vec<(Method, String)> = self.tree.iter_prefix("")
+            .map(|(method, pattern, _)| (method.clone(), pattern))
+            .collect();
+
+        self.invalidate();
+        self.tree.clear();
+        self.names.clear();
+        self.extensions.clear();
+        self.aliases.clear();
+
+        for (method, pattern) in removed {
+            self.hooks.notify(RouteEvent::Remove, &method, &pattern);
+        }
+    }
+
+    /// Updates the value of the method, returning the old value.
+    pub fn update(&mut self, method: Method, path: &str, value: V) -> Result<V, UpdateError> {
+        let path = self.native(path);
+        self.update_native(method, &path, value)
+    }
+
+    /// Updates the value of the method of the route named `name`, returning
+    /// the old value.
+    pub fn update_by_name(&mut self, name: &str, method: Method, value: V) -> Result<V, UpdateError> {
+        let pattern = self.names.get(name).ok_or_else(|| UpdateError::UnknownName(name.to_string()))?.clone();
+        self.update_native(method, &pattern, value)
+    }
+
+    fn update_native(&mut self, method: Method, path: &str, value: V) -> Result<V, UpdateError> {
+        self.invalidate();
+        let old = self.tree.update(method.clone(), path, value)?;
+        self.hooks.notify(RouteEvent::Update, &method, path);
+        Ok(old)
+    }
+
+    /// Updates the value of each of the methods, returning the old values in
+    /// the iteration order of the methods.
+    pub fn update_methods(&mut self, methods: impl Into<MethodSet>, path: &str, value: V) -> Result<Vec<V>, UpdateError> {
+        let methods = methods.into();
+        let path = self.native(path);
+        self.invalidate();
+        let updated = self.tree.update_methods(methods, &path, value)?;
+        for method in methods.iter() {
+            self.hooks.notify(RouteEvent::Update, &method, &path);
+        }
+
+        Ok(updated)
+    }
+
+    /// Starts a scope, which registers routes under `prefix` with shared
+    /// extensions and methods, such as `router.scope("/admin").extension(Role::Admin)`.
+    /// The prefix is in the syntax of the router.
+    pub fn scope(&mut self, prefix: &str) -> Scope<'_, V> {
+        let prefix = with_start_slash(&self.native(prefix)).trim_end_matches('/').to_string();
+        Scope::new(self, prefix)
+    }
+
+    /// Starts a transaction, which stages changes and applies them together.
+    pub fn transaction(&mut self) -> Transaction<'_, V> {
+        Transaction::new(self)
+    }
+
+    /// Applies the changes of a transaction to a copy of the router, which
+    /// replaces the router once every change has succeeded. The copy takes
+    /// the translated patterns, so it has the native syntax.
+    pub(crate) fn apply(&mut self, changes: Vec<Change<V>>) -> Result<(), TransactionError> {
+        let mut staged = Router {
+            tree: self.tree.clone(),
+            names: self.names.clone(),
+            extensions: self.extensions.clone(),
+            fallbacks: self.fallbacks.clone(),
+            aliases: self.aliases.clone(),
+            redirects: self.redirects.clone(),
+            rewrites: self.rewrites.clone(),
+            options: self.options.clone(),
+            // Conflicts are returned rather than panicking.
+            conflict_policy: match self.conflict_policy {
+                ConflictPolicy::Panic => ConflictPolicy::Error,
+                policy => policy,
+            },
+            limits: self.limits,
+            syntax: Syntax::Native,
+            hooks: Hooks::default(),
+            constraints: self.constraints.clone(),
+            guarded: self.guarded,
+            #[cfg(feature = "std")]
+            cache: None,
+        };
+
+        let mut events = Vec::new();
+        for (index, change) in changes.into_iter().enumerate() {
+            let native = |path: &str| {
+                self.try_native(path)
+                    .map(Cow::into_owned)
+                    .map_err(|error| TransactionError::InvalidPattern(index, error))
+            };
+            match change {
+                Change::Insert(method, path, value) => {
+                    let path = native(&path)?;
+                    let set_methods = staged.insert_with(&[method], &path, value).map_err(|kind| match kind {
+                        ConflictKind::InvalidPattern(error) => TransactionError::InvalidPattern(index, error),
+                        kind => TransactionError::Conflict(index, kind),
+                    })?;
+                    let (path, _) = staged.constraints.split(&path);
+                    events.extend(set_methods.into_iter().map(|method| (RouteEvent::Insert, method, path.to_string())));
+                }
+                Change::Remove(path) => {
+                    let path = native(&path)?;
+                    let removed = staged.remove(&path).ok_or(TransactionError::NotFound(index))?;
+                    events.extend(removed.into_iter().map(|(method, _)| (RouteEvent::Remove, method, path.clone())));
+                }
+                Change::RemoveMethod(method, path) => {
+                    let path = native(&path)?;
+                    staged.remove_method(method.clone(), &path).ok_or(TransactionError::NotFound(index))?;
+                    events.push((RouteEvent::Remove, method, path));
+                }
+                Change::Update(method, path, value) => {
+                    let path = native(&path)?;
+                    staged.update(method.clone(), &path, value).map_err(|error| TransactionError::Update(index, error))?;
+                    events.push((RouteEvent::Update, method, path));
+                }
+            }
+        }
+
+        self.invalidate();
+        self.tree = staged.tree;
+        self.names = staged.names;
+        self.extensions = staged.extensions;
+        self.aliases = staged.aliases;
+        self.guarded = staged.guarded;
+        for (event, method, path) in events {
+            self.hooks.notify(event, &method, &path);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the registered patterns which start with `prefix`, sorted.
+    pub fn complete(&self, prefix: &str) -> Vec<String> {
+        self.tree.complete(prefix)
+    }
+
+    /// Returns the registered patterns within `max_distance` segments of the
+    /// path, closest first. See `Node::suggest`.
+    pub fn suggest(&self, path: &str, max_distance: usize) -> Vec<String> {
+        self.tree.suggest(path, max_distance)
+    }
+
+    /// Returns every route in the lexicographic order of the patterns.
+    pub fn iter(&self) -> impl Iterator<Item = (&Method, String, &V)> {
+        self.tree.iter().filter(|(method, pattern, _)| !self.is_alias(method, pattern))
+    }
+
+    /// Returns the routes whose pattern is in the range, in the lexicographic
+    /// order of the patterns.
+    pub fn range<'r, R: RangeBounds<&'r str>>(&self, range: R) -> impl Iterator<Item = (&Method, String, &V)> {
+        self.tree.range(range).filter(|(method, pattern, _)| !self.is_alias(method, pattern))
+    }
+
+    /// Returns the routes whose pattern starts with `prefix`, as the method,
+    /// pattern and value of each route.
+    pub fn iter_prefix(&self, prefix: &str) -> impl Iterator<Item = (&Method, String, &V)> {
+        self.tree.iter_prefix(prefix).filter(|(method, pattern, _)| !self.is_alias(method, pattern))
+    }
+
+    /// Returns Some(SearchResult<V>), if successful. Otherwise returns None.
+    pub fn search(&self, method: Method, path: &str) -> Option<SearchResult<V>> {
+        self.search_with_context(method, path, &Extensions::new())
+    }
+
+    /// Searches like `search`, passing the request data to the guards of the
+    /// routes, such as the headers or the host of the request.
+    pub fn search_with_context(&self, method: Method, path: &str, request: &Extensions) -> Option<SearchResult<V>> {
+        let Some(ref metrics) = self.hooks.metrics else {
+            return self.lookup(method, path, request);
+        };
+
+        let result = self.lookup(method.clone(), path, request);
+        match result {
+            Some(ref result) if !result.is_fallback() => metrics.on_match(&method, result.pattern()),
+            _ => metrics.on_miss(&method, path),
+        }
+        result
+    }
+
+    /// Searches like `search`, but returns an error instead of no match when
+    /// the path exceeds a limit of the router.
+    pub fn try_search(&self, method: Method, path: &str) -> Result<Option<SearchResult<V>>, LimitError> {
+        self.limits.check_path(path)?;
+        Ok(self.search(method, path))
+    }
+
+    /// Searches the path of a URI such as `/users/42?active=true`, returning
+    /// the pairs of its query string with the result. The fragment is ignored.
+    pub fn search_uri(&self, method: Method, uri: &str) -> Option<(SearchResult<V>, Query)> {
+        let (path, query) = split_uri(uri);
+        let result = self.search(method, path)?;
+        Some((result, Query::parse(query)))
+    }
+
+    /// Searches the method and the path of an HTTP request. The path of an
+    /// absolute-form target such as `http://example.com/users` is used, while
+    /// the authority form of `CONNECT` and the `*` of `OPTIONS` match nothing.
+    #[cfg(feature = "http")]
+    pub fn route_request<B>(&self, req: &http::Request<B>) -> Option<SearchResult<V>> {
+        let path = req.uri().path();
+        if !path.starts_with('/') {
+            return None;
+        }
+        self.search(Method::from(req.method()), path)
+    }
+
+    /// Parses an HTTP request line such as `GET /users/42 HTTP/1.1` and
+    /// searches its target like `search_uri`. The target may be in origin
+    /// form or in absolute form, while the authority form of `CONNECT` and the
+    /// `*` of `OPTIONS` are rejected.
+    pub fn search_request_line(&self, line: &str) -> Result<Option<(SearchResult<V>, Query)>, RequestLineError> {
+        let (method, target) = parse_request_line(line)?;
+        let (path, query) = split_uri(target);
+        let path = if path.is_empty() { "/" } else { path };
+        Ok(self.search(method, path).map(|result| (result, Query::parse(query))))
+    }
+
+    /// Searches the tree, attaching the extensions of the matched route, or
+    /// falls back to the fallback of the path.
+    fn lookup(&self, method: Method, path: &str, request: &Extensions) -> Option<SearchResult<V>> {
+        if self.limits.check_path(path).is_err() {
+            return None;
+        }
+
+        let normalized = normalize(path, self.options.unicode, self.options.merge_slashes, self.options.dot_segments)?;
+        let rewritten = self.rewrite(&normalized);
+        let path = rewritten.as_deref().unwrap_or(&normalized);
+
+        if self.extensions.is_empty() && self.fallbacks.is_empty() {
+            return self.search_tree(method, path);
+        }
+
+        let result = match self.guarded {
+            true => self.search_guarded(method.clone(), path, request),
+            false => self.search_tree(method.clone(), path),
+        };
+        match result {
+            Some(result) if self.extensions.is_empty() => Some(result),
+            Some(result) => {
+                let extensions = self.matched_extensions(&method, result.pattern());
+                Some(result.with_extensions(extensions))
+            }
+            None => self.search_fallback(method, path),
+        }
+    }
+
+    /// Searches the tree without the cache, skipping the routes whose guard
+    /// rejects the request or whose parameters don't satisfy their constraints.
+    fn search_guarded(&self, method: Method, path: &str, request: &Extensions) -> Option<SearchResult<V>> {
+        let accept = |pattern: &str, key: &Method, params: &Params| {
+            let Some(extensions) = self.extensions.get(pattern).and_then(|extensions| extensions.get(key)) else {
+                return true;
+            };
+
+            extensions.get::<ParamConstraints>().is_none_or(|constraints| constraints.check(params))
+                && extensions.get::<Guard>().is_none_or(|guard| guard.check(&MatchContext::new(&method, path, pattern, params, request)))
+        };
+        self.tree.search_filtered(method.clone(), path, &self.options, &accept)
+    }
+
+    /// Returns the fallback of the longest prefix of the path which has one
+    /// for the method or ANY.
+    fn search_fallback(&self, method: Method, path: &str) -> Option<SearchResult<V>> {
+        let (_, _, rest) = self.fallbacks.search_longest_prefix(method.clone(), path)?;
+        let prefix = &path[..path.len() - rest.len()];
+        self.fallbacks.search(method, prefix).map(SearchResult::into_fallback)
+    }
+
+    /// Searches like `search`, but returns the redirect of the redirect route
+    /// matching the path when no route matches it. Redirect routes take
+    /// precedence over fallbacks.
+    pub fn resolve(&self, method: Method, path: &str) -> Option<Target<V>> {
+        match self.search(method, path) {
+            Some(result) if !result.is_fallback() => Some(Target::Route(result)),
+            result => self.search_redirect(path).map(Target::Redirect).or(result.map(Target::Route)),
+        }
+    }
+
+    fn search_redirect(&self, path: &str) -> Option<Redirect> {
+        let result = self.redirects.search(Method::ANY, path)?;
+        let (target, kind) = result.value();
+        Some(Redirect {
+            location: fill_target(target, &result, Fill::EncodePath)?,
+            permanent: *kind == RedirectKind::Permanent,
+        })
+    }
+
+    /// Registers a redirect route from the paths matching `from` to `to`, for
+    /// every method, which `resolve` returns when no route matches a path. The
+    /// parameters of `to` are filled with the parameters of the same names in
+    /// the path, such as `insert_redirect("/users/$id", "/people/$id", kind)`.
+    ///
+    /// Both patterns are in the syntax of the router. An invalid pattern, or
+    /// a `to` with a `*` wildcard or a parameter which `from` doesn't have,
+    /// is returned as `ConflictKind::InvalidPattern`. The values filled in
+    /// the location are percent-encoded, keeping the escapes of the path.
+    pub fn insert_redirect(&mut self, from: &str, to: &str, kind: RedirectKind) -> Result<(), ConflictKind> {
+        let from = self.try_native(from).map_err(ConflictKind::InvalidPattern)?;
+        let target = parse_target(&from, &self.try_native(to).map_err(ConflictKind::InvalidPattern)?)?;
+        self.redirects.insert_with(&[Method::ANY], &from, (target, kind), self.conflict_policy)?;
+        Ok(())
+    }
+
+    /// Registers a rewrite rule, which makes `search` and `resolve` match the
+    /// paths matching `from` as the path `to`, whose parameters are filled
+    /// with the parameters of the same names in the path. The rules are
+    /// applied once, before matching, so a legacy path can reach the routes
+    /// of its new path, such as `insert_rewrite("/old/$id", "/new/$id")`.
+    ///
+    /// Both patterns are in the syntax of the router. An invalid pattern, or
+    /// a `to` with a `*` wildcard or a parameter which `from` doesn't have,
+    /// is returned as `ConflictKind::InvalidPattern`.
+    pub fn insert_rewrite(&mut self, from: &str, to: &str) -> Result<(), ConflictKind> {
+        let from = self.try_native(from).map_err(ConflictKind::InvalidPattern)?;
+        let target = parse_target(&from, &self.try_native(to).map_err(ConflictKind::InvalidPattern)?)?;
+        self.rewrites.insert_with(&[Method::ANY], &from, target, self.conflict_policy)?;
+        Ok(())
+    }
+
+    /// Removes the rewrite rule, returning its target if any.
+    pub fn remove_rewrite(&mut self, from: &str) -> Option<String> {
+        let from = self.native(from);
+        self.rewrites.remove_method(Method::ANY, &from).map(|target| target.to_string())
+    }
+
+    /// Returns the path rewritten by the rule matching it, if any.
+    fn rewrite(&self, path: &str) -> Option<String> {
+        if self.rewrites.is_empty() {
+            return None;
+        }
+
+        let result = self.rewrites.search(Method::ANY, path)?;
+        fill_target(result.value(), &result, Fill::Raw)
+    }
+
+    /// Removes the redirect route, returning its target if any.
+    pub fn remove_redirect(&mut self, from: &str) -> Option<String> {
+        let from = self.native(from);
+        self.redirects.remove_method(Method::ANY, &from).map(|(target, _)| target.to_string())
+    }
+
+    /// Returns the routes which no path can match, so that misconfigurations
+    /// surface at startup.
+    ///
+    /// Routes are matched by priority rather than in the order of their
+    /// registration, so a static route takes precedence over a wildcard
+    /// registered before it. A route is shadowed by another route of its
+    /// method, or of `Method::ANY`, which matches all of its paths and takes
+    /// precedence where they differ, such as `/$name/*` over `/*/posts`, or
+    /// when the searched paths are normalized or rewritten before matching,
+    /// such as the routes with a `//` when slashes are merged. Routes with a
+    /// guard or constraints don't shadow other routes, and paths with a `//`
+    /// are left out.
+    pub fn shadowed_routes(&self) -> Vec<ShadowedRoute> {
+        let routes: Vec<(&Method, String, RoutePattern, Automaton)> = self.tree.iter()
+            .filter_map(|(method, pattern, _)| {
+                let route = RoutePattern::parse(&pattern).ok()?;
+                let automaton = Automaton::new(&route, false);
+                Some((method, pattern, route, automaton))
+            })
+            .collect();
+
+        let mut shadowed = Vec::new();
+        for (method, pattern, route, automaton) in routes.iter() {
+            let shadowing_route = || routes.iter()
+                .find(|(other_method, other, _, other_automaton)| {
+                    (*other_method == *method || **other_method == Method::ANY)
+                        && other_automaton.shadows(automaton)
+                        && !self.is_filtered(other_method, other)
+                })
+                .map(|(_, other, _, _)| Shadowing::Route(other.clone()));
+
+            let cause = shadowing(route, self.options.merge_slashes, self.options.dot_segments)
+                .or_else(|| self.rewriting_rule(route).map(Shadowing::Rewritten))
+                .or_else(shadowing_route);
+            if let Some(cause) = cause {
+                shadowed.push(ShadowedRoute { method: (*method).clone(), pattern: pattern.clone(), cause });
+            }
+        }
+
+        shadowed
+    }
+
+    /// Returns true if the route has a guard or constrained parameters, which
+    /// may reject the paths it matches.
+    fn is_filtered(&self, method: &Method, pattern: &str) -> bool {
+        self.extensions.get(pattern)
+            .and_then(|extensions| extensions.get(method))
+            .is_some_and(|extensions| extensions.get::<Guard>().is_some() || extensions.get::<ParamConstraints>().is_some())
+    }
+
+    /// Returns the source of a rewrite rule which matches every path of the
+    /// route, when the rules rewrite them all to paths the route doesn't match.
+    fn rewriting_rule(&self, route: &RoutePattern) -> Option<String> {
+        let mut covering = None;
+        for (_, from, target) in self.rewrites.iter() {
+            let source = RoutePattern::parse(&from).ok()?;
+            if !source.overlaps(route) {
+                continue;
+            }
+            if !always_fills(&source, target) || target.may_fill(route) {
+                return None;
+            }
+            if covering.is_none() && source.covers(route) {
+                covering = Some(from);
+            }
+        }
+
+        covering
+    }
+
+    /// Sets the fallback of the method, which is returned by the searches of
+    /// the method that match no route, instead of None. The fallback of
+    /// `Method::ANY` applies to every method, and the fallbacks of prefixes
+    /// take precedence.
+    ///
+    /// Returns the replaced fallback, if any.
+    pub fn set_fallback(&mut self, method: Method, value: V) -> Option<V> {
+        self.invalidate();
+        self.fallbacks.insert_replace(method, "/", value)
+    }
+
+    /// Removes the fallback of the method, returning it if any.
+    pub fn unset_fallback(&mut self, method: Method) -> Option<V> {
+        self.invalidate();
+        self.fallbacks.remove_method(method, "/")
+    }
+
+    /// Sets the fallback of the prefix, which is returned by the searches of
+    /// the paths under the prefix that match no route, unless a longer prefix
+    /// has a fallback too. The prefix is in the syntax of the router and may
+    /// contain path parameters, such as `/users/$id`, and a fallback of `/`
+    /// applies to every path, like `set_fallback(Method::ANY, value)`.
+    ///
+    /// Returns the replaced fallback, if any.
+    pub fn insert_fallback(&mut self, prefix: &str, value: V) -> Option<V> {
+        let prefix = self.native(prefix);
+        self.invalidate();
+        self.fallbacks.insert_replace(Method::ANY, trim_prefix(&prefix), value)
+    }
+
+    /// Sets the fallback of the method at a native prefix, unless it has one.
+    fn keep_fallback(&mut self, method: Method, prefix: &str, value: V) {
+        let _ = self.fallbacks.insert_with(&[method], prefix, value, ConflictPolicy::Ignore);
+    }
+
+    /// Removes the fallback of the prefix, returning it if any.
+    pub fn remove_fallback(&mut self, prefix: &str) -> Option<V> {
+        let prefix = self.native(prefix);
+        self.invalidate();
+        self.fallbacks.remove_method(Method::ANY, trim_prefix(&prefix))
+    }
+
+    /// Searches the tree, or the cache when it is enabled.
+    fn search_tree(&self, method: Method, path: &str) -> Option<SearchResult<V>> {
+        #[cfg(feature = "std")]
+        if let Some(ref cache) = self.cache {
+            let matched = cache.get_or_search(&method, path, || self.tree.search_matched(method.clone(), path, &self.options))?;
+            return self.tree.matched_result(path, &matched);
+        }
+
+        self.tree.search_with(method, path, &self.options)
+    }
+
+    /// Returns the extensions of the route matched by a search of the method,
+    /// which may have fallen back to the GET or ANY handler of the pattern.
+    fn matched_extensions(&self, method: &Method, pattern: &str) -> Option<Arc<Extensions>> {
+        let extensions = self.extensions.get(pattern)?;
+        let get = (self.options.head_can_use_get && *method == Method::HEAD).then_some(Method::GET);
+        let matched = [Some(method.clone()), get, Some(Method::ANY)].into_iter()
+            .flatten()
+            .find(|method| self.tree.registered_pattern(method, pattern).is_some())?;
+        extensions.get(&matched).cloned()
+    }
+
+    /// Returns the extensions of the route, or None if the route isn't registered
+    /// or has no extensions.
+    pub fn extensions(&self, method: Method, path: &str) -> Option<&Extensions> {
+        let pattern = self.tree.registered_pattern(&method, &self.native(path))?;
+        self.extensions.get(&pattern)?.get(&method).map(|extensions| &**extensions)
+    }
+
+    /// Returns the extensions of the route, which are empty until a value is
+    /// inserted, or None if the route isn't registered. They are returned by
+    /// the searches matching the route, and dropped with the route.
+    pub fn extensions_mut(&mut self, method: Method, path: &str) -> Option<&mut Extensions> {
+        let path = self.native(path);
+        self.native_extensions_mut(method, &path)
+    }
+
+    fn native_extensions_mut(&mut self, method: Method, path: &str) -> Option<&mut Extensions> {
+        let pattern = self.tree.registered_pattern(&method, path)?;
+        let extensions = self.extensions.entry(pattern).or_default().get_or_insert(method, Arc::default());
+        Some(Arc::make_mut(extensions))
+    }
+
+    /// Sets the guard of a registered route, replacing its previous guard.
+    /// Returns false if the route isn't registered.
+    ///
+    /// Guards are evaluated by `search` and `search_with_context`, but not by
+    /// compiled routers, and searches skip the cache once a route has one.
+    pub fn set_guard<F>(&mut self, method: Method, path: &str, guard: F) -> bool
+    where
+        F: Fn(&MatchContext) -> bool + Send + Sync + 'static,
+    {
+        let Some(extensions) = self.extensions_mut(method, path) else {
+            return false;
+        };
+
+        extensions.insert(Guard::new(guard));
+        self.guarded = true;
+        true
+    }
+
+    /// Adds a validator of the value of a parameter of a registered route.
+    /// The route matches a path only when every validator of its parameters
+    /// accepts their values, and matching goes on with the next candidate
+    /// otherwise, so that a malformed id is not found rather than matched.
+    ///
+    /// Returns false if the route isn't registered or has no such parameter.
+    /// Compiled routers ignore validators.
+    pub fn add_validator<F>(&mut self, method: Method, path: &str, param: &str, validator: F) -> bool
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        let path = self.native(path);
+        let Some(pattern) = self.tree.registered_pattern(&method, &path) else {
+            return false;
+        };
+        if !RoutePattern::parse(&pattern).is_ok_and(|pattern| pattern.param_names().contains(&param)) {
+            return false;
+        }
+
+        let Some(extensions) = self.native_extensions_mut(method, &path) else {
+            return false;
+        };
+        match extensions.get_mut::<ParamConstraints>() {
+            Some(constraints) => constraints.push(param, validator),
+            None => {
+                let mut constraints = ParamConstraints::default();
+                constraints.push(param, validator);
+                extensions.insert(constraints);
+            }
+        }
+
+        self.guarded = true;
+        true
+    }
+
+    /// Removes the guard of a route, returning true if it had one.
+    pub fn remove_guard(&mut self, method: Method, path: &str) -> bool {
+        self.extensions_mut(method, path).is_some_and(|extensions| extensions.remove::<Guard>().is_some())
+    }
+
+    /// Sets the extensions of a registered route.
+    fn attach_extensions(&mut self, method: Method, path: &str, extensions: Arc<Extensions>) {
+        if let Some(pattern) = self.tree.registered_pattern(&method, path) {
+            self.guarded |= extensions.contains::<Guard>() || extensions.contains::<ParamConstraints>();
+            self.extensions.entry(pattern).or_default().insert(method, extensions);
+        }
+    }
+
+    fn detach_extensions(&mut self, method: &Method, path: &str) {
+        let Some(pattern) = self.tree.registered_pattern(method, path) else {
+            return;
+        };
+
+        if let Some(extensions) = self.extensions.get_mut(&pattern) {
+            extensions.remove(method);
+            if extensions.is_empty() {
+                self.extensions.remove(&pattern);
+            }
+        }
+    }
+
+    /// Drops the extensions and the aliases of the routes which are no longer
+    /// registered.
+    fn prune_removed(&mut self) {
+        let tree = &self.tree;
+        self.extensions.retain(|pattern, extensions| {
+            *extensions = extensions.drain()
+                .into_iter()
+                .filter(|(method, _)| tree.registered_pattern(method, pattern).is_some())
+                .collect();
+            !extensions.is_empty()
+        });
+
+        self.aliases.retain(|pattern, primaries| {
+            *primaries = primaries.drain()
+                .into_iter()
+                .filter(|(method, primary)| {
+                    tree.registered_pattern(method, pattern).is_some() && tree.registered_pattern(method, primary).is_some()
+                })
+                .collect();
+            !primaries.is_empty()
+        });
+    }
+
+    /// Empties the cache before a change of the router.
+    fn invalidate(&mut self) {
+        #[cfg(feature = "std")]
+        if let Some(ref mut cache) = self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Returns the route matching the longest part of the path which ends at a
+    /// segment boundary, with its value and the rest of the path. See
+    /// `Node::search_longest_prefix`.
+    pub fn search_longest_prefix<'p>(&self, method: Method, path: &'p str) -> Option<(String, &V, &'p str)> {
+        self.tree.longest_prefix_with(method, path, &self.options)
+    }
+
+    /// Searches like `search`, recording every decision of the search.
+    pub fn explain(&self, method: Method, path: &str) -> Explanation<V> {
+        self.tree.explain_with(method, path, &self.options)
+    }
+
+    /// Returns a hash of the methods and patterns of the routes, which doesn't
+    /// depend on the insertion order or the values.
+    pub fn fingerprint(&self) -> u64 {
+        self.tree.fingerprint()
+    }
+
+    /// Compiles the routes into a read-only router for faster lookups, which
+    /// keeps the search options of this router.
+    pub fn compile(&self) -> CompiledRouter<V> {
+        CompiledRouter::new(&self.tree, self.options.clone())
+    }
+
+    /// Returns every method which has a handler for the path, including HEAD
+    /// when it falls back to GET.
+    ///
+    /// The set contains `Method::ANY` if any method is accepted. Extension
+    /// methods aren't included.
+    pub fn allowed_methods(&self, path: &str) -> MethodSet {
+        let mut methods = self.tree.allowed_methods(path);
+        if self.options.head_can_use_get && methods.contains(&Method::GET) {
+            methods.insert(Method::HEAD);
+        }
+
+        methods
+    }
+
+    /// Grafts all routes of `router` under `prefix`.
+    ///
+    /// The prefix is in the syntax of the router and may contain path
+    /// parameters, such as `/users/$id`, whose names are merged with the
+    /// parameters of the nested routes. A nested `/` route is registered as
+    /// the prefix itself, and routes of `router` keep their names and the
+    /// extensions, unless the conflict policy keeps an existing route. The
+    /// fallbacks of `router` are nested too, keeping the existing fallbacks.
+    ///
+    /// Conflicts are resolved with the conflict policy. With
+    /// `ConflictPolicy::Error`, nothing is grafted when a route conflicts.
+    /// Nothing is grafted either when a route name of `router` is already
+    /// used by another pattern, which returns `ConflictKind::NameTaken`.
+    pub fn nest(&mut self, prefix: &str, router: Router<V>) -> Result<(), ConflictKind> {
+        let prefix = self.native(prefix);
+        let prefix = prefix.trim_end_matches('/');
+
+        let mut routes = Vec::new();
+        router.tree.for_each_route(&mut |method, pattern, value| {
+            let extensions = router.extensions.get(pattern).and_then(|extensions| extensions.get(method)).cloned();
+            routes.push((method.clone(), join_prefix(prefix, pattern), value.clone(), extensions));
+        });
+
+        if self.conflict_policy == ConflictPolicy::Error {
+            for (method, pattern, _, _) in routes.iter() {
+                if let Some(kind) = self.tree.would_conflict(method.clone(), pattern) {
+                    return Err(kind);
+                }
+            }
+        }
+        for (name, pattern) in router.names.iter() {
+            self.check_name(name, &join_prefix(prefix, pattern))?;
+        }
+
+        for (method, pattern, value, extensions) in routes {
+            let set_methods = self.insert_with(&[method], &pattern, value)?;
+            if let Some(extensions) = extensions {
+                for method in set_methods {
+                    self.attach_extensions(method, &pattern, extensions.clone());
+                }
+            }
+        }
+
+        for (method, pattern, value) in router.fallbacks.iter() {
+            self.keep_fallback(method.clone(), &join_prefix(prefix, &pattern), value.clone());
+        }
+        self.adopt_aliases(prefix, router.aliases);
+
+        // Named routes keep their names under the new prefix.
+        for (name, pattern) in router.names {
+            let pattern = join_prefix(prefix, &pattern);
+            self.names.insert(name, pattern);
+        }
+
+        Ok(())
+    }
+
+    /// Moves the routes of `router` into this router, calling `resolver` with
+    /// the existing and the incoming value of a route registered in both. The
+    /// named routes of `router` keep their names, and the routes which take
+    /// the incoming value take its extensions. The fallbacks of `router` are
+    /// added, keeping the existing fallbacks.
+    ///
+    /// Nothing is moved when the routers have ambiguous parameters or
+    /// wildcards, or when a route name of `router` is already used by another
+    /// pattern, which returns `ConflictKind::NameTaken`.
+    pub fn merge<F: FnMut(&V, &V) -> Resolution<V>>(&mut self, router: Router<V>, mut resolver: F) -> Result<(), ConflictKind> {
+        for (name, pattern) in router.names.iter() {
+            self.check_name(name, pattern)?;
+        }
+
+        // The routes are merged in this order, and the resolver is called for
+        // the existing ones.
+        let incoming: Vec<(Method, String, bool)> = router.tree.iter_prefix("")
+            .map(|(method, pattern, _)| {
+                let exists = self.tree.would_conflict(method.clone(), &pattern) == Some(ConflictKind::Duplicate);
+                (method.clone(), pattern, exists)
+            })
+            .collect();
+
+        let mut resolutions = Vec::new();
+        self.invalidate();
+        self.tree.merge(router.tree, |existing, incoming| {
+            let resolution = resolver(existing, incoming);
+            resolutions.push(!matches!(resolution, Resolution::Existing));
+            resolution
+        })?;
+
+        let mut resolutions = resolutions.into_iter();
+        for (method, pattern, exists) in incoming {
+            let event = if !exists {
+                RouteEvent::Insert
+            } else if resolutions.next() == Some(true) {
+                RouteEvent::Update
+            } else {
+                continue;
+            };
+
+            self.hooks.notify(event, &method, &pattern);
+            let extensions = router.extensions.get(&pattern).and_then(|extensions| extensions.get(&method));
+            match extensions {
+                Some(extensions) => self.attach_extensions(method, &pattern, extensions.clone()),
+                None => self.detach_extensions(&method, &pattern),
+            }
+        }
+
+        for (method, pattern, value) in router.fallbacks.iter() {
+            self.keep_fallback(method.clone(), &pattern, value.clone());
+        }
+        self.adopt_aliases("", router.aliases);
+
+        self.names.extend(router.names);
+
+        Ok(())
+    }
+
+    /// Generates the URL path of the route named `name`, filling its path
+    /// parameters with `params`. The value of the `*` wildcards is given with
+    /// the name `*`.
+    ///
+    /// Every parameter of the route must be given, and no others. The values
+    /// are percent-encoded, except the `/` of multi-segment parameters, and
+    /// only an optional `$path*` parameter can be empty.
+    pub fn path_for(&self, name: &str, params: &[(&str, &str)]) -> Result<String, PathForError> {
+        let pattern = self.names.get(name).ok_or_else(|| PathForError::UnknownRoute(name.to_string()))?;
+
+        let pattern = RoutePattern::parse(pattern).unwrap_or_default();
+
+        for (param, _) in params {
+            let expected = pattern.segments().iter().any(|segment| match segment {
+                Segment::Param(name) | Segment::Greedy { name, .. } => name == param,
+                Segment::Wildcard => *param == "*",
+                Segment::Static(_) => false,
+            });
+
+            if !expected {
+                return Err(PathForError::UnexpectedParam(param.to_string()));
+            }
+        }
+
+        fill_pattern(&pattern, params, Fill::Encode)
+    }
+}
+
+impl<V: Clone + PartialEq> Router<V> {
+    /// Returns the routes added, removed or changed in `other` compared with
+    /// this router, matching routes by method and pattern.
+    pub fn diff(&self, other: &Router<V>) -> Vec<RouteChange<V>> {
+        self.tree.diff(&other.tree)
+    }
+}
+
+impl<V: Clone> Router<Weighted<V>> {
+    /// Searches like `search`, and then picks one of the weighted values of
+    /// the route with `Weighted::pick`. `point` is a random number, or the
+    /// hash of a key for sticky picks, so the source of randomness is up to
+    /// the caller. Routes whose total weight is 0 don't match.
+    pub fn search_weighted(&self, method: Method, path: &str, point: u64) -> Option<SearchResult<V>> {
+        let result = self.search(method, path)?;
+        let value = result.value().pick(point)?.clone();
+        Some(result.map_value(|_| value))
+    }
+}
+
+impl<V> Router<V> {
+    /// Returns whether a HEAD search falls back to the GET handler.
+    pub fn head_can_use_get(&self) -> bool {
+        self.options.head_can_use_get
+    }
+
+    /// Returns what happens when a route conflicts with a registered route.
+    pub fn conflict_policy(&self) -> ConflictPolicy {
+        self.conflict_policy
+    }
+
+    pub fn merge_slashes(&self) -> bool {
+        self.options.merge_slashes
+    }
+
+    pub fn dot_segments(&self) -> DotSegments {
+        self.options.dot_segments
+    }
+
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Returns the capacity of the search cache, which is 0 when disabled.
+    #[cfg(feature = "std")]
+    pub fn cache_capacity(&self) -> usize {
+        self.cache.as_ref().map_or(0, |cache| cache.capacity())
+    }
+
+    /// Returns the syntax of the patterns passed to the router.
+    pub fn syntax(&self) -> Syntax {
+        self.syntax
+    }
+
+    /// Translates a pattern in the syntax of the router into the native
+    /// syntax. It panics on invalid patterns like inserting them.
+    pub(crate) fn native<'a>(&self, path: &'a str) -> Cow<'a, str> {
+        self.try_native(path).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Translates the pattern like `native`, returning the syntax error
+    /// instead of panicking.
+    fn try_native<'a>(&self, path: &'a str) -> Result<Cow<'a, str>, PatternError> {
+        Ok(self.normalize_unicode(self.syntax.translate(path)?))
+    }
+
+    fn normalize_unicode<'a>(&self, pattern: Cow<'a, str>) -> Cow<'a, str> {
+        let Some(normalizer) = self.options.unicode else {
+            return pattern;
+        };
+
+        match normalizer(&pattern) {
+            Cow::Owned(normalized) => Cow::Owned(normalized),
+            Cow::Borrowed(_) => pattern,
+        }
+    }
+
+    /// Returns the number of routes, counting each method of a path.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns true if the router has no routes.
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Returns the number of paths which have a handler for the method.
+    pub fn count_method(&self, method: Method) -> usize {
+        self.tree.count_method(method)
+    }
+
+    /// Returns the underlying radix tree.
+    pub fn tree(&self) -> &Node<V> {
+        &self.tree
+    }
+
+    /// Consumes the router, returning the underlying radix tree.
+    pub fn into_tree(self) -> Node<V> {
+        self.tree
+    }
+}
+
+/// Renders the radix structure of the routes, like `Node`.
+impl<V: Clone> fmt::Display for Router<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.tree, f)
+    }
+}
+
+impl<V: Clone> Default for Router<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> From<Node<V>> for Router<V> {
+    fn from(tree: Node<V>) -> Self {
+        Self {
+            tree,
+            names: BTreeMap::new(),
+            extensions: BTreeMap::new(),
+            fallbacks: Node::default(),
+            aliases: BTreeMap::new(),
+            redirects: Node::default(),
+            rewrites: Node::default(),
+            options: SearchOptions::default(),
+            conflict_policy: ConflictPolicy::default(),
+            limits: Limits::default(),
+            syntax: Syntax::default(),
+            hooks: Hooks::default(),
+            constraints: ConstraintRegistry::default(),
+            guarded: false,
+            #[cfg(feature = "std")]
+            cache: None,
+        }
+    }
+}
+
+pub(crate) fn with_start_slash(path: &str) -> String {
+    if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{}", path)
+    }
+}
+
+/// Trims the trailing `/` of a prefix, except for the root.
+fn trim_prefix(prefix: &str) -> &str {
+    match prefix.trim_end_matches('/') {
+        "" => "/",
+        prefix => prefix,
+    }
+}
+
+pub(crate) fn join_prefix(prefix: &str, pattern: &str) -> String {
+    if pattern == "/" && !prefix.is_empty() {
+        prefix.to_string()
+    } else {
+        format!("{}{}", prefix, pattern)
+    }
+}
+
+/// Parses the target of a redirect or rewrite rule, checking that it only
+/// has parameters of the source pattern.
+fn parse_target(from: &str, to: &str) -> Result<RoutePattern, ConflictKind> {
+    let source = RoutePattern::parse(from).map_err(ConflictKind::InvalidPattern)?;
+    let target = RoutePattern::parse(to).map_err(ConflictKind::InvalidPattern)?;
+
+    let names = source.param_names();
+    for segment in target.segments() {
+        let message = match segment {
+            Segment::Param(name) | Segment::Greedy { name, .. } if !names.contains(&name.as_str()) => {
+                format!("The target {:?} has a parameter {:?} which {:?} doesn't have.", to, name, from)
+            }
+            Segment::Wildcard => format!("The target {:?} can't have a * wildcard.", to),
+            _ => continue,
+        };
+        return Err(ConflictKind::InvalidPattern(PatternError::new(message)));
+    }
+
+    Ok(target)
+}
+
+/// Returns true if the target of a rule can be filled with the parameters
+/// of every path matching its source, which fails when a parameter takes a
+/// value with a `/`, or an empty value, from a multi-segment parameter.
+fn always_fills(source: &RoutePattern, target: &RoutePattern) -> bool {
+    let source_segment = |name: &str| source.segments().iter().find(|segment| match segment {
+        Segment::Param(param) | Segment::Greedy { name: param, .. } => param == name,
+        _ => false,
+    });
+
+    target.segments().iter().all(|segment| match segment {
+        Segment::Param(name) => matches!(source_segment(name), Some(Segment::Param(_))),
+        Segment::Greedy { name, optional: false } => !matches!(source_segment(name), Some(Segment::Greedy { optional: true, .. })),
+        _ => true,
+    })
+}
+
+/// Fills the target of a rule with the parameters of the path matching it.
+fn fill_target<T>(target: &RoutePattern, result: &SearchResult<T>, fill: Fill) -> Option<String> {
+    let params: Vec<(&str, &str)> = result.params().iter()
+        .map(|param| (param.name().as_str(), param.value().as_str()))
+        .collect();
+    fill_pattern(target, &params, fill).ok()
+}
+
+/// How the values of the parameters are written into a filled pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fill {
+    /// As they are, for a path which is searched again.
+    Raw,
+    /// Percent-encoded, for values which aren't encoded yet.
+    Encode,
+    /// Percent-encoded, keeping the escapes of values taken from a path.
+    EncodePath,
+}
+
+/// Fills the parameters of the pattern with the values of the same names.
+/// Empty values are rejected, except for an optional `$path*` parameter.
+fn fill_pattern(pattern: &RoutePattern, params: &[(&str, &str)], fill: Fill) -> Result<String, PathForError> {
+    let find = |param: &str| {
+        params.iter()
+            .find(|(k, _)| *k == param)
+            .map(|(_, v)| *v)
+            .ok_or_else(|| PathForError::MissingParam(param.to_string()))
+    };
+    let push = |path: &mut String, value: &str, multi_segment: bool| match fill {
+        Fill::Raw => path.push_str(value),
+        _ => push_encoded(path, value, multi_segment, fill == Fill::EncodePath),
+    };
+
+    let mut path = String::new();
+    for segment in pattern.segments() {
+        match segment {
+            Segment::Static(text) => path.push_str(text),
+            Segment::Param(name) => {
+                let value = find(name)?;
+                if value.is_empty() || value.contains('/') {
+                    return Err(PathForError::InvalidParam(name.to_string()));
+                }
+                push(&mut path, value, false);
+            }
+            Segment::Wildcard => {
+                let value = find("*")?;
+                if value.is_empty() {
+                    return Err(PathForError::InvalidParam("*".to_string()));
+                }
+                push(&mut path, value, true);
+            }
+            Segment::Greedy { name, optional } => {
+                let value = find(name)?;
+                if value.is_empty() && !optional {
+                    return Err(PathForError::InvalidParam(name.to_string()));
+                }
+                push(&mut path, value, true);
+            }
+        }
+    }
+
+    Ok(path)
+}
+
+/// Appends the value to the path, percent-encoding the bytes which can't be
+/// in a path segment, such as `?`, `#` and `%`. The `/` of a multi-segment
+/// value is kept, and so are the `%` of valid escapes with `keep_escapes`.
+fn push_encoded(path: &mut String, value: &str, multi_segment: bool, keep_escapes: bool) {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    let bytes = value.as_bytes();
+    for (i, &byte) in bytes.iter().enumerate() {
+        let escape = keep_escapes && byte == b'%' && bytes.get(i + 1..i + 3).is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~'
+            | b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'=' | b':' | b'@' => path.push(byte as char),
+            b'/' if multi_segment => path.push('/'),
+            b'%' if escape => path.push('%'),
+            _ => {
+                path.push('%');
+                path.push(HEX[usize::from(byte >> 4)] as char);
+                path.push(HEX[usize::from(byte & 0xF)] as char);
+            }
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use alloc::{string::String, vec::Vec};
+use crate::error::ConflictKind;
+use crate::extensions::Extensions;
+use crate::method::{Method, MethodSet};
+use crate::router::{join_prefix, with_start_slash, Router};
+
+/// A group of routes sharing a path prefix, started with `Router::scope`.
+///
+/// Every route inserted through the scope is registered under the prefix,
+/// and the shared extensions are added to its own. Routes inserted with
+/// `route` take the shared methods, which are `Method::ANY` by default.
+#[derive(Debug)]
+pub struct Scope<'a, V> {
+    router: &'a mut Router<V>,
+    /// The prefix in the native syntax, without a trailing `/`
+    prefix: String,
+    extensions: Extensions,
+    methods: MethodSet,
+}
+
+impl<'a, V: Clone> Scope<'a, V> {
+    pub(crate) fn new(router: &'a mut Router<V>, prefix: String) -> Self {
+        Self {
+            router,
+            prefix,
+            extensions: Extensions::new(),
+            methods: Method::ANY.into(),
+        }
+    }
+
+    /// Adds the value to the extensions of every route inserted afterwards.
+    pub fn extension<T: Clone + Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.extensions.insert(value);
+        self
+    }
+
+    /// Sets the methods of the routes inserted with `route`.
+    pub fn methods(mut self, methods: impl Into<MethodSet>) -> Self {
+        self.methods = methods.into();
+        self
+    }
+
+    /// Inserts a value under the prefix, resolving conflicts with the conflict
+    /// policy of the router.
+    pub fn insert(&mut self, method: Method, path: &str, value: V) -> Result<(), ConflictKind> {
+        self.insert_with(&[method], path, value)
+    }
+
+    /// Inserts a value under the prefix for each of the shared methods.
+    pub fn route(&mut self, path: &str, value: V) -> Result<(), ConflictKind> {
+        let methods: Vec<Method> = self.methods.iter().collect();
+        self.insert_with(&methods, path, value)
+    }
+
+    /// Starts a scope nested under `prefix`, which inherits the extensions and
+    /// the methods of this scope.
+    pub fn scope(&mut self, prefix: &str) -> Scope<'_, V> {
+        let prefix = join_prefix(&self.prefix, with_start_slash(&self.router.native(prefix)).trim_end_matches('/'));
+        Scope {
+            router: &mut *self.router,
+            prefix,
+            extensions: self.extensions.clone(),
+            methods: self.methods,
+        }
+    }
+
+    fn insert_with(&mut self, methods: &[Method], path: &str, value: V) -> Result<(), ConflictKind> {
+        let path = join_prefix(&self.prefix, &with_start_slash(&self.router.native(path)));
+        self.router.insert_extended(methods, &path, value, &self.extensions)
+    }
+}
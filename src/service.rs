@@ -0,0 +1,101 @@
+use std::boxed::Box;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::string::ToString;
+use http::header::{HeaderValue, ALLOW};
+use http::{Request, Response, StatusCode};
+use tower_service::Service;
+use crate::router::Router;
+use crate::tree::Node;
+
+type BoxFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send>>;
+
+/// A `tower::Service` which dispatches requests to the service matched in
+/// the radix tree.
+///
+/// The path parameters of the matched route are inserted into the request
+/// extensions as `Params`. Requests without a matching route are answered
+/// with an empty `404 Not Found` response, or with an empty `405 Method Not
+/// Allowed` response and an `Allow` header if the path has routes for other
+/// methods.
+///
+/// There is no hyper-specific service yet, so hyper servers serve it through
+/// `hyper_util::service::TowerToHyperService`.
+pub struct RouterService<S> {
+    router: Arc<Router<S>>,
+}
+
+impl<S> RouterService<S> {
+    pub fn new(router: Router<S>) -> Self {
+        Self {
+            router: Arc::new(router),
+        }
+    }
+}
+
+impl<S> Clone for RouterService<S> {
+    fn clone(&self) -> Self {
+        Self {
+            router: self.router.clone(),
+        }
+    }
+}
+
+impl<S> From<Node<S>> for RouterService<S> {
+    fn from(tree: Node<S>) -> Self {
+        Self::new(Router::from(tree))
+    }
+}
+
+impl<S> From<Router<S>> for RouterService<S> {
+    fn from(router: Router<S>) -> Self {
+        Self::new(router)
+    }
+}
+
+impl<S, B, RB> Service<Request<B>> for RouterService<S>
+where
+    S: Service<Request<B>, Response = Response<RB>> + Clone + Send + 'static,
+    S::Future: Send,
+    B: Send + 'static,
+    RB: Default,
+{
+    type Response = Response<RB>;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // The readiness of the matched service is checked for each request.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        let result = self.router.route_request(&req);
+
+        let Some(result) = result else {
+            let allowed = self.router.allowed_methods(req.uri().path());
+            return Box::pin(async move {
+                let mut response = Response::new(RB::default());
+                if allowed.is_empty() {
+                    *response.status_mut() = StatusCode::NOT_FOUND;
+                } else {
+                    *response.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+                    if let Ok(allow) = HeaderValue::from_str(&allowed.to_string()) {
+                        response.headers_mut().insert(ALLOW, allow);
+                    }
+                }
+                Ok(response)
+            });
+        };
+
+        let mut service = result.value().clone();
+        req.extensions_mut().insert(result.params().clone());
+
+        Box::pin(async move {
+            poll_fn(|cx| service.poll_ready(cx)).await?;
+            service.call(req).await
+        })
+    }
+}
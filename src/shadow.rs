@@ -0,0 +1,27 @@
+use alloc::string::String;
+use crate::method::Method;
+
+/// A route which no path can match, found by `Router::shadowed_routes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShadowedRoute {
+    pub method: Method,
+    pub pattern: String,
+    pub cause: Shadowing,
+}
+
+/// Why no path can match a route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Shadowing {
+    /// The static text of the route has a `//`, which is merged in the
+    /// searched paths.
+    MergedSlashes,
+    /// The static text of the route has a `.` or `..` segment, which is
+    /// normalized or rejected in the searched paths.
+    DotSegments,
+    /// This route takes precedence for every path of the route.
+    Route(String),
+    /// The paths of the route are rewritten by the rule of this pattern, to
+    /// paths which the route doesn't match.
+    Rewritten(String),
+}
@@ -0,0 +1,240 @@
+use alloc::{string::{String, ToString}, vec, vec::Vec};
+use crate::error::SnapshotError;
+use crate::method::{Method, MethodMap};
+use crate::tree::{Node, NodeData, ROOT};
+
+/// The first bytes of a snapshot, followed by its version.
+const MAGIC: &[u8; 4] = b"RDXT";
+/// The version of the snapshot format, which is bumped by every incompatible
+/// change.
+const VERSION: u8 = 1;
+/// Encodes a missing child or missing parameter names.
+const NONE: u32 = u32::MAX;
+
+impl<V> Node<V> {
+    /// Encodes the tree into a compact binary snapshot, which `from_bytes`
+    /// decodes without inserting the routes again. Each value is appended to
+    /// the buffer by `encode`.
+    ///
+    /// Snapshots start with the version of their format, and only snapshots
+    /// of the version of the crate can be decoded.
+    ///
+    /// The nodes are numbered in depth-first order, leaving out the nodes
+    /// freed by removals, so a snapshot only depends on the structure of the
+    /// tree and is the same across runs.
+    pub fn to_bytes<F: FnMut(&V, &mut Vec<u8>)>(&self, mut encode: F) -> Vec<u8> {
+        let order = preorder(&self.nodes);
+        let mut numbers = vec![0; self.nodes.len()];
+        for (number, &id) in order.iter().enumerate() {
+            numbers[id] = number as u32;
+        }
+
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+
+        put_u32(&mut out, order.len() as u32);
+        put_u32(&mut out, 0);
+
+        let mut value_bytes = Vec::new();
+        for node in order.iter().map(|&id| &self.nodes[id]) {
+            put_bytes(&mut out, node.path.as_bytes());
+
+            put_u32(&mut out, node.static_child.len() as u32);
+            for (&c, &child) in node.static_indices.iter().zip(node.static_child.iter()) {
+                put_u32(&mut out, c as u32);
+                put_u32(&mut out, numbers[child]);
+            }
+            for child in [node.param_child, node.star_child] {
+                put_u32(&mut out, child.map_or(NONE, |child| numbers[child]));
+            }
+
+            match node.leaf_param_names {
+                Some(ref names) => {
+                    put_u32(&mut out, names.len() as u32);
+                    for name in names.iter() {
+                        put_bytes(&mut out, name.as_bytes());
+                    }
+                }
+                None => put_u32(&mut out, NONE),
+            }
+
+            put_u32(&mut out, node.leaf_handler.len() as u32);
+            for (method, value) in node.leaf_handler.iter() {
+                put_bytes(&mut out, method.as_str().as_bytes());
+                value_bytes.clear();
+                encode(value, &mut value_bytes);
+                put_bytes(&mut out, &value_bytes);
+            }
+        }
+
+        out
+    }
+
+    /// Decodes a snapshot of `to_bytes`, turning the bytes of each value back
+    /// into the value with `decode`.
+    pub fn from_bytes<F: FnMut(&[u8]) -> Option<V>>(bytes: &[u8], mut decode: F) -> Result<Self, SnapshotError> {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(SnapshotError::InvalidFormat);
+        }
+        let version = reader.take(1)?[0];
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        let len = reader.u32()? as usize;
+        let free_len = reader.u32()?;
+        let free = (0..free_len).map(|_| reader.index(len)).collect::<Result<Vec<_>, _>>()?;
+
+        let mut nodes = Vec::new();
+        for _ in 0..len {
+            let path = reader.string()?.into_boxed_str();
+
+            let mut static_indices = Vec::new();
+            let mut static_child = Vec::new();
+            for _ in 0..reader.u32()? {
+                static_indices.push(char::from_u32(reader.u32()?).ok_or(SnapshotError::InvalidFormat)?);
+                static_child.push(reader.index(len)?);
+            }
+            let param_child = reader.child(len)?;
+            let star_child = reader.child(len)?;
+
+            let leaf_param_names = match reader.u32()? {
+                NONE => None,
+                count => Some((0..count).map(|_| reader.string()).collect::<Result<Vec<_>, _>>()?),
+            };
+
+            let mut leaf_handler = MethodMap::new();
+            for _ in 0..reader.u32()? {
+                let method = Method::from_name(&reader.string()?).map_err(|_| SnapshotError::InvalidFormat)?;
+                let value_len = reader.u32()? as usize;
+                let value = decode(reader.take(value_len)?).ok_or(SnapshotError::InvalidValue)?;
+                leaf_handler.insert(method, value);
+            }
+
+            nodes.push(NodeData {
+                path,
+                static_indices,
+                static_child,
+                param_child,
+                star_child,
+                leaf_handler,
+                leaf_param_names,
+            });
+        }
+
+        if !reader.bytes.is_empty() || !is_tree(&nodes, &free) {
+            return Err(SnapshotError::InvalidFormat);
+        }
+
+        Ok(Self::from_arena(nodes, free))
+    }
+}
+
+/// Returns the nodes reachable from the root, in depth-first order with the
+/// static children first, then the param child and the star child.
+fn preorder<V>(nodes: &[NodeData<V>]) -> Vec<usize> {
+    let mut order = Vec::new();
+    let mut pending = vec![ROOT];
+    while let Some(id) = pending.pop() {
+        order.push(id);
+
+        let node = &nodes[id];
+        pending.extend(node.star_child);
+        pending.extend(node.param_child);
+        pending.extend(node.static_child.iter().rev());
+    }
+
+    order
+}
+
+/// Returns true if the root is a node and every other node is the child of
+/// at most one node, unless it is free, so that the nodes reachable from the
+/// root form a tree. The static children must be sorted by their first
+/// character, like the tree keeps them.
+fn is_tree<V>(nodes: &[NodeData<V>], free: &[usize]) -> bool {
+    if nodes.is_empty() {
+        return false;
+    }
+
+    let mut referenced = vec![false; nodes.len()];
+    referenced[ROOT] = true;
+    for &index in free {
+        if core::mem::replace(&mut referenced[index], true) {
+            return false;
+        }
+    }
+
+    for node in nodes.iter() {
+        let first_chars = node.static_child.iter().map(|&child| nodes[child].path.chars().next());
+        if !first_chars.eq(node.static_indices.iter().copied().map(Some)) || !node.static_indices.is_sorted() {
+            return false;
+        }
+
+        let children = node.static_child.iter().copied().chain(node.param_child).chain(node.star_child);
+        for child in children {
+            if core::mem::replace(&mut referenced[child], true) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Writes the bytes after their length.
+fn put_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    put_u32(out, bytes.len() as u32);
+    out.extend_from_slice(bytes);
+}
+
+/// Reads the parts of a snapshot, failing when the snapshot is truncated.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SnapshotError> {
+        if self.bytes.len() < len {
+            return Err(SnapshotError::InvalidFormat);
+        }
+
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> Result<u32, SnapshotError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads the index of a node, which must be one of the `len` nodes.
+    fn index(&mut self, len: usize) -> Result<usize, SnapshotError> {
+        let index = self.u32()? as usize;
+        if index >= len {
+            return Err(SnapshotError::InvalidFormat);
+        }
+
+        Ok(index)
+    }
+
+    fn child(&mut self, len: usize) -> Result<Option<usize>, SnapshotError> {
+        match self.u32()? {
+            NONE => Ok(None),
+            index if (index as usize) < len => Ok(Some(index as usize)),
+            _ => Err(SnapshotError::InvalidFormat),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, SnapshotError> {
+        let len = self.u32()? as usize;
+        let bytes = self.take(len)?;
+        core::str::from_utf8(bytes).map(ToString::to_string).map_err(|_| SnapshotError::InvalidFormat)
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+use std::vec::Vec;
+use crate::error::{ConflictKind, UpdateError};
+use crate::handle::RouterHandle;
+use crate::method::{Method, MethodSet};
+use crate::router::Router;
+use crate::tree::SearchResult;
+
+/// A router shared between threads, which is searched concurrently and
+/// changed by one writer at a time.
+///
+/// Every change copies the router, applies the change to the copy and then
+/// publishes it (read-copy-update), so searches never wait for a writer and
+/// always see either the old or the new routes. Searches take no lock: they
+/// clone the `Arc` of the current router, which the writers swap atomically.
+/// Since each change copies the routes, several changes are better made at
+/// once with `write`.
+#[derive(Debug)]
+pub struct SyncRouter<V> {
+    handle: RouterHandle<V>,
+}
+
+impl<V: Clone> SyncRouter<V> {
+    pub fn new() -> Self {
+        Self::from(Router::new())
+    }
+
+    /// Returns Some(SearchResult<V>), if successful. Otherwise returns None.
+    pub fn search(&self, method: Method, path: &str) -> Option<SearchResult<V>> {
+        self.handle.load().search(method, path)
+    }
+
+    /// Returns every method which has a handler for the path.
+    pub fn allowed_methods(&self, path: &str) -> MethodSet {
+        self.handle.load().allowed_methods(path)
+    }
+
+    /// Inserts a value, resolving conflicts with the conflict policy.
+    pub fn insert(&self, method: Method, path: &str, value: V) -> Result<(), ConflictKind> {
+        self.handle.update(|router| router.insert(method, path, value))
+    }
+
+    /// Inserts the same value for each of the methods, such as `Method::GET | Method::POST`.
+    pub fn insert_methods(&self, methods: impl Into<MethodSet>, path: &str, value: V) -> Result<(), ConflictKind> {
+        self.handle.update(|router| router.insert_methods(methods, path, value))
+    }
+
+    /// Removes every method of the path.
+    ///
+    /// Returns the removed handlers, or None if the path isn't registered.
+    pub fn remove(&self, path: &str) -> Option<Vec<(Method, V)>> {
+        self.handle.update(|router| router.remove(path))
+    }
+
+    /// Removes the handler of the method, keeping the other methods of the path.
+    ///
+    /// Returns the removed value, if any.
+    pub fn remove_method(&self, method: Method, path: &str) -> Option<V> {
+        self.handle.update(|router| router.remove_method(method, path))
+    }
+
+    /// Updates the value of the method, returning the old value.
+    pub fn update(&self, method: Method, path: &str, value: V) -> Result<V, UpdateError> {
+        self.handle.update(|router| router.update(method, path, value))
+    }
+
+    /// Makes several changes at once, which searches see together.
+    ///
+    /// Returns the result of `f`. When `f` panics, nothing is changed.
+    pub fn write<R, F: FnOnce(&mut Router<V>) -> R>(&self, f: F) -> R {
+        self.handle.update(f)
+    }
+}
+
+impl<V> SyncRouter<V> {
+    /// Returns a snapshot of the current routes, which isn't affected by
+    /// later changes.
+    pub fn snapshot(&self) -> Arc<Router<V>> {
+        self.handle.load()
+    }
+}
+
+impl<V: Clone> Default for SyncRouter<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> From<Router<V>> for SyncRouter<V> {
+    fn from(router: Router<V>) -> Self {
+        Self {
+            handle: RouterHandle::new(router),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::error::ConflictKind;
+use crate::method::Method;
+use crate::persistent::PersistentNode;
+use crate::tree::{ConflictPolicy, SearchResult};
+
+/// A collection of per-tenant route tables, which share the nodes they have
+/// in common.
+///
+/// Every tenant starts with the shared routes, and its own routes are added
+/// to a `PersistentNode` which copies only the nodes on their path, so
+/// thousands of near-identical tables don't multiply memory. Tenants without
+/// routes of their own share the whole tree of the shared routes.
+///
+/// A route of a tenant replaces the shared route of the same method and
+/// pattern for the tenant, and stays when the shared route changes.
+#[derive(Debug, Clone)]
+pub struct TenantRouter<V> {
+    shared: PersistentNode<V>,
+    tenants: BTreeMap<String, Tenant<V>>,
+}
+
+/// The route table of a tenant.
+#[derive(Debug, Clone)]
+struct Tenant<V> {
+    tree: PersistentNode<V>,
+    /// The routes which the tenant inserted itself
+    own: Vec<(Method, String, V)>,
+}
+
+impl<V> Tenant<V> {
+    fn owns(&self, method: &Method, path: &str) -> bool {
+        self.own.iter().any(|(own_method, own_path, _)| own_method == method && own_path == path)
+    }
+}
+
+impl<V: Clone> TenantRouter<V> {
+    pub fn new() -> Self {
+        Self {
+            shared: PersistentNode::new(),
+            tenants: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the tree of the shared routes.
+    pub fn shared(&self) -> &PersistentNode<V> {
+        &self.shared
+    }
+
+    /// Inserts a route for every tenant, including the tenants added later,
+    /// except the tenants which inserted the same route themselves.
+    ///
+    /// Returns the conflict of the route with the shared routes or with the
+    /// routes of a tenant, in which case no tenant is changed.
+    pub fn insert_shared(&mut self, method: Method, path: &str, value: V) -> Result<(), ConflictKind> {
+        let shared = self.shared.try_insert(method.clone(), path, value.clone())?;
+        let mut trees = Vec::with_capacity(self.tenants.len());
+        for tenant in self.tenants.values() {
+            trees.push(match tenant.tree.ptr_eq(&self.shared) {
+                true => shared.clone(),
+                false if tenant.owns(&method, path) => tenant.tree.clone(),
+                false => tenant.tree.try_insert(method.clone(), path, value.clone())?,
+            });
+        }
+
+        for (tenant, tree) in self.tenants.values_mut().zip(trees) {
+            tenant.tree = tree;
+        }
+        self.shared = shared;
+        Ok(())
+    }
+
+    /// Removes a shared route from every tenant. The routes of the same path
+    /// which a tenant inserted itself are kept.
+    pub fn remove_shared(&mut self, path: &str) {
+        let shared = self.shared.remove(path);
+        for tenant in self.tenants.values_mut() {
+            if tenant.tree.ptr_eq(&self.shared) {
+                tenant.tree = shared.clone();
+                continue;
+            }
+
+            let mut tree = tenant.tree.remove(path);
+            for (method, _, value) in tenant.own.iter().filter(|(_, own_path, _)| own_path == path) {
+                tree = tree.insert(method.clone(), path, value.clone());
+            }
+            tenant.tree = tree;
+        }
+
+        self.shared = shared;
+    }
+
+    /// Adds a tenant with the shared routes, returning false if the tenant
+    /// already exists.
+    pub fn add_tenant(&mut self, tenant: &str) -> bool {
+        if self.tenants.contains_key(tenant) {
+            return false;
+        }
+
+        self.tenants.insert(tenant.to_string(), Tenant { tree: self.shared.clone(), own: Vec::new() });
+        true
+    }
+
+    /// Removes a tenant, returning its tree if any.
+    pub fn remove_tenant(&mut self, tenant: &str) -> Option<PersistentNode<V>> {
+        self.tenants.remove(tenant).map(|tenant| tenant.tree)
+    }
+
+    /// Inserts a route for the tenant only, adding the tenant if it is new.
+    /// The route replaces the route of the same method and pattern, shared
+    /// or not.
+    ///
+    /// Returns the conflict of the route with the other routes of the
+    /// tenant, in which case nothing is changed.
+    pub fn insert(&mut self, tenant: &str, method: Method, path: &str, value: V) -> Result<(), ConflictKind> {
+        let tree = self.tenants.get(tenant).map_or(&self.shared, |tenant| &tenant.tree);
+        let tree = tree.insert_with(method.clone(), path, value.clone(), ConflictPolicy::Overwrite)?;
+
+        self.add_tenant(tenant);
+        let tenant = self.tenants.get_mut(tenant).unwrap();
+        tenant.tree = tree;
+        tenant.own.retain(|(own_method, own_path, _)| !(*own_method == method && own_path == path));
+        tenant.own.push((method, path.to_string(), value));
+        Ok(())
+    }
+
+    /// Removes every method of the path for the tenant only.
+    pub fn remove(&mut self, tenant: &str, path: &str) {
+        if let Some(tenant) = self.tenants.get_mut(tenant) {
+            tenant.tree = tenant.tree.remove(path);
+            tenant.own.retain(|(_, own_path, _)| own_path != path);
+        }
+    }
+
+    /// Returns the tree of the tenant.
+    pub fn tenant(&self, tenant: &str) -> Option<&PersistentNode<V>> {
+        self.tenants.get(tenant).map(|tenant| &tenant.tree)
+    }
+
+    /// Returns the tenants in order.
+    pub fn tenants(&self) -> impl Iterator<Item = &str> {
+        self.tenants.keys().map(String::as_str)
+    }
+
+    /// Returns true if the tenant has no routes of its own, so it shares the
+    /// whole tree of the shared routes.
+    pub fn is_shared(&self, tenant: &str) -> bool {
+        self.tenants.get(tenant).is_some_and(|tenant| tenant.tree.ptr_eq(&self.shared))
+    }
+
+    /// Searches the routes of the tenant, or only the shared routes if the
+    /// tenant doesn't exist.
+    pub fn search(&self, tenant: &str, method: Method, path: &str) -> Option<SearchResult<V>> {
+        self.tenants.get(tenant).map_or(&self.shared, |tenant| &tenant.tree).search(method, path)
+    }
+}
+
+impl<V: Clone> Default for TenantRouter<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
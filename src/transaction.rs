@@ -0,0 +1,82 @@
+use alloc::{string::{String, ToString}, vec::Vec};
+use crate::error::TransactionError;
+use crate::method::Method;
+use crate::router::Router;
+
+/// A batch of changes to a router, started with `Router::transaction`.
+///
+/// The changes are staged in order and only applied by `commit`, which
+/// either applies all of them or none. Dropping the transaction without
+/// committing it discards the changes.
+#[derive(Debug)]
+pub struct Transaction<'a, V> {
+    router: &'a mut Router<V>,
+    changes: Vec<Change<V>>,
+}
+
+/// A staged change of a transaction.
+#[derive(Debug)]
+pub(crate) enum Change<V> {
+    Insert(Method, String, V),
+    Remove(String),
+    RemoveMethod(Method, String),
+    Update(Method, String, V),
+}
+
+impl<'a, V: Clone> Transaction<'a, V> {
+    pub(crate) fn new(router: &'a mut Router<V>) -> Self {
+        Self {
+            router,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Stages the insertion of a value.
+    pub fn insert(&mut self, method: Method, path: &str, value: V) -> &mut Self {
+        self.changes.push(Change::Insert(method, path.to_string(), value));
+        self
+    }
+
+    /// Stages the removal of every method of the path.
+    pub fn remove(&mut self, path: &str) -> &mut Self {
+        self.changes.push(Change::Remove(path.to_string()));
+        self
+    }
+
+    /// Stages the removal of the handler of the method.
+    pub fn remove_method(&mut self, method: Method, path: &str) -> &mut Self {
+        self.changes.push(Change::RemoveMethod(method, path.to_string()));
+        self
+    }
+
+    /// Stages the update of the value of the method.
+    pub fn update(&mut self, method: Method, path: &str, value: V) -> &mut Self {
+        self.changes.push(Change::Update(method, path.to_string(), value));
+        self
+    }
+
+    /// Returns the number of staged changes.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Returns true if no change is staged.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Applies the staged changes in order. When any of them fails, the router
+    /// is left unchanged and the first error is returned.
+    ///
+    /// Inserts follow the conflict policy of the router, except that conflicts
+    /// are returned instead of panicking, and constraints and limits apply
+    /// like they do to `Router::insert`. Invalid patterns are errors rather
+    /// than panics. Removing a route which isn't registered is an error. The
+    /// hooks of the router are only called once every change has succeeded.
+    pub fn commit(self) -> Result<(), TransactionError> {
+        self.router.apply(self.changes)
+    }
+
+    /// Discards the staged changes.
+    pub fn rollback(self) {}
+}
@@ -1,61 +1,506 @@
-use std::collections::HashMap;
-use crate::method::Method;
+use alloc::collections::BTreeMap;
+use core::fmt;
+use core::mem::size_of;
+use core::ops::RangeBounds;
+use core::str::FromStr;
+use alloc::sync::Arc;
+use alloc::{boxed::Box, format, string::{String, ToString}, vec, vec::Vec};
+use crate::compiled::CompiledRouter;
+use crate::explain::{Explanation, Step, Trace};
+use crate::extensions::Extensions;
+use crate::error::{ConflictKind, DumpError, ParamError, UpdateError, Violation};
+use crate::method::{Discriminator, Method, MethodMap, MethodSet};
+use crate::normalize::{DotSegments, UnicodeNormalizer};
+use crate::pattern::{self, RoutePattern};
+
+/// The index of the root node in the arena.
+pub(crate) const ROOT: usize = 0;
+
+/// A radix tree, whose nodes are allocated from an arena and refer to their
+/// children by index.
+///
+/// The values of a pattern are told apart by `K`, which is the HTTP method by
+/// default. Features tied to HTTP, such as `allowed_methods` or compiling,
+/// are only available for `Method`.
+///
+/// Patterns and paths may contain any Unicode text, and nodes are only split
+/// at character boundaries.
+#[derive(Debug, Clone)]
+pub struct Node<V, K = Method> {
+    /// The nodes of the tree, starting with the root node
+    pub(crate) nodes: Vec<NodeData<V, K>>,
+    /// The indices of the removed nodes, which are reused by new nodes
+    pub(crate) free: Vec<usize>,
+    /// The number of paths which have a handler for each key
+    counts: MethodMap<usize, K>,
+}
 
 /// A node in radix tree
 #[derive(Debug, Clone)]
-pub struct Node<V> {
-    path: String,
-    /// The list of first letters of static path child nodes
-    static_indices: Vec<char>,
+pub(crate) struct NodeData<V, K = Method> {
+    /// The path fragment of the node, boxed since it never grows
+    pub(crate) path: Box<str>,
+    /// The list of first letters of static path child nodes, sorted for binary search
+    pub(crate) static_indices: Vec<char>,
     /// The list of static path child nodes
-    static_child: Vec<Option<Self>>,
+    pub(crate) static_child: Vec<usize>,
     /// The path parameter child node
-    param_child: Option<Box<Self>>,
+    pub(crate) param_child: Option<usize>,
     /// The * wildcard child node
-    star_child: Option<Box<Self>>,
+    pub(crate) star_child: Option<usize>,
     /// If this node is the end of the URL path, then call the handler.
-    leaf_handler: HashMap<Method, V>,
+    pub(crate) leaf_handler: MethodMap<V, K>,
     /// The names of the parameters
-    leaf_param_names: Option<Vec<String>>,
+    pub(crate) leaf_param_names: Option<Vec<String>>,
 }
 
-impl<V: Clone> Node<V> {
+impl<V: Clone, K: Discriminator> Node<V, K> {
     pub fn new() -> Self {
-        Self {
-            path: "/".to_string(),
+        Self::with_root(NodeData {
+            path: "/".into(),
             ..Default::default()
+        })
+    }
+
+    pub fn insert(&mut self, method: K, path: &str, value: V) {
+        let leaf = self.insert_leaf(path);
+        self.set_handlers(leaf, &[method], value);
+    }
+
+    /// Inserts a value, replacing the existing value of the method instead of panicking.
+    ///
+    /// Returns the replaced value, if any.
+    pub fn insert_replace(&mut self, method: K, path: &str, value: V) -> Option<V> {
+        let leaf = self.insert_leaf(path);
+        let replaced = self.nodes[leaf].leaf_handler.insert(method.clone(), value);
+        if replaced.is_none() {
+            self.count_added(&method);
+        }
+        replaced
+    }
+
+    /// Creates a tree from the routes, like `collect`, but returns the
+    /// conflict of the first route which conflicts with a previous one or has
+    /// an invalid pattern, instead of panicking.
+    pub fn try_from_iter<P, I>(routes: I) -> Result<Self, ConflictKind>
+    where
+        P: AsRef<str>,
+        I: IntoIterator<Item = (K, P, V)>,
+    {
+        let mut tree = Self::new();
+        tree.try_extend(routes)?;
+        Ok(tree)
+    }
+
+    /// Inserts the routes, like `extend`, but returns the conflict of the
+    /// first route which conflicts or has an invalid pattern, instead of
+    /// panicking. The routes before it stay inserted.
+    pub fn try_extend<P, I>(&mut self, routes: I) -> Result<(), ConflictKind>
+    where
+        P: AsRef<str>,
+        I: IntoIterator<Item = (K, P, V)>,
+    {
+        for (method, path, value) in routes {
+            self.insert_with(&[method], path.as_ref(), value, ConflictPolicy::Error)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether inserting the method and path would conflict with an
+    /// existing route, without changing the tree.
+    pub fn would_conflict(&self, method: K, path: &str) -> Option<ConflictKind> {
+        if let Err(error) = RoutePattern::parse(path) {
+            return Some(ConflictKind::InvalidPattern(error));
+        }
+
+        self.find_conflict(ROOT, &method, strip_start_slash(path), Vec::new())
+    }
+
+    /// Gets the entry of the method and path for in-place manipulation.
+    ///
+    /// Returns the conflict instead of a vacant entry when the pattern is
+    /// invalid or couldn't be inserted, such as ambiguous parameters.
+    pub fn entry(&mut self, method: K, path: &str) -> Result<Entry<'_, V, K>, ConflictKind> {
+        RoutePattern::parse(path).map_err(ConflictKind::InvalidPattern)?;
+
+        let path = strip_start_slash(path).to_string();
+        let leaf = self.find_path(ROOT, path.clone())
+            .filter(|leaf| self.nodes[*leaf].leaf_handler.contains_key(&method));
+
+        match leaf {
+            Some(leaf) => Ok(Entry::Occupied(OccupiedEntry {
+                tree: self,
+                leaf,
+                method,
+            })),
+            None => match self.find_conflict(ROOT, &method, &path, Vec::new()) {
+                Some(kind) => Err(kind),
+                None => Ok(Entry::Vacant(VacantEntry {
+                    tree: self,
+                    method,
+                    path,
+                })),
+            },
         }
     }
 
-    pub fn insert(&mut self, method: Method, path: &str, value: V) {
-        self.insert_path(&method, strip_start_slash(path.to_string()), value, None);
+    /// Inserts the same value for each of the methods, such as `Method::GET | Method::POST`.
+    pub fn insert_methods(&mut self, methods: impl IntoIterator<Item = K>, path: &str, value: V) {
+        let methods: Vec<K> = methods.into_iter().collect();
+        let leaf = self.insert_leaf(path);
+        self.set_handlers(leaf, &methods, value);
+    }
+
+    /// Removes the handlers of the methods, keeping the other methods of the path.
+    ///
+    /// Returns the removed handlers, or None if none of the methods is registered.
+    pub fn remove_methods(&mut self, methods: impl IntoIterator<Item = K>, path: &str) -> Option<Vec<(K, V)>> {
+        let methods: Vec<K> = methods.into_iter().collect();
+        let removed = self.remove_path(ROOT, Some(&methods), strip_start_slash(path).to_string());
+        Some(removed).filter(|v| !v.is_empty())
+    }
+
+    /// Updates the value of the method, returning the old value.
+    pub fn update(&mut self, method: K, path: &str, value: V) -> Result<V, UpdateError<K>> {
+        self.update_path(&[method], strip_start_slash(path).to_string(), value)
+            .map(|mut v| v.pop().unwrap())
+    }
+
+    /// Updates the value of each of the methods, returning the old values in
+    /// the iteration order of the methods.
+    ///
+    /// Nothing is updated unless every method is registered for the path.
+    pub fn update_methods(&mut self, methods: impl IntoIterator<Item = K>, path: &str, value: V) -> Result<Vec<V>, UpdateError<K>> {
+        let methods: Vec<K> = methods.into_iter().collect();
+        self.update_path(&methods, strip_start_slash(path).to_string(), value)
+    }
+
+    fn update_path(&mut self, methods: &[K], path: String, value: V) -> Result<Vec<V>, UpdateError<K>> {
+        match self.find_path(ROOT, path) {
+            Some(leaf) => self.nodes[leaf].update_handlers(methods, value),
+            None => Err(UpdateError::PathNotFound),
+        }
     }
 
-    pub fn remove(&mut self, path: &str) {
-        self.remove_path(strip_start_slash(path.to_string()));
+    /// Removes every method of the path.
+    ///
+    /// Returns the removed handlers, or None if the path isn't registered.
+    pub fn remove(&mut self, path: &str) -> Option<Vec<(K, V)>> {
+        let removed = self.remove_path(ROOT, None, strip_start_slash(path).to_string());
+        Some(removed).filter(|v| !v.is_empty())
     }
 
-    pub fn update(&mut self, method: Method, path: &str, value: V) {
-        self.update_path(&method, strip_start_slash(path.to_string()), value);
+    /// Removes the handler of the method, keeping the other methods of the path.
+    ///
+    /// Returns the removed value, if any.
+    pub fn remove_method(&mut self, method: K, path: &str) -> Option<V> {
+        self.remove_path(ROOT, Some(&[method]), strip_start_slash(path).to_string())
+            .pop()
+            .map(|(_, v)| v)
     }
 
     /// Returns Some(SearchResult<V>), if successful. Otherwise returns None.
-    pub fn search(&self, method: Method, path: &str) -> Option<SearchResult<V>> {
-        let match_result = self.internal_search(&method, strip_start_slash(path.to_string()));
+    ///
+    /// A key without a handler of its own falls back to `Discriminator::fallback`.
+    pub fn search(&self, method: K, path: &str) -> Option<SearchResult<V>> {
+        self.search_traced(method, path, &mut Trace::off())
+    }
+
+    /// Searches like `search`, recording every node entered, branch tried and
+    /// branch rejected, to find out why a path matches a route or none.
+    pub fn explain(&self, method: K, path: &str) -> Explanation<V> {
+        let mut trace = Trace::on();
+        let result = self.search_traced(method, path, &mut trace);
+        Explanation::new(result, trace.into_steps())
+    }
+
+    fn search_traced(&self, method: K, path: &str, trace: &mut Trace) -> Option<SearchResult<V>> {
+        match K::fallback() {
+            Some(fallback) => self.search_keys(&[method, fallback], path, trace, &mut LeafFilter::off()),
+            None => self.search_keys(&[method], path, trace, &mut LeafFilter::off()),
+        }
+    }
+
+    /// Returns the match of the first of the keys which has a handler.
+    fn search_keys(&self, keys: &[K], path: &str, trace: &mut Trace, filter: &mut LeafFilter<K>) -> Option<SearchResult<V>> {
+        let path = path.strip_prefix('/').unwrap_or(path);
+        let mut match_result = self.internal_search(ROOT, keys, path.to_string(), trace, filter)?;
+        match_result.route.reverse();
+
+        let params = offset_params(&match_result.param_names, &match_result.param_offsets, path);
+        let pattern = self.route_pattern(&match_result.route);
+        Some(SearchResult::new(match_result.value, params, pattern))
+    }
+
+    /// Returns the route matching the longest part of the path which ends at a
+    /// segment boundary, as its pattern, its value and the rest of the path,
+    /// such as to find the mount point of a path.
+    ///
+    /// A `/` route matches any path, and its rest is the whole path.
+    pub fn search_longest_prefix<'p>(&self, method: K, path: &'p str) -> Option<(String, &V, &'p str)> {
+        match K::fallback() {
+            Some(fallback) => self.longest_prefix_keys(&[method, fallback], path),
+            None => self.longest_prefix_keys(&[method], path),
+        }
+    }
+
+    /// Returns the longest prefix match of the first of the keys which has a
+    /// handler, trying the prefixes from the longest.
+    fn longest_prefix_keys<'p>(&self, keys: &[K], path: &'p str) -> Option<(String, &V, &'p str)> {
+        let ends = core::iter::once(path.len()).chain(path.rmatch_indices('/').map(|(i, _)| i));
+        for end in ends {
+            let prefix = strip_start_slash(&path[..end]).to_string();
+            if let Some(mut match_result) = self.internal_search(ROOT, keys, prefix, &mut Trace::off(), &mut LeafFilter::off()) {
+                match_result.route.reverse();
+                let leaf = &self.nodes[match_result.route[match_result.route.len() - 1]];
+                let value = leaf.get_handler(keys)?;
+                return Some((self.route_pattern(&match_result.route), value, &path[end..]));
+            }
+        }
+
+        None
+    }
+
+    /// Returns the pattern of the leaf at the end of the route, given as the
+    /// nodes from the root, with the parameter names of the leaf.
+    fn route_pattern(&self, route: &[usize]) -> String {
+        let leaf = &self.nodes[route[route.len() - 1]];
+        let mut names = leaf.leaf_param_names.iter().flatten();
+        let mut pattern = String::from("/");
+
+        for pair in route.windows(2) {
+            let (parent, child) = (&self.nodes[pair[0]], pair[1]);
+            let path = &*self.nodes[child].path;
+            if parent.param_child == Some(child) {
+                pattern.push('$');
+                pattern.push_str(names.next().map_or(path, |name| name));
+            } else if parent.star_child == Some(child) {
+                // A multi-segment parameter keeps its `+` or `*` suffix.
+                match path.char_indices().last() {
+                    Some((suffix, _)) => {
+                        pattern.push('$');
+                        pattern.push_str(names.next().map_or(&path[..suffix], |name| name));
+                        pattern.push_str(&path[suffix..]);
+                    }
+                    None => pattern.push('*'),
+                }
+            } else {
+                let segment_start = pattern.ends_with('/');
+                pattern.push_str(&pattern::escape(path, segment_start));
+            }
+        }
+
+        pattern
+    }
+
+    /// Calls `f` with the method, pattern and value of every registered route.
+    pub(crate) fn for_each_route<F: FnMut(&K, &str, &V)>(&self, f: &mut F) {
+        self.walk_leaves(ROOT, "/".to_string(), "", &mut |leaf, pattern| {
+            for (method, value) in self.nodes[leaf].leaf_handler.iter() {
+                f(method, pattern, value);
+            }
+        });
+    }
+
+    /// Keeps only the routes for which `f` returns true, given the method,
+    /// pattern and value of each route, and then compacts the tree.
+    pub fn retain<F: FnMut(&K, &str, &V) -> bool>(&mut self, mut f: F) {
+        let mut removed = Vec::new();
+        self.for_each_route(&mut |method, pattern, value| {
+            if !f(method, pattern, value) {
+                removed.push((method.clone(), pattern.to_string()));
+            }
+        });
 
-        match_result.map(|v| SearchResult {
-            value: v.value.clone().unwrap(),
-            params: v.from_params(),
+        for (method, pattern) in removed {
+            self.remove_path(ROOT, Some(&[method]), strip_start_slash(&pattern).to_string());
+        }
+
+        self.compact();
+    }
+
+    /// Returns true if the pattern has a handler for any method.
+    pub(crate) fn contains_path(&self, path: &str) -> bool {
+        self.find_path(ROOT, strip_start_slash(path).to_string())
+            .is_some_and(|leaf| !self.nodes[leaf].leaf_handler.is_empty())
+    }
+
+    /// Returns the handlers of the pattern, or None if it has none.
+    pub(crate) fn handlers(&self, path: &str) -> Option<&MethodMap<V, K>> {
+        let leaf = self.find_path(ROOT, strip_start_slash(path).to_string())?;
+        Some(&self.nodes[leaf].leaf_handler).filter(|handlers| !handlers.is_empty())
+    }
+
+    /// Returns the pattern of the route as it was registered, with the
+    /// parameter names of its leaf, or None if the key has no handler there.
+    pub(crate) fn registered_pattern(&self, key: &K, path: &str) -> Option<String> {
+        let path = strip_start_slash(path).to_string();
+        let leaf = self.find_path(ROOT, path.clone())?;
+        let node = &self.nodes[leaf];
+        if !node.leaf_handler.contains_key(key) {
+            return None;
+        }
+
+        let mut pattern = RoutePattern::parse(&format!("/{}", path)).unwrap_or_default();
+        if let Some(ref names) = node.leaf_param_names {
+            pattern.rename_params(names);
+        }
+        Some(pattern.to_string())
+    }
+
+    /// Returns every route in the lexicographic order of the patterns, as the
+    /// method, pattern and value of each route. The order doesn't depend on
+    /// the order in which the routes were inserted.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, String, &V)> {
+        self.range(..)
+    }
+
+    /// Returns the routes whose pattern is in the range, such as
+    /// `"/a".."/b"`, in the lexicographic order of the patterns.
+    ///
+    /// The standard methods of a pattern come first, in the order of
+    /// `Method::index`, followed by the extension methods in the order of
+    /// their names.
+    pub fn range<'r, R: RangeBounds<&'r str>>(&self, range: R) -> impl Iterator<Item = (&K, String, &V)> {
+        let mut leaves = Vec::new();
+        self.walk_leaves(ROOT, "/".to_string(), "", &mut |leaf, pattern| {
+            if range.contains(&pattern) {
+                leaves.push((pattern.to_string(), leaf));
+            }
+        });
+
+        // Parameters are renamed for each leaf, so the walk isn't in order.
+        leaves.sort();
+        leaves.into_iter().flat_map(move |(pattern, leaf)| {
+            self.nodes[leaf].leaf_handler.iter().map(move |(method, value)| (method, pattern.clone(), value))
         })
     }
 
-    fn insert_path(&mut self, method: &Method, path: String, value: V, param_names: Option<Vec<String>>) {
+    /// Returns the registered patterns which start with `prefix`, sorted, such
+    /// as to complete a pattern typed in a tool.
+    pub fn complete(&self, prefix: &str) -> Vec<String> {
+        let mut patterns = Vec::new();
+        let full_prefix = format!("/{}", strip_start_slash(prefix));
+        self.walk_leaves(ROOT, "/".to_string(), &full_prefix[1..], &mut |_, pattern| {
+            if pattern.starts_with(&full_prefix) {
+                patterns.push(pattern.to_string());
+            }
+        });
+
+        patterns.sort();
+        patterns
+    }
+
+    /// Returns the registered patterns within `max_distance` of the path,
+    /// closest first, to suggest the route meant by a path which matches none.
+    ///
+    /// The distance is the number of segments to insert, remove or replace in
+    /// the path to match the pattern, where a parameter matches any segment and
+    /// a wildcard any number of segments.
+    pub fn suggest(&self, path: &str, max_distance: usize) -> Vec<String> {
+        let path = strip_start_slash(path).to_string();
+        let path_segments: Vec<&str> = path.split('/').collect();
+
+        let mut suggestions = Vec::new();
+        self.walk_leaves(ROOT, "/".to_string(), "", &mut |_, pattern| {
+            let distance = segment_distance(&pattern[1..], &path_segments);
+            if distance <= max_distance {
+                suggestions.push((distance, pattern.to_string()));
+            }
+        });
+
+        suggestions.sort();
+        suggestions.into_iter().map(|(_, pattern)| pattern).collect()
+    }
+
+    /// Returns the routes whose pattern starts with `prefix`, as the method,
+    /// pattern and value of each route.
+    ///
+    /// The prefix is compared with the text of the patterns, so `/api` also
+    /// matches `/apis`, while `/api/` only matches the routes under `/api/`.
+    pub fn iter_prefix(&self, prefix: &str) -> impl Iterator<Item = (&K, String, &V)> {
+        let mut routes = Vec::new();
+        let full_prefix = format!("/{}", strip_start_slash(prefix));
+        self.walk_leaves(ROOT, "/".to_string(), &full_prefix[1..], &mut |leaf, pattern| {
+            if pattern.starts_with(&full_prefix) {
+                for (method, value) in self.nodes[leaf].leaf_handler.iter() {
+                    routes.push((method, pattern.to_string(), value));
+                }
+            }
+        });
+
+        routes.into_iter()
+    }
+
+    /// Calls `f` with the index and pattern of every leaf under the node which
+    /// may start with the rest of the prefix. Param nodes are shared by routes
+    /// with different parameter names, so the caller compares the patterns.
+    pub(crate) fn walk_leaves<F: FnMut(usize, &str)>(&self, id: usize, pattern: String, prefix: &str, f: &mut F) {
+        let node = &self.nodes[id];
+        if !node.leaf_handler.is_empty() {
+            f(id, &leaf_pattern(&pattern, node.leaf_param_names.as_deref()));
+        }
+
+        for (child, text) in self.child_patterns(id, pattern.ends_with('/')) {
+            if let Some(rest) = prefix_rest(prefix, &text) {
+                self.walk_leaves(child, pattern.clone() + &text, rest, f);
+            }
+        }
+    }
+
+    /// Returns the children of the node with their text in a pattern, in the
+    /// order of static, param and wildcard children.
+    fn child_patterns(&self, id: usize, segment_start: bool) -> Vec<(usize, String)> {
+        let node = &self.nodes[id];
+        let mut children: Vec<(usize, String)> = node.static_child.iter()
+            .map(|&child| (child, pattern::escape(&self.nodes[child].path, segment_start).into_owned()))
+            .collect();
+
+        if let Some(param_child) = node.param_child {
+            children.push((param_child, format!("${}", self.nodes[param_child].path)));
+        }
+
+        if let Some(star_child) = node.star_child {
+            children.push((star_child, wildcard_pattern(&self.nodes[star_child].path)));
+        }
+
+        children
+    }
+
+    /// Sets the value of the methods on the leaf. It panics if a method
+    /// already has a value.
+    fn set_handlers(&mut self, leaf: usize, methods: &[K], value: V) {
+        for method in methods {
+            if self.nodes[leaf].leaf_handler.contains_key(method) {
+                panic!("A method of a path only appear once.");
+            }
+        }
+
+        for method in methods {
+            if self.nodes[leaf].leaf_handler.insert(method.clone(), value.clone()).is_none() {
+                self.count_added(method);
+            }
+        }
+    }
+
+    /// Validates the pattern, and returns its leaf node.
+    fn insert_leaf(&mut self, path: &str) -> usize {
+        if let Err(error) = RoutePattern::parse(path) {
+            panic!("{}", error);
+        }
+
+        self.insert_path(ROOT, strip_start_slash(path).to_string(), None)
+    }
+
+    /// Returns the leaf node of the path, creating the nodes along the way.
+    fn insert_path(&mut self, id: usize, path: String, param_names: Option<Vec<String>>) -> usize {
         if path.is_empty() {
-            // Assign a value to self.leaf_param_names
+            // Assign a value to leaf_param_names
             if let Some(ref param_names) = param_names {
                 // Make sure the current path parameters are the same as the old ones.
                 // When they aren't, we have a ambiguous path.
-                if let Some(ref leaf_param_names) = self.leaf_param_names {
+                if let Some(ref leaf_param_names) = self.nodes[id].leaf_param_names {
                     if param_names.len() != leaf_param_names.len() {
                         // This should never happen.
                         panic!("Reached leaf node with differing the number of path parameters. Please report this as a bug.");
@@ -66,363 +511,1619 @@ impl<V: Clone> Node<V> {
                         panic!("Path parameters {:?} are ambiguous with {:?}.", leaf_param_names, param_names);
                     }
                 } else {
-                    self.leaf_param_names = Some(param_names.clone());
+                    self.nodes[id].leaf_param_names = Some(param_names.clone());
                 }
             }
 
-            self.set_handler(method.clone(), value);
-            return;
+            return id;
         }
 
-        let first_char = path.chars().next().unwrap();
-        let next_slash = path.chars().position(|c| c == '/');
+        // Handle the * wildcard and multi-segment parameters at the start of a segment
+        if let Some((wildcard_path, len)) = pattern::wildcard(&path).filter(|_| self.nodes[id].path.ends_with('/')) {
+            // A wildcard is either the last token, or followed by a slash.
+            let remaining_path = &path[len..];
+            if !remaining_path.is_empty() && !remaining_path.starts_with('/') {
+                panic!("Other characters were found after *");
+            }
+
+            let star_child = match self.nodes[id].star_child {
+                Some(star_child) => star_child,
+                None => {
+                    let star_child = self.alloc(NodeData {
+                        path: wildcard_path.into(),
+                        ..Default::default()
+                    });
+                    self.nodes[id].star_child = Some(star_child);
+                    star_child
+                }
+            };
+
+            // Ambiguous wildcards, such as /files/* and /files/$path+.
+            if *self.nodes[star_child].path != *wildcard_path {
+                panic!("Wildcards {:?} and {:?} are ambiguous.", &path[..len], wildcard_pattern(&self.nodes[star_child].path));
+            }
+
+            let param_names = if wildcard_path.is_empty() {
+                param_names
+            } else {
+                Some(push_name(param_names, &wildcard_path[..wildcard_path.len() - 1]))
+            };
+
+            return self.insert_path(star_child, remaining_path.to_string(), param_names);
+        }
 
-        // Token is the path of the current node
-        let (token, token_end) = if first_char == '/' {
-            ("/".to_string(), Some(1))
-        } else if next_slash.is_none() {
-            (path.clone(), Some(path.len()))
+        let first_char = path.chars().next().unwrap();
+        let segment_start = self.nodes[id].path.ends_with('/');
+
+        // Token is the path of the current node. A static token ends at the
+        // next slash or parameter, and a parameter token ends with its name.
+        let token_end = if first_char == '/' {
+            1
+        } else if first_char == '$' {
+            1 + pattern::param_name_len(&path[1..])
         } else {
-            (path.chars().take(next_slash.unwrap_or_default()).collect(), next_slash)
+            let segment_end = path.find('/').unwrap_or(path.len());
+            pattern::static_len(&path, segment_start).min(segment_end)
         };
 
-        let remaining_path = path.chars().skip(token_end.unwrap_or_default()).collect();
+        let token = path[..token_end].to_string();
+        let remaining_path = path[token_end..].to_string();
 
         if first_char == '$' { // Handle path parameters
             // Token is the path of the current node and also the parameter name.
             let token = token[1..].to_string();
 
-            if self.param_child.is_none() {
-                self.param_child = Some(Box::new(Node {
-                    path: token.clone(),
-                    ..Default::default()
-                }));
-            }
+            let param_child = match self.nodes[id].param_child {
+                Some(param_child) => param_child,
+                None => {
+                    let param_child = self.alloc(NodeData {
+                        path: token.as_str().into(),
+                        ..Default::default()
+                    });
+                    self.nodes[id].param_child = Some(param_child);
+                    param_child
+                }
+            };
 
             let param_names = param_names.map(|mut v| {
                 v.push(token.clone());
                 v
             }).or_else(|| Some(vec![token]));
 
-            self.param_child.as_mut().unwrap().insert_path(method, remaining_path, value, param_names);
-        } else if first_char == '*' { // Handle the * wildcard
-            if path != "*" {
-                panic!("Other characters were found after *");
-            }
-
-            if self.star_child.is_none() {
-                self.star_child = Some(Box::new(Node {
-                    ..Default::default()
-                }));
-            }
-
-            self.star_child.as_mut().map(|node| {
-                node.set_handler(method.clone(), value);
-                node.leaf_param_names = param_names;
-            });
+            self.insert_path(param_child, remaining_path, param_names)
         } else { // Handle static path
-            // Do we have an existing node that starts with the same letter?
-            for (i, c) in self.static_indices.clone().iter().enumerate() {
-                if first_char == *c {
-                    // Yes. Split it based on the existing node.
-                    let len = self.split_common_prefix(i, token.clone());
+            // The node stores the static text without escapes.
+            let token = pattern::unescape(&token).into_owned();
+            let first_char = token.chars().next().unwrap();
 
-                    self.static_child.get_mut(i).unwrap().as_mut().map(|v| {
-                        v.insert_path(method, path[len..].to_string(), value.clone(), param_names);
-                    });
-
-                    return;
-                }
+            // Do we have an existing node that starts with the same letter?
+            let position = self.nodes[id].static_indices.binary_search(&first_char);
+            if let Ok(i) = position {
+                // Yes. Split it based on the existing node.
+                self.split_common_prefix(id, i, token);
+
+                let static_child = self.nodes[id].static_child[i];
+                let next_path = pattern::strip_literal(&path, &self.nodes[static_child].path, segment_start).unwrap().to_string();
+                return self.insert_path(static_child, next_path, param_names);
             }
 
-            // No existing node starting with the letter, so create it.
-            let mut child_node = Self {
-                path: token,
+            // No existing node starting with the letter, so create it. The
+            // children are kept sorted by their first letter.
+            let i = position.unwrap_err();
+            let static_child = self.alloc(NodeData {
+                path: token.into(),
                 ..Default::default()
-            };
+            });
+            self.nodes[id].static_indices.insert(i, first_char);
+            self.nodes[id].static_child.insert(i, static_child);
 
-            self.static_indices.push(first_char);
-            child_node.insert_path(method, remaining_path, value, param_names);
-            self.static_child.push(Some(child_node));
+            self.insert_path(static_child, remaining_path, param_names)
         }
     }
 
-    fn remove_path(&mut self, path: String) {
+    /// Returns the node registered for the path, treating parameters and the
+    /// wildcard as part of the pattern instead of matching them.
+    fn find_path(&self, id: usize, path: String) -> Option<usize> {
         if path.is_empty() {
-            self.leaf_handler.clear();
-            self.leaf_param_names = None;
-            return;
+            return Some(id);
         }
 
-        let path_len = path.len();
+        let node = &self.nodes[id];
 
         // First see if this matches a static path
-        let first_char = path.chars().next().unwrap();
-        for (i, c) in self.static_indices.iter().enumerate() {
-            if first_char == *c {
-                let static_child = self.static_child[i].as_mut().unwrap();
-                let static_child_path_len = static_child.path.len();
-                if path_len >= static_child_path_len && path.starts_with(static_child.path.as_str()) {
-                    let next_path = path.chars().skip(static_child_path_len).collect();
-                    static_child.remove_path(next_path);
-
-                    if static_child.leaf_handler.is_empty()
-                        && static_child.static_child.is_empty()
-                        && static_child.param_child.is_none()
-                        && static_child.star_child.is_none() { // Remove static child node
-                        self.static_child.remove(i);
-                        self.static_indices.remove(i);
-                    } else { // Merge nodes
-                        if static_child.leaf_handler.is_empty()
-                            && static_child.static_child.len() == 1
-                            && static_child.param_child.is_none()
-                            && static_child.path.ne("/") {
-                            let static_child_child = static_child.static_child[0].as_mut().unwrap();
-                            if static_child_child.path.ne("/") {
-                                let static_child_path = static_child.path.clone();
-                                static_child_child.path = static_child_path + static_child_child.path.as_str();
-                                self.static_child[i] = Some(static_child_child.clone());
-                            }
-                        }
-                    }
+        if let Some((i, next_path)) = self.strip_static_child(id, &path) {
+            return self.find_path(node.static_child[i], next_path);
+        }
 
-                    return;
+        // Check for a wildcard * or a multi-segment parameter.
+        if let Some((wildcard_path, len)) = pattern::wildcard(&path) {
+            if let Some(star_child) = node.star_child {
+                if *self.nodes[star_child].path == *wildcard_path {
+                    return self.find_path(star_child, path[len..].to_string());
                 }
-
-                break;
             }
         }
 
         // Didn't find a static path, so check for a path parameter.
-        if path.starts_with("$") {
-            if let Some(ref mut param_child) = self.param_child {
-                let next_slash = path.chars().position(|c| c == '/').unwrap_or(path_len);
-                let next_path = path.chars().skip(next_slash).collect();
-                param_child.remove_path(next_path);
-
-                // Remove param child node
-                if param_child.leaf_handler.is_empty() && param_child.static_child.is_empty() {
-                    self.param_child = None;
-                }
-                return;
+        if let Some(name) = path.strip_prefix('$') {
+            if let Some(param_child) = node.param_child {
+                let next_path = name[pattern::param_name_len(name)..].to_string();
+                return self.find_path(param_child, next_path);
             }
         }
 
-        // Finally check for a wildcard *
-        if path.starts_with("*") {
-            if self.star_child.is_some() {
-                // Remove wildcard * child node
-                self.star_child = None;
-            }
-        }
+        None
     }
 
-    fn update_path(&mut self, method: &Method, path: String, value: V) {
-        if path.is_empty() {
-            self.update_handler(method.clone(), value);
-            return;
+    /// Inserts the value for each of the methods, resolving conflicts with the
+    /// policy. Invalid patterns and shadowing wildcards panic unless the policy
+    /// is `ConflictPolicy::Error`.
+    ///
+    /// Returns the methods whose value was set.
+    pub(crate) fn insert_with(&mut self, methods: &[K], path: &str, value: V, policy: ConflictPolicy) -> Result<Vec<K>, ConflictKind> {
+        if policy == ConflictPolicy::Panic {
+            let leaf = self.insert_leaf(path);
+            self.set_handlers(leaf, methods, value);
+            return Ok(methods.to_vec());
         }
 
-        let path_len = path.len();
+        if let Err(error) = RoutePattern::parse(path) {
+            if policy == ConflictPolicy::Error {
+                return Err(ConflictKind::InvalidPattern(error));
+            }
+            panic!("{}", error);
+        }
 
-        // First see if this matches a static path
-        let first_char = path.chars().next().unwrap();
-        for (i, c) in self.static_indices.iter().enumerate() {
-            if first_char == *c {
-                let static_child = self.static_child[i].as_mut().unwrap();
-                let static_child_path_len = static_child.path.len();
-                if path_len >= static_child_path_len && path.starts_with(static_child.path.as_str()) {
-                    let next_path = path.chars().skip(static_child_path_len).collect();
-                    static_child.update_path(method, next_path, value);
-                    return;
-                }
-                break;
+        let path = strip_start_slash(path).to_string();
+        let mut set_methods = Vec::new();
+        let mut rename = false;
+        for method in methods {
+            match self.find_conflict(ROOT, method, &path, Vec::new()) {
+                None => set_methods.push(method.clone()),
+                Some(kind) if policy == ConflictPolicy::Error => return Err(kind),
+                Some(ConflictKind::Duplicate) => if policy == ConflictPolicy::Overwrite {
+                    set_methods.push(method.clone());
+                },
+                Some(ConflictKind::AmbiguousParams(_)) => if policy == ConflictPolicy::Overwrite {
+                    rename = true;
+                    set_methods.push(method.clone());
+                } else {
+                    return Ok(Vec::new());
+                },
+                // Let insert_path panic with its message.
+                Some(_) => break,
             }
         }
 
-        // Didn't find a static path, so check for a path parameter.
-        if path.starts_with("$") {
-            if let Some(ref mut param_child) = self.param_child {
-                let next_slash = path.chars().position(|c| c == '/').unwrap_or(path_len);
-                let next_path: String = path.chars().skip(next_slash).collect();
-                param_child.update_path(method, next_path, value);
-                return;
+        if rename {
+            // The new parameter names replace the names of the leaf.
+            if let Some(leaf) = self.find_path(ROOT, path.clone()) {
+                self.nodes[leaf].leaf_param_names = None;
             }
         }
 
-        // Finally check for a wildcard *
-        if path.starts_with("*") {
-            if let Some(ref mut star_child) = self.star_child {
-                star_child.update_handler(method.clone(), value);
+        let leaf = self.insert_path(ROOT, path, None);
+        for method in set_methods.iter() {
+            if self.nodes[leaf].leaf_handler.insert(method.clone(), value.clone()).is_none() {
+                self.count_added(method);
             }
         }
+
+        Ok(set_methods)
     }
 
-    fn internal_search(&self, method: &Method, path: String) -> Option<MatchResult<V>> {
+    /// Follows the path like `insert_path`, returning the conflict of the new
+    /// route. The path can't conflict once it leaves the existing nodes.
+    fn find_conflict(&self, id: usize, method: &K, path: &str, mut param_names: Vec<String>) -> Option<ConflictKind> {
+        let node = &self.nodes[id];
         if path.is_empty() {
-            if self.leaf_handler.is_empty() {
-                return None;
-            }
-
-            return Some(MatchResult {
-                value: self.leaf_handler.get(method).cloned(),
-                param_names: self.leaf_param_names.clone().unwrap_or_default(),
-                param_values: Vec::new(),
-            });
+            return match node.leaf_param_names {
+                Some(ref leaf_param_names) if *leaf_param_names != param_names => Some(ConflictKind::AmbiguousParams(leaf_param_names.clone())),
+                _ if node.leaf_handler.contains_key(method) => Some(ConflictKind::Duplicate),
+                _ => None,
+            };
         }
 
-        let path_len = path.len();
+        if let Some((wildcard_path, len)) = pattern::wildcard(path).filter(|_| node.path.ends_with('/')) {
+            let star_child = node.star_child?;
+            if *self.nodes[star_child].path != *wildcard_path {
+                return Some(ConflictKind::Shadowing(wildcard_pattern(&self.nodes[star_child].path)));
+            }
 
-        // First see if this matches a static path
-        let first_char = path.chars().next().unwrap();
-        let mut found = None;
-        for (i, c) in self.static_indices.iter().enumerate() {
-            if first_char == *c {
-                let static_child = self.static_child[i].as_ref().unwrap();
-                let static_child_path_len = static_child.path.len();
-                if path_len >= static_child_path_len && path.starts_with(static_child.path.as_str()) {
-                    let next_path = path.chars().skip(static_child_path_len).collect();
-                    found = static_child.internal_search(method, next_path);
-                }
-                break;
+            if !wildcard_path.is_empty() {
+                param_names.push(wildcard_path[..wildcard_path.len() - 1].to_string());
             }
+            return self.find_conflict(star_child, method, &path[len..], param_names);
         }
 
-        // If we find a node and it has a valid handler, then return here.
-        if found.as_ref().filter(|v| v.value.is_some()).is_some() {
-            return found;
+        if let Some(name) = path.strip_prefix('$') {
+            let len = pattern::param_name_len(name);
+            param_names.push(name[..len].to_string());
+            return self.find_conflict(node.param_child?, method, &name[len..], param_names);
         }
 
-        // Didn't find a static path, so check for a path parameter.
-        if let Some(ref param_child) = self.param_child {
-            let next_slash = path.chars().position(|c| c == '/').unwrap_or(path_len);
-            // Value is the parameter value
-            let value: String = path.chars().take(next_slash).collect();
-            let next_path: String = path.chars().skip(next_slash).collect();
+        let (i, next_path) = self.strip_static_child(id, path)?;
+        self.find_conflict(node.static_child[i], method, &next_path, param_names)
+    }
 
-            if !value.is_empty() { // Don't match on empty value
-                let match_result = param_child.internal_search(method, next_path);
+    /// Removes the handlers of the methods, or every handler when methods is None.
+    ///
+    /// Returns the removed handlers.
+    fn remove_path(&mut self, id: usize, methods: Option<&[K]>, path: String) -> Vec<(K, V)> {
+        if path.is_empty() {
+            return self.remove_handlers(id, methods);
+        }
 
-                if match_result.as_ref().filter(|v| v.value.is_some()).is_some() {
-                    // Handle the values of the path parameters
-                    if let Some(mut match_result) = match_result {
-                        let mut param_values = vec![value];
-                        param_values.append(&mut match_result.param_values.to_vec());
-                        match_result.param_values = param_values;
+        // First see if this matches a static path
+        if let Some((i, next_path)) = self.strip_static_child(id, &path) {
+            let static_child = self.nodes[id].static_child[i];
+            let removed = self.remove_path(static_child, methods, next_path);
+            self.prune_child(id, static_child);
+            return removed;
+        }
 
-                        return Some(match_result);
-                    }
+        // Check for a wildcard * or a multi-segment parameter.
+        if let Some((wildcard_path, len)) = pattern::wildcard(&path) {
+            if let Some(star_child) = self.nodes[id].star_child {
+                if *self.nodes[star_child].path == *wildcard_path {
+                    let removed = self.remove_path(star_child, methods, path[len..].to_string());
+                    self.prune_child(id, star_child);
+                    return removed;
                 }
             }
         }
 
-        // Finally check for a wildcard *
-        if let Some(ref star_child) = self.star_child {
-            let value = star_child.leaf_handler.get(method);
-
-            if value.is_some() {
-                return Some(MatchResult {
-                    value: value.cloned(),
-                    param_names: star_child.leaf_param_names.clone().unwrap_or_default(),
-                    param_values: Vec::new(),
-                });
+        // Didn't find a static path, so check for a path parameter.
+        if let Some(name) = path.strip_prefix('$') {
+            if let Some(param_child) = self.nodes[id].param_child {
+                let next_path = name[pattern::param_name_len(name)..].to_string();
+                let removed = self.remove_path(param_child, methods, next_path);
+                self.prune_child(id, param_child);
+                return removed;
             }
         }
 
-        None
+        Vec::new()
     }
 
-    /// Returns the length of the common prefix
-    fn split_common_prefix(&mut self, existing_node_index: usize, path: String) -> usize {
-        let child_node = self.static_child.get(existing_node_index).unwrap().as_ref().unwrap();
-
-        if path.starts_with(child_node.path.as_str()) {
-            // No split needs to be done. Rather, the new path shares the entire
-            // prefix with the existing node, so the new node is just a child of
-            // the existing node. Or the new path is the same as the existing path,
-            // which means that we just move on to the next token.
-            let len = child_node.path.len();
-            return len;
+    /// Removes the handlers of the methods from the node, or every handler
+    /// when methods is None, and returns them.
+    fn remove_handlers(&mut self, id: usize, methods: Option<&[K]>) -> Vec<(K, V)> {
+        let removed = self.nodes[id].remove_handlers(methods);
+        for (key, _) in removed.iter() {
+            if let Some(count) = self.counts.get_mut(key) {
+                *count -= 1;
+                if *count == 0 {
+                    self.counts.remove(key);
+                }
+            }
         }
+        removed
+    }
 
-        let len = path.chars().zip(child_node.path.chars()).take_while(|(l, r)| l == r).count();
-        let common_prefix = path[..len].to_string();
-        let child_path = child_node.path.chars().skip(len).collect::<String>();
-        let new_node = Self {
-            path: common_prefix,
-            static_indices: vec![child_path.chars().next().unwrap()],
-            ..Default::default()
-        };
-
-        // Use new_node to replace child_node
-        let mut old_node = self.static_child[existing_node_index].replace(new_node);
-        old_node.as_mut().map(|v| {
-            v.path = child_path;
+    /// Removes every route whose pattern starts with `prefix`, merging the
+    /// remaining nodes afterwards.
+    ///
+    /// Returns the method, pattern and value of the removed routes. Like
+    /// `iter_prefix`, the prefix is compared with the text of the patterns.
+    pub fn remove_prefix(&mut self, prefix: &str) -> Vec<(K, String, V)> {
+        let full_prefix = format!("/{}", strip_start_slash(prefix));
+
+        let mut leaves = Vec::new();
+        self.walk_leaves(ROOT, "/".to_string(), &full_prefix[1..], &mut |leaf, pattern| {
+            if pattern.starts_with(&full_prefix) {
+                leaves.push((leaf, pattern.to_string()));
+            }
         });
 
-        // Old_node as a child node
-        self.static_child.get_mut(existing_node_index).unwrap().as_mut().map(|v| {
-            v.static_child.push(old_node);
-        });
+        let mut removed = Vec::new();
+        for (leaf, pattern) in leaves {
+            let handlers = self.remove_handlers(leaf, None);
+            removed.extend(handlers.into_iter().map(|(method, value)| (method, pattern.clone(), value)));
+        }
 
-        len
+        self.prune_prefix(ROOT, true, &full_prefix[1..]);
+        removed
     }
 
-    fn set_handler(&mut self, method: Method, value: V) {
-        if self.leaf_handler.contains_key(&method) {
-            panic!("A method of a path only appear once.");
+    /// Moves every route whose pattern starts with `prefix` into a new tree,
+    /// like `BTreeMap::split_off`. The routes keep their whole patterns.
+    pub fn split_off(&mut self, prefix: &str) -> Self {
+        let mut tree = Self::new();
+        for (method, pattern, value) in self.remove_prefix(prefix) {
+            tree.insert(method, &pattern, value);
         }
 
-        self.leaf_handler.insert(method, value);
+        tree
     }
 
-    fn update_handler(&mut self, method: Method, value: V) {
-        if !self.leaf_handler.contains_key(&method) {
-            panic!("This method does not exist for this path.");
+    /// Moves the routes of `other` into this tree. When both trees have a
+    /// route for the same method and pattern, `resolver` is called with the
+    /// existing and the incoming value to pick the value to keep.
+    ///
+    /// Ambiguous parameters and wildcards can't be resolved with values, so
+    /// they are returned as an error before any route is moved.
+    pub fn merge<F: FnMut(&V, &V) -> Resolution<V>>(&mut self, mut other: Self, mut resolver: F) -> Result<(), ConflictKind> {
+        for (method, pattern, _) in other.iter_prefix("") {
+            match self.find_conflict(ROOT, method, strip_start_slash(&pattern), Vec::new()) {
+                None | Some(ConflictKind::Duplicate) => {}
+                Some(kind) => return Err(kind),
+            }
         }
 
-        self.leaf_handler.insert(method, value);
-    }
-}
+        for (method, pattern, value) in other.remove_prefix("") {
+            let leaf = self.insert_path(ROOT, strip_start_slash(&pattern).to_string(), None);
+            let value = match self.nodes[leaf].leaf_handler.get(&method) {
+                Some(existing) => match resolver(existing, &value) {
+                    Resolution::Existing => continue,
+                    Resolution::Incoming => value,
+                    Resolution::Merged(merged) => merged,
+                },
+                None => {
+                    self.count_added(&method);
+                    value
+                }
+            };
 
-impl<V> Default for Node<V> {
-    fn default() -> Self {
-        Self {
-            path: "".to_string(),
-            static_indices: Vec::new(),
-            static_child: Vec::new(),
-            param_child: None,
-            star_child: None,
-            leaf_handler: HashMap::new(),
-            leaf_param_names: None,
+            self.nodes[leaf].leaf_handler.insert(method, value);
         }
+
+        Ok(())
     }
-}
 
-/// The response returned when getting the value for a specific path.
-#[derive(Debug)]
-pub struct SearchResult<V> {
-    value: V,
-    /// The path parameters
-    params: Vec<Param>,
-}
+    /// Removes the unused nodes under the node which may start with the rest
+    /// of the prefix, and merges the remaining ones.
+    fn prune_prefix(&mut self, id: usize, segment_start: bool, prefix: &str) {
+        for (child, text) in self.child_patterns(id, segment_start) {
+            if let Some(rest) = prefix_rest(prefix, &text) {
+                self.prune_prefix(child, text.ends_with('/'), rest);
+                self.prune_child(id, child);
+            }
+        }
+    }
 
-impl<V> SearchResult<V> {
-    pub fn value(&self) -> &V {
-        &self.value
+    /// Removes the child node when it has become unused, or merges a static
+    /// child with its only static child.
+    fn prune_child(&mut self, id: usize, child: usize) {
+        let node = &self.nodes[child];
+        let parent = &self.nodes[id];
+
+        if node.is_unused() {
+            let parent = &mut self.nodes[id];
+            if parent.param_child == Some(child) { // Remove param child node
+                parent.param_child = None;
+            } else if parent.star_child == Some(child) { // Remove wildcard * child node
+                parent.star_child = None;
+            } else if let Some(i) = parent.static_child.iter().position(|&c| c == child) { // Remove static child node
+                parent.static_child.remove(i);
+                parent.static_indices.remove(i);
+            }
+            self.dealloc(child);
+        } else if let Some(i) = parent.static_child.iter().position(|&c| c == child) { // Merge nodes
+            if node.leaf_handler.is_empty()
+                && node.static_child.len() == 1
+                && node.param_child.is_none()
+                && node.star_child.is_none()
+                && &*node.path != "/" {
+                let static_child_child = node.static_child[0];
+                if &*self.nodes[static_child_child].path != "/" {
+                    let path = format!("{}{}", node.path, self.nodes[static_child_child].path);
+                    self.nodes[static_child_child].path = path.into();
+                    self.nodes[id].static_child[i] = static_child_child;
+                    self.dealloc(child);
+                }
+            }
+        }
     }
 
-    pub fn params(&self) -> &Vec<Param> {
-        &self.params
+    /// Returns the index of the static child at the start of the pattern, and
+    /// the rest of the pattern.
+    fn strip_static_child(&self, id: usize, path: &str) -> Option<(usize, String)> {
+        let node = &self.nodes[id];
+        node.static_child.iter().enumerate().find_map(|(i, &child)| {
+            pattern::strip_literal(path, &self.nodes[child].path, node.path.ends_with('/'))
+                .map(|next_path| (i, next_path.to_string()))
+        })
     }
-}
 
-/// Param is a single path parameter, consisting of a name and a value.
-#[derive(Debug)]
-pub struct Param {
-    name: String,
-    value: String,
-}
+    /// Returns the match of the highest priority, trying the static child, then
+    /// the param child and finally the wildcard child. When a branch fails at
+    /// any depth, the search backtracks and tries the next branch.
+    fn internal_search(&self, id: usize, keys: &[K], path: String, trace: &mut Trace, filter: &mut LeafFilter<K>) -> Option<MatchResult<V>> {
+        trace.record(|| Step::Enter { node: self.nodes[id].path.to_string(), rest: path.clone() });
+        filter.enter(id);
+        let found = self.search_children(id, keys, path, trace, filter);
+        filter.leave();
+
+        let Some(mut found) = found else {
+            trace.record(|| Step::Leave { node: self.nodes[id].path.to_string() });
+            return None;
+        };
+
+        found.route.push(id);
+        Some(found)
+    }
+
+    /// Returns the match of the node or of one of its children.
+    fn search_children(&self, id: usize, keys: &[K], path: String, trace: &mut Trace, filter: &mut LeafFilter<K>) -> Option<MatchResult<V>> {
+        let node = &self.nodes[id];
+        if path.is_empty() {
+            let found = self.leaf_handler(id, keys, filter).map(|value| MatchResult {
+                value: value.clone(),
+                param_names: node.leaf_param_names.clone().unwrap_or_default(),
+                param_offsets: Vec::new(),
+                route: Vec::new(),
+            });
+            trace.record(|| match found {
+                Some(_) => Step::Matched { node: node.path.to_string() },
+                None => Step::NoHandler { node: node.path.to_string() },
+            });
+
+            // A `$path*` parameter also matches an empty path.
+            return match node.star_child {
+                Some(star_child) if found.is_none() && self.nodes[star_child].path.ends_with('*') => self.wildcard_match(star_child, keys, &path, trace, filter),
+                _ => found,
+            };
+        }
+
+        // First see if this matches a static path
+        let first_char = path.chars().next().unwrap();
+        match node.static_indices.binary_search(&first_char) {
+            Ok(i) => {
+                let static_child = node.static_child[i];
+                match path.strip_prefix(&*self.nodes[static_child].path) {
+                    Some(next_path) => {
+                        let found = self.internal_search(static_child, keys, next_path.to_string(), trace, filter);
+
+                        // If we find a node and it has a valid handler, then return here.
+                        if found.is_some() {
+                            return found;
+                        }
+                    }
+                    None => trace.record(|| Step::StaticMismatch {
+                        child: self.nodes[static_child].path.to_string(),
+                        rest: path.clone(),
+                    }),
+                }
+            }
+            Err(_) if !node.static_child.is_empty() => trace.record(|| Step::NoStaticChild { first: first_char }),
+            Err(_) => {}
+        }
+
+        // Didn't find a static path, so check for a path parameter.
+        if let Some(param_child) = node.param_child {
+            for value_end in self.param_value_ends(param_child, &path) {
+                let next_path = path[value_end..].to_string();
+                trace.record(|| Step::TryParam {
+                    name: self.nodes[param_child].path.to_string(),
+                    value: path[..value_end].to_string(),
+                });
+
+                filter.push_value(&path[..value_end]);
+                let found = self.internal_search(param_child, keys, next_path, trace, filter);
+                filter.pop_value();
+
+                if let Some(mut match_result) = found {
+                    // Handle the values of the path parameters
+                    match_result.param_offsets.insert(0, (path.len(), path.len() - value_end));
+                    return Some(match_result);
+                }
+            }
+        }
+
+        // Finally check for a wildcard *
+        if let Some(star_child) = node.star_child {
+            // A wildcard in the middle of a pattern matches one or more
+            // segments, trying the fewest segments first.
+            if self.nodes[star_child].has_children() {
+                for (next_slash, _) in path.match_indices('/').filter(|(i, _)| *i > 0) {
+                    trace.record(|| Step::TryWildcard { value: path[..next_slash].to_string() });
+                    let found = self.internal_search(star_child, keys, path[next_slash..].to_string(), trace, filter);
+                    if found.is_some() {
+                        return found;
+                    }
+                }
+            }
+
+            // A wildcard at the end of a pattern matches the rest of the path.
+            trace.record(|| Step::Wildcard { value: path.clone() });
+            return self.wildcard_match(star_child, keys, &path, trace, filter);
+        }
+
+        None
+    }
+
+    /// Returns the match of the wildcard node at the end of a pattern. The
+    /// rest of the path is the value of a multi-segment parameter.
+    fn wildcard_match(&self, id: usize, keys: &[K], path: &str, trace: &mut Trace, filter: &mut LeafFilter<K>) -> Option<MatchResult<V>> {
+        let node = &self.nodes[id];
+        filter.enter(id);
+        if !node.path.is_empty() {
+            filter.push_value(path);
+        }
+        let value = self.leaf_handler(id, keys, filter);
+        if !node.path.is_empty() {
+            filter.pop_value();
+        }
+        filter.leave();
+
+        let found = value.map(|value| MatchResult {
+            value: value.clone(),
+            param_names: node.leaf_param_names.clone().unwrap_or_default(),
+            param_offsets: if node.path.is_empty() { Vec::new() } else { vec![(path.len(), 0)] },
+            route: vec![id],
+        });
+        trace.record(|| match found {
+            Some(_) => Step::Matched { node: node.path.to_string() },
+            None => Step::NoHandler { node: node.path.to_string() },
+        });
+
+        found
+    }
+
+    /// Returns the handler of the first of the keys which has one at the leaf
+    /// and which the filter accepts, given the pattern of the leaf and the
+    /// parameters.
+    fn leaf_handler(&self, id: usize, keys: &[K], filter: &LeafFilter<K>) -> Option<&V> {
+        let Some(accept) = filter.accept else {
+            return self.nodes[id].get_handler(keys);
+        };
+
+        let mut matched = None;
+        keys.iter().find_map(|key| {
+            let value = self.nodes[id].leaf_handler.get(key)?;
+            let (pattern, params) = matched.get_or_insert_with(|| {
+                let names = self.nodes[id].leaf_param_names.iter().flatten();
+                let params = names.zip(filter.values.iter())
+                    .map(|(name, value)| Param::new(name.clone(), value.clone()))
+                    .collect();
+                (self.route_pattern(&filter.route), Params(params))
+            });
+            accept(pattern, key, params).then_some(value)
+        })
+    }
+
+    /// Returns the possible ends of the value of the param node at the start
+    /// of the path, shortest first.
+    ///
+    /// The value is the whole path segment, unless static text follows the
+    /// parameter in the same segment, such as `$name.json`. Empty values never match.
+    fn param_value_ends(&self, id: usize, path: &str) -> Vec<usize> {
+        let segment_end = path.find('/').unwrap_or(path.len());
+        let in_segment = self.nodes[id].static_child.iter()
+            .any(|&child| !self.nodes[child].path.starts_with('/'));
+
+        if in_segment {
+            path[..segment_end].char_indices()
+                .skip(1)
+                .map(|(i, _)| i)
+                .chain((segment_end > 0).then_some(segment_end))
+                .collect()
+        } else if segment_end > 0 {
+            vec![segment_end]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Splits the static child at the common prefix of its path and `path`,
+    /// returning the length in bytes of the common prefix.
+    fn split_common_prefix(&mut self, id: usize, existing_node_index: usize, path: String) -> usize {
+        let child = self.nodes[id].static_child[existing_node_index];
+        let child_node = &self.nodes[child];
+
+        if path.starts_with(&*child_node.path) {
+            // No split needs to be done. Rather, the new path shares the entire
+            // prefix with the existing node, so the new node is just a child of
+            // the existing node. Or the new path is the same as the existing path,
+            // which means that we just move on to the next token.
+            let len = child_node.path.len();
+            return len;
+        }
+
+        let len = common_prefix_len(&path, &child_node.path);
+        let common_prefix = path[..len].to_string();
+        let child_path = child_node.path[len..].to_string();
+
+        // The new node replaces the existing node, which becomes its child.
+        let new_node = self.alloc(NodeData {
+            path: common_prefix.into(),
+            static_indices: vec![child_path.chars().next().unwrap()],
+            static_child: vec![child],
+            ..Default::default()
+        });
+        self.nodes[child].path = child_path.into();
+        self.nodes[id].static_child[existing_node_index] = new_node;
+
+        len
+    }
+}
+
+impl<V: Clone> Node<V> {
+    /// Creates a tree from a listing of `dump`, with the value returned by
+    /// `f` for each route. Empty lines and lines starting with `#` are
+    /// skipped.
+    pub fn from_dump<F: FnMut(&Method, &str) -> V>(text: &str, mut f: F) -> Result<Self, DumpError> {
+        let mut tree = Node::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let method = line.split_once(' ')
+                .and_then(|(method, pattern)| Some((Method::from_name(method).ok()?, pattern)));
+            let Some((method, pattern)) = method else {
+                return Err(DumpError::InvalidLine(index + 1));
+            };
+
+            let value = f(&method, pattern);
+            tree.insert_with(&[method], pattern, value, ConflictPolicy::Error)
+                .map_err(|kind| DumpError::Conflict(index + 1, kind))?;
+        }
+
+        Ok(tree)
+    }
+
+    /// Compiles the tree into a read-only router for faster lookups.
+    pub fn compile(&self) -> CompiledRouter<V> {
+        CompiledRouter::new(self, SearchOptions::default())
+    }
+
+    /// Returns every method which has a handler for the path.
+    ///
+    /// The set contains `Method::ANY` if any method is accepted. Extension
+    /// methods aren't included.
+    pub fn allowed_methods(&self, path: &str) -> MethodSet {
+        let mut methods = MethodSet::new();
+        self.collect_methods(ROOT, strip_start_slash(path), &mut methods);
+        methods
+    }
+
+    /// Searches like `search`, falling back to the handler of GET for HEAD
+    /// when enabled, and then to the handler of `Method::ANY`.
+    pub(crate) fn search_with(&self, method: Method, path: &str, options: &SearchOptions) -> Option<SearchResult<V>> {
+        self.search_with_trace(method, path, options, &mut Trace::off(), &mut LeafFilter::off())
+    }
+
+    /// Searches like `search_with`, skipping the handlers which `accept`
+    /// rejects, given the pattern of their leaf and their method, as if they
+    /// weren't registered.
+    pub(crate) fn search_filtered(&self, method: Method, path: &str, options: &SearchOptions, accept: &Accept<Method>) -> Option<SearchResult<V>> {
+        self.search_with_trace(method, path, options, &mut Trace::off(), &mut LeafFilter::new(accept))
+    }
+
+    /// Searches like `search_with`, returning the match instead of its result,
+    /// to be cached.
+    #[cfg(feature = "std")]
+    pub(crate) fn search_matched(&self, method: Method, path: &str, options: &SearchOptions) -> Option<Matched> {
+        let keys = if options.head_can_use_get && method == Method::HEAD {
+            vec![Method::HEAD, Method::GET, Method::ANY]
+        } else {
+            vec![method, Method::ANY]
+        };
+
+        let path = path.strip_prefix('/').unwrap_or(path);
+        let mut match_result = self.internal_search(ROOT, &keys, path.to_string(), &mut Trace::off(), &mut LeafFilter::off())?;
+        match_result.route.reverse();
+        let leaf = match_result.route[match_result.route.len() - 1];
+        let key = keys.into_iter().find(|key| self.nodes[leaf].leaf_handler.contains_key(key))?;
+        let pattern = self.route_pattern(&match_result.route);
+        Some(Matched { key, leaf, pattern, param_offsets: match_result.param_offsets })
+    }
+
+    /// Returns the result of a match of `search_matched` for the same path.
+    #[cfg(feature = "std")]
+    pub(crate) fn matched_result(&self, path: &str, matched: &Matched) -> Option<SearchResult<V>> {
+        let path = path.strip_prefix('/').unwrap_or(path);
+        let leaf = &self.nodes[matched.leaf];
+        let value = leaf.leaf_handler.get(&matched.key)?.clone();
+        let names = leaf.leaf_param_names.as_deref().unwrap_or_default();
+        let params = offset_params(names, &matched.param_offsets, path);
+        Some(SearchResult::new(value, params, matched.pattern.clone()))
+    }
+
+    /// Searches the longest prefix like `search_with`.
+    pub(crate) fn longest_prefix_with<'p>(&self, method: Method, path: &'p str, options: &SearchOptions) -> Option<(String, &V, &'p str)> {
+        if options.head_can_use_get && method == Method::HEAD {
+            self.longest_prefix_keys(&[Method::HEAD, Method::GET, Method::ANY], path)
+        } else {
+            self.longest_prefix_keys(&[method, Method::ANY], path)
+        }
+    }
+
+    /// Explains a search like `search_with`.
+    pub(crate) fn explain_with(&self, method: Method, path: &str, options: &SearchOptions) -> Explanation<V> {
+        let mut trace = Trace::on();
+        let result = self.search_with_trace(method, path, options, &mut trace, &mut LeafFilter::off());
+        Explanation::new(result, trace.into_steps())
+    }
+
+    fn search_with_trace(&self, method: Method, path: &str, options: &SearchOptions, trace: &mut Trace, filter: &mut LeafFilter<Method>) -> Option<SearchResult<V>> {
+        if options.head_can_use_get && method == Method::HEAD {
+            self.search_keys(&[Method::HEAD, Method::GET, Method::ANY], path, trace, filter)
+        } else {
+            self.search_keys(&[method, Method::ANY], path, trace, filter)
+        }
+    }
+
+    /// Returns a hash of the methods and patterns of the routes, which doesn't
+    /// depend on the insertion order or the values.
+    ///
+    /// The hash is stable across runs and builds, so instances can compare
+    /// their routes by the fingerprint.
+    pub fn fingerprint(&self) -> u64 {
+        let mut fingerprint: u64 = 0;
+        self.for_each_route(&mut |method, pattern, _| {
+            // The route hashes are added, so that the order doesn't matter.
+            let route = fnv1a(fnv1a(FNV_OFFSET, method.as_str().as_bytes()), pattern.as_bytes());
+            fingerprint = fingerprint.wrapping_add(route);
+        });
+
+        fnv1a(FNV_OFFSET, &fingerprint.to_le_bytes())
+    }
+
+    /// Adds the methods of every leaf which matches the path to `methods`.
+    fn collect_methods(&self, id: usize, path: &str, methods: &mut MethodSet) {
+        let node = &self.nodes[id];
+        if path.is_empty() {
+            methods.extend(node.leaf_handler.keys().cloned());
+
+            // A `$path*` parameter also matches an empty path.
+            if let Some(star_child) = node.star_child {
+                if self.nodes[star_child].path.ends_with('*') {
+                    methods.extend(self.nodes[star_child].leaf_handler.keys().cloned());
+                }
+            }
+            return;
+        }
+
+        // Static paths
+        let first_char = path.chars().next().unwrap();
+        if let Ok(i) = node.static_indices.binary_search(&first_char) {
+            let static_child = node.static_child[i];
+            if let Some(next_path) = path.strip_prefix(&*self.nodes[static_child].path) {
+                self.collect_methods(static_child, next_path, methods);
+            }
+        }
+
+        // Path parameters
+        if let Some(param_child) = node.param_child {
+            for value_end in self.param_value_ends(param_child, path) {
+                self.collect_methods(param_child, &path[value_end..], methods);
+            }
+        }
+
+        // Wildcard * and multi-segment parameters
+        if let Some(star_child) = node.star_child {
+            for (next_slash, _) in path.match_indices('/').filter(|(i, _)| *i > 0) {
+                self.collect_methods(star_child, &path[next_slash..], methods);
+            }
+
+            methods.extend(self.nodes[star_child].leaf_handler.keys().cloned());
+        }
+    }
+}
+
+impl<V> Node<V> {
+    /// Builds a node from its parts. It is used by the source generated with
+    /// `codegen::RouteTable`, which has already checked the routes.
+    #[doc(hidden)]
+    pub fn __from_parts(
+        path: &str,
+        mut static_child: Vec<Self>,
+        param_child: Option<Self>,
+        star_child: Option<Self>,
+        leaf_handler: Vec<(Method, V)>,
+        leaf_param_names: Option<&[&str]>,
+    ) -> Self {
+        static_child.sort_by_key(|child| child.nodes[ROOT].path.chars().next());
+
+        let mut tree = Self::with_root(NodeData {
+            path: path.into(),
+            static_indices: static_child.iter().map(|child| child.nodes[ROOT].path.chars().next().unwrap()).collect(),
+            leaf_handler: leaf_handler.into_iter().collect(),
+            leaf_param_names: leaf_param_names.map(|names| names.iter().map(|name| name.to_string()).collect()),
+            ..Default::default()
+        });
+
+        let mut graft = |mut child: Self| {
+            for (key, count) in child.counts.iter() {
+                *tree.counts.get_or_insert(key.clone(), 0) += count;
+            }
+            tree.graft(&mut child, ROOT)
+        };
+        let static_child = static_child.into_iter().map(&mut graft).collect();
+        let param_child = param_child.map(&mut graft);
+        let star_child = star_child.map(&mut graft);
+
+        let root = &mut tree.nodes[ROOT];
+        root.static_child = static_child;
+        root.param_child = param_child;
+        root.star_child = star_child;
+        tree
+    }
+}
+
+impl<V, K: Discriminator> Node<V, K> {
+    /// Removes every route.
+    pub fn clear(&mut self) {
+        let root = NodeData {
+            path: core::mem::take(&mut self.nodes[ROOT].path),
+            ..Default::default()
+        };
+        *self = Self::with_root(root);
+    }
+
+    /// Returns the number of routes, counting each method of a path.
+    pub fn len(&self) -> usize {
+        self.counts.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Returns true if the tree has no routes.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Returns the number of nodes on the longest chain from the root to a
+    /// leaf, which bounds how deep a search recurses.
+    pub fn depth(&self) -> usize {
+        self.node_depth(ROOT)
+    }
+
+    fn node_depth(&self, id: usize) -> usize {
+        let node = &self.nodes[id];
+        let children = node.static_child.iter().copied().chain(node.param_child).chain(node.star_child);
+        1 + children.map(|child| self.node_depth(child)).max().unwrap_or(0)
+    }
+
+    /// Returns the number of paths which have a handler for the method.
+    pub fn count_method(&self, method: K) -> usize {
+        self.counts.get(&method).copied().unwrap_or(0)
+    }
+
+    /// Checks the structural invariants of the tree, returning every violation
+    /// found. A tree changed only through its methods has none, so a violation
+    /// is a bug, which is worth reporting with the output of `dump`.
+    pub fn validate(&self) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        let mut visited = vec![false; self.nodes.len()];
+        for &id in self.free.iter() {
+            if visited.get(id).is_none_or(|&visited| visited) || id == ROOT {
+                violations.push(Violation::InvalidFreeList(id));
+            } else {
+                visited[id] = true;
+            }
+        }
+
+        if !self.nodes.is_empty() {
+            visited[ROOT] = true;
+            self.validate_node(ROOT, "/".to_string(), 0, &mut visited, &mut violations);
+        }
+
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(violations),
+        }
+    }
+
+    /// Checks the node and its descendants, whose pattern is `pattern` and
+    /// which are under `params` parameters.
+    fn validate_node(&self, id: usize, pattern: String, params: usize, visited: &mut [bool], violations: &mut Vec<Violation>) {
+        let node = &self.nodes[id];
+        let segment_start = pattern.ends_with('/');
+
+        if node.static_indices.len() != node.static_child.len() {
+            violations.push(Violation::StaticIndices(pattern.clone()));
+        }
+        if !node.static_indices.windows(2).all(|pair| pair[0] < pair[1]) {
+            violations.push(Violation::UnsortedStaticChildren(pattern.clone()));
+        }
+        if !node.leaf_handler.is_empty() && node.leaf_param_names.as_ref().map_or(0, Vec::len) != params {
+            violations.push(Violation::ParamNames(pattern.clone()));
+        }
+
+        let mut children = Vec::new();
+        for (i, &child) in node.static_child.iter().enumerate() {
+            let Some(child_node) = self.nodes.get(child) else {
+                violations.push(Violation::InvalidIndex(pattern.clone()));
+                continue;
+            };
+
+            let child_pattern = pattern.clone() + &pattern::escape(&child_node.path, segment_start);
+            match child_node.path.chars().next() {
+                None => violations.push(Violation::EmptyStaticNode(child_pattern.clone())),
+                Some(first) if node.static_indices.get(i) != Some(&first) => violations.push(Violation::StaticIndices(pattern.clone())),
+                Some(_) => {}
+            }
+
+            let only_child = child_node.static_child.first().and_then(|&grandchild| self.nodes.get(grandchild));
+            if child_node.leaf_handler.is_empty()
+                && child_node.static_child.len() == 1
+                && child_node.param_child.is_none()
+                && child_node.star_child.is_none()
+                && &*child_node.path != "/"
+                && only_child.is_some_and(|grandchild| &*grandchild.path != "/") {
+                violations.push(Violation::UnmergedNode(child_pattern.clone()));
+            }
+            children.push((child, child_pattern, params));
+        }
+
+        if let Some(param_child) = node.param_child {
+            match self.nodes.get(param_child) {
+                Some(child_node) => {
+                    let name = &child_node.path;
+                    if name.is_empty() || !name.chars().all(pattern::is_param_char) {
+                        violations.push(Violation::InvalidParamName(pattern.clone()));
+                    }
+                    children.push((param_child, format!("{}${}", pattern, name), params + 1));
+                }
+                None => violations.push(Violation::InvalidIndex(pattern.clone())),
+            }
+        }
+
+        if let Some(star_child) = node.star_child {
+            match self.nodes.get(star_child) {
+                Some(child_node) => {
+                    let path = &child_node.path;
+                    let named = pattern::wildcard(&format!("${}", path)).is_some_and(|(_, len)| len == path.len() + 1);
+                    if !segment_start || (!path.is_empty() && !named) {
+                        violations.push(Violation::InvalidWildcard(pattern.clone()));
+                    }
+                    children.push((star_child, pattern.clone() + &wildcard_pattern(path), params + usize::from(!path.is_empty())));
+                }
+                None => violations.push(Violation::InvalidIndex(pattern.clone())),
+            }
+        }
+
+        for (child, child_pattern, child_params) in children {
+            if core::mem::replace(&mut visited[child], true) {
+                violations.push(Violation::SharedNode(child_pattern));
+                continue;
+            }
+            if self.nodes[child].is_unused() {
+                violations.push(Violation::UnusedNode(child_pattern.clone()));
+            }
+            self.validate_node(child, child_pattern, child_params, visited, violations);
+        }
+    }
+
+    /// Returns the memory used by the tree, not counting the heap memory of
+    /// the values.
+    pub fn memory_usage(&self) -> MemoryStats {
+        let mut stats = MemoryStats {
+            heap_bytes: self.nodes.capacity() * size_of::<NodeData<V, K>>()
+                + self.free.capacity() * size_of::<usize>()
+                + self.counts.heap_size(),
+            ..Default::default()
+        };
+
+        // Removed nodes are empty, so only the live nodes add to the sizes.
+        stats.node_count = self.nodes.len() - self.free.len();
+        for node in self.nodes.iter() {
+            let leaf_map_bytes = node.leaf_handler.heap_size();
+            let param_names_bytes = node.leaf_param_names.as_ref().map_or(0, |names| {
+                names.capacity() * size_of::<String>() + names.iter().map(String::capacity).sum::<usize>()
+            });
+
+            stats.path_bytes += node.path.len();
+            stats.leaf_map_bytes += leaf_map_bytes;
+            stats.heap_bytes += node.path.len()
+                + node.static_indices.capacity() * size_of::<char>()
+                + node.static_child.capacity() * size_of::<usize>()
+                + leaf_map_bytes
+                + param_names_bytes;
+        }
+
+        stats
+    }
+
+    fn with_root(root: NodeData<V, K>) -> Self {
+        Self::from_arena(vec![root], Vec::new())
+    }
+
+    /// Creates a tree from its arena, counting the handlers of its nodes.
+    pub(crate) fn from_arena(nodes: Vec<NodeData<V, K>>, free: Vec<usize>) -> Self {
+        let mut counts = MethodMap::new();
+        for key in nodes.iter().flat_map(|node| node.leaf_handler.keys()) {
+            *counts.get_or_insert(key.clone(), 0) += 1;
+        }
+
+        Self { nodes, free, counts }
+    }
+
+    fn count_added(&mut self, key: &K) {
+        *self.counts.get_or_insert(key.clone(), 0) += 1;
+    }
+
+    /// Moves the node of `other` and its children into this arena, returning
+    /// the new index of the node.
+    ///
+    /// Nodes are added before their children, so a tree grafted into an empty
+    /// arena has its root first.
+    fn graft(&mut self, other: &mut Self, id: usize) -> usize {
+        let mut node = core::mem::take(&mut other.nodes[id]);
+        let new_id = self.alloc(NodeData::default());
+        node.static_child = node.static_child.iter().map(|&child| self.graft(other, child)).collect();
+        node.param_child = node.param_child.map(|child| self.graft(other, child));
+        node.star_child = node.star_child.map(|child| self.graft(other, child));
+        self.nodes[new_id] = node;
+        new_id
+    }
+
+    /// Moves the nodes into a new arena without the slots of removed nodes.
+    fn compact(&mut self) {
+        let mut tree = Self {
+            nodes: Vec::with_capacity(self.nodes.len() - self.free.len()),
+            free: Vec::new(),
+            counts: core::mem::take(&mut self.counts),
+        };
+        tree.graft(self, ROOT);
+        *self = tree;
+    }
+
+    /// Adds the node to the arena, reusing the slot of a removed node if any.
+    fn alloc(&mut self, node: NodeData<V, K>) -> usize {
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = node;
+                id
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Releases the slot of a removed node.
+    fn dealloc(&mut self, id: usize) {
+        self.nodes[id] = NodeData::default();
+        self.free.push(id);
+    }
+}
+
+impl<V: Clone + PartialEq, K: Discriminator> Node<V, K> {
+    /// Returns the routes added, removed or changed in `other` compared with
+    /// this tree, matching routes by method and pattern.
+    ///
+    /// Removed and changed routes come in the order of this tree, followed by
+    /// the added routes in the order of `other`.
+    pub fn diff(&self, other: &Self) -> Vec<RouteChange<V, K>> {
+        // The routes of `other` by pattern. Matched routes are taken out, so
+        // the remaining ones are the added routes.
+        let mut others: BTreeMap<String, Vec<(&K, &V)>> = BTreeMap::new();
+        for (method, pattern, value) in other.iter_prefix("") {
+            others.entry(pattern).or_default().push((method, value));
+        }
+
+        let mut changes = Vec::new();
+        for (method, pattern, value) in self.iter_prefix("") {
+            let found = others.get_mut(&pattern).and_then(|routes| {
+                let i = routes.iter().position(|(m, _)| *m == method)?;
+                Some(routes.remove(i).1)
+            });
+
+            match found {
+                Some(new) if new == value => {}
+                Some(new) => changes.push(RouteChange::Changed {
+                    method: method.clone(),
+                    pattern,
+                    old: value.clone(),
+                    new: new.clone(),
+                }),
+                None => changes.push(RouteChange::Removed {
+                    method: method.clone(),
+                    pattern,
+                    value: value.clone(),
+                }),
+            }
+        }
+
+        for (method, pattern, value) in other.iter_prefix("") {
+            if others.get(&pattern).is_some_and(|routes| routes.iter().any(|(m, _)| *m == method)) {
+                changes.push(RouteChange::Added {
+                    method: method.clone(),
+                    pattern,
+                    value: value.clone(),
+                });
+            }
+        }
+
+        changes
+    }
+
+    /// Returns true if both trees have the same routes and values, however
+    /// their nodes were split while inserting.
+    pub fn routes_eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.diff(other).is_empty()
+    }
+}
+
+impl<V: Clone + PartialEq, K: Discriminator> PartialEq for Node<V, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.routes_eq(other)
+    }
+}
+
+impl<V: Clone + Eq, K: Discriminator> Eq for Node<V, K> {}
+
+impl<V: Clone, K: Discriminator + fmt::Display> Node<V, K> {
+    /// Prints the structure of the tree, as rendered by `Display`.
+    #[cfg(feature = "std")]
+    pub fn print_tree(&self) {
+        std::println!("{}", self);
+    }
+
+    /// Lists the routes one per line, such as `GET /users/$id`, sorted by
+    /// pattern and then by key, so that route tables can be diffed. The
+    /// listing is parsed back by `from_dump`.
+    pub fn dump(&self) -> String {
+        let mut routes = Vec::new();
+        self.for_each_route(&mut |key, pattern, _| routes.push((pattern.to_string(), key.to_string())));
+        routes.sort();
+
+        routes.iter().map(|(pattern, key)| format!("{} {}\n", key, pattern)).collect()
+    }
+
+    /// Writes the children of the node, each on its own line under its parent.
+    fn fmt_children(&self, f: &mut fmt::Formatter<'_>, id: usize, segment_start: bool, indent: &str) -> fmt::Result {
+        let children = self.child_patterns(id, segment_start);
+        for (i, (child, text)) in children.iter().enumerate() {
+            let last = i + 1 == children.len();
+            write!(f, "\n{}{}{}", indent, if last { "└── " } else { "├── " }, text)?;
+            self.fmt_handlers(f, *child)?;
+
+            let indent = format!("{}{}", indent, if last { "    " } else { "│   " });
+            self.fmt_children(f, *child, text.ends_with('/'), &indent)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the keys of the handlers of the node, if any.
+    fn fmt_handlers(&self, f: &mut fmt::Formatter<'_>, id: usize) -> fmt::Result {
+        let mut keys = self.nodes[id].leaf_handler.keys();
+        if let Some(first) = keys.next() {
+            write!(f, " [{}", first)?;
+            for key in keys {
+                write!(f, ", {}", key)?;
+            }
+            write!(f, "]")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders the radix structure of the tree, one node per line under its
+/// parent: static fragments, `$param` and `*` children, followed by the keys
+/// of their handlers.
+///
+/// ```text
+/// /
+/// ├── api
+/// │   └── /
+/// │       └── users [GET, POST]
+/// │           └── /
+/// │               └── $id [GET]
+/// └── * [ANY]
+/// ```
+impl<V: Clone, K: Discriminator + fmt::Display> fmt::Display for Node<V, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let root = &self.nodes[ROOT];
+        write!(f, "{}", root.path)?;
+        self.fmt_handlers(f, ROOT)?;
+        self.fmt_children(f, ROOT, root.path.ends_with('/'), "")
+    }
+}
+
+impl Node<String> {
+    /// Writes a Rust expression which builds this tree with `__from_parts`,
+    /// using the values as the source of the handlers.
+    pub(crate) fn write_source(&self, out: &mut String) {
+        self.write_node_source(ROOT, out, 0);
+    }
+
+    fn write_node_source(&self, id: usize, out: &mut String, depth: usize) {
+        let node = &self.nodes[id];
+        let indent = "    ".repeat(depth);
+        let write_child = |out: &mut String, child: Option<usize>| match child {
+            Some(child) => {
+                out.push_str("Some(");
+                self.write_node_source(child, out, depth + 1);
+                out.push(')');
+            }
+            None => out.push_str("None"),
+        };
+
+        out.push_str(&format!("::radixtree::Node::__from_parts(\n{}    {:?},\n{}    vec![", indent, node.path, indent));
+        for &child in node.static_child.iter() {
+            out.push_str(&format!("\n{}        ", indent));
+            self.write_node_source(child, out, depth + 2);
+            out.push(',');
+        }
+        if !node.static_child.is_empty() {
+            out.push_str(&format!("\n{}    ", indent));
+        }
+        out.push_str(&format!("],\n{}    ", indent));
+        write_child(out, node.param_child);
+        out.push_str(&format!(",\n{}    ", indent));
+        write_child(out, node.star_child);
+        out.push_str(&format!(",\n{}    vec![", indent));
+
+        let handlers: Vec<String> = node.leaf_handler.iter().map(|(method, value)| {
+            let method = if method.index().is_some() {
+                format!("::radixtree::Method::{}", method.as_str())
+            } else {
+                format!("::radixtree::Method::extension({:?}).unwrap()", method.as_str())
+            };
+            format!("({}, {})", method, value)
+        }).collect();
+        out.push_str(&handlers.join(", "));
+
+        let param_names = match node.leaf_param_names {
+            Some(ref names) => format!("Some(&{:?}[..])", names),
+            None => "None".to_string(),
+        };
+        out.push_str(&format!("],\n{}    {},\n{})", indent, param_names, indent));
+    }
+}
+
+impl<V, K: Discriminator> Default for Node<V, K> {
+    fn default() -> Self {
+        Self::with_root(NodeData::default())
+    }
+}
+
+/// Collects routes into a tree, panicking on conflicts like `insert`.
+impl<V: Clone, K: Discriminator, P: AsRef<str>> FromIterator<(K, P, V)> for Node<V, K> {
+    fn from_iter<I: IntoIterator<Item = (K, P, V)>>(routes: I) -> Self {
+        let mut tree = Self::new();
+        tree.extend(routes);
+        tree
+    }
+}
+
+/// Inserts the routes, panicking on conflicts like `insert`.
+impl<V: Clone, K: Discriminator, P: AsRef<str>> Extend<(K, P, V)> for Node<V, K> {
+    fn extend<I: IntoIterator<Item = (K, P, V)>>(&mut self, routes: I) {
+        for (method, path, value) in routes {
+            self.insert(method, path.as_ref(), value);
+        }
+    }
+}
+
+impl<V: Clone, K: Discriminator> NodeData<V, K> {
+    /// Returns the handler of the first of the keys which has one.
+    fn get_handler(&self, keys: &[K]) -> Option<&V> {
+        keys.iter().find_map(|key| self.leaf_handler.get(key))
+    }
+
+    fn remove_handlers(&mut self, methods: Option<&[K]>) -> Vec<(K, V)> {
+        let removed = match methods {
+            Some(methods) => methods.iter()
+                .filter_map(|method| self.leaf_handler.remove(method).map(|value| (method.clone(), value)))
+                .collect(),
+            None => self.leaf_handler.drain(),
+        };
+
+        if self.leaf_handler.is_empty() {
+            self.leaf_param_names = None;
+        }
+
+        removed
+    }
+}
+
+impl<V: Clone, K: Discriminator> NodeData<V, K> {
+    fn update_handlers(&mut self, methods: &[K], value: V) -> Result<Vec<V>, UpdateError<K>> {
+        if self.leaf_handler.is_empty() {
+            return Err(UpdateError::PathNotFound);
+        }
+
+        for method in methods {
+            if !self.leaf_handler.contains_key(method) {
+                return Err(UpdateError::MethodNotFound(method.clone()));
+            }
+        }
+
+        Ok(methods.iter()
+            .map(|method| self.leaf_handler.insert(method.clone(), value.clone()).unwrap())
+            .collect())
+    }
+}
+
+impl<V, K: Discriminator> NodeData<V, K> {
+    /// Returns true if the node has any child node.
+    pub(crate) fn has_children(&self) -> bool {
+        !self.static_child.is_empty() || self.param_child.is_some() || self.star_child.is_some()
+    }
+
+    /// Returns true if the node has neither handlers nor child nodes.
+    fn is_unused(&self) -> bool {
+        self.leaf_handler.is_empty() && !self.has_children()
+    }
+}
+
+impl<V, K: Discriminator> Default for NodeData<V, K> {
+    fn default() -> Self {
+        Self {
+            path: "".into(),
+            static_indices: Vec::new(),
+            static_child: Vec::new(),
+            param_child: None,
+            star_child: None,
+            leaf_handler: MethodMap::new(),
+            leaf_param_names: None,
+        }
+    }
+}
+
+/// A view into a single route of the tree, which may either be vacant or occupied.
+pub enum Entry<'a, V, K = Method> {
+    Occupied(OccupiedEntry<'a, V, K>),
+    Vacant(VacantEntry<'a, V, K>),
+}
+
+impl<'a, V: Clone, K: Discriminator> Entry<'a, V, K> {
+    /// Inserts `default` if the entry is vacant, and returns a mutable reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `default` if the entry is vacant, and returns a
+    /// mutable reference to the value.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Calls `f` with the value if the entry is occupied.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(ref mut entry) = self {
+            f(entry.get_mut());
+        }
+
+        self
+    }
+}
+
+/// An occupied entry of the tree.
+pub struct OccupiedEntry<'a, V, K = Method> {
+    tree: &'a mut Node<V, K>,
+    /// The index of the leaf node
+    leaf: usize,
+    method: K,
+}
+
+impl<'a, V, K: Discriminator> OccupiedEntry<'a, V, K> {
+    pub fn get(&self) -> &V {
+        self.tree.nodes[self.leaf].leaf_handler.get(&self.method).unwrap()
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        self.tree.nodes[self.leaf].leaf_handler.get_mut(&self.method).unwrap()
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        self.tree.nodes[self.leaf].leaf_handler.get_mut(&self.method).unwrap()
+    }
+
+    /// Replaces the value, returning the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        core::mem::replace(self.get_mut(), value)
+    }
+}
+
+/// A vacant entry of the tree.
+pub struct VacantEntry<'a, V, K = Method> {
+    tree: &'a mut Node<V, K>,
+    method: K,
+    /// The path without the start slash
+    path: String,
+}
+
+impl<'a, V: Clone, K: Discriminator> VacantEntry<'a, V, K> {
+    /// Inserts the value, and returns a mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let leaf = self.tree.insert_path(ROOT, self.path, None);
+        self.tree.count_added(&self.method);
+        self.tree.nodes[leaf].leaf_handler.get_or_insert(self.method, value)
+    }
+}
+
+/// The memory used by a tree, as returned by `Node::memory_usage`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    node_count: usize,
+    path_bytes: usize,
+    leaf_map_bytes: usize,
+    heap_bytes: usize,
+}
+
+impl MemoryStats {
+    /// Returns the number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    /// Returns the total length of the path fragments of the nodes.
+    pub fn path_bytes(&self) -> usize {
+        self.path_bytes
+    }
+
+    /// Returns the heap bytes of the maps from methods to values.
+    pub fn leaf_map_bytes(&self) -> usize {
+        self.leaf_map_bytes
+    }
+
+    /// Returns the approximate heap bytes of the tree, including the paths and
+    /// the maps.
+    pub fn heap_bytes(&self) -> usize {
+        self.heap_bytes
+    }
+}
+
+/// What happens when a route is registered again, or with different parameter
+/// names than a registered route.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Panic, which is the default.
+    #[default]
+    Panic,
+    /// Replace the registered value and parameter names.
+    Overwrite,
+    /// Keep the registered route, and drop the new one.
+    Ignore,
+    /// Return the conflict as an error.
+    Error,
+}
+
+/// A difference between two trees, as returned by `Node::diff`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteChange<V, K = Method> {
+    /// A route which only the other tree has.
+    Added { method: K, pattern: String, value: V },
+    /// A route which only this tree has.
+    Removed { method: K, pattern: String, value: V },
+    /// A route whose value is different in the other tree.
+    Changed { method: K, pattern: String, old: V, new: V },
+}
+
+/// The value kept by `Node::merge` for a route registered in both trees.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution<V> {
+    /// Keep the existing value.
+    Existing,
+    /// Replace the existing value with the incoming one.
+    Incoming,
+    /// Replace the existing value with a new value.
+    Merged(V),
+}
+
+/// The tree-level options which change how paths are matched.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SearchOptions {
+    /// Whether a HEAD request falls back to the GET handler.
+    pub(crate) head_can_use_get: bool,
+    /// The Unicode normalization of the patterns and paths of routers.
+    pub(crate) unicode: Option<UnicodeNormalizer>,
+    /// Whether routers merge the duplicate slashes of the paths.
+    pub(crate) merge_slashes: bool,
+    /// How the paths with dot segments are searched by routers.
+    pub(crate) dot_segments: DotSegments,
+}
+
+
+/// The response returned when getting the value for a specific path.
+#[derive(Debug, Clone)]
+pub struct SearchResult<V> {
+    value: V,
+    /// The path parameters
+    params: Params,
+    /// The registered pattern of the matched route
+    pattern: String,
+    /// The extensions of the matched route, which only `Router::search` sets
+    extensions: Option<Arc<Extensions>>,
+    /// Whether the value is a fallback rather than the value of a route
+    fallback: bool,
+}
+
+impl<V> SearchResult<V> {
+    pub(crate) fn new(value: V, params: Vec<Param>, pattern: String) -> Self {
+        Self { value, params: Params(params), pattern, extensions: None, fallback: false }
+    }
+
+    pub(crate) fn into_fallback(self) -> Self {
+        Self { fallback: true, ..self }
+    }
+
+    pub(crate) fn with_extensions(self, extensions: Option<Arc<Extensions>>) -> Self {
+        Self { extensions, ..self }
+    }
+
+    /// Replaces the value, keeping the parameters and the pattern.
+    pub(crate) fn map_value<U>(self, f: impl FnOnce(V) -> U) -> SearchResult<U> {
+        SearchResult {
+            value: f(self.value),
+            params: self.params,
+            pattern: self.pattern,
+            extensions: self.extensions,
+            fallback: self.fallback,
+        }
+    }
+
+    /// Puts the parameters before the parameters of the result, such as the
+    /// parameters of a host.
+    pub(crate) fn prepend_params(mut self, params: Params) -> Self {
+        let mut all = params.0;
+        all.append(&mut self.params.0);
+        self.params = Params(all);
+        self
+    }
+
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+
+    /// Returns the registered pattern of the matched route, such as
+    /// `/users/$id`, which doesn't depend on the values of the parameters.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn params(&self) -> &Params {
+        &self.params
+    }
+
+    /// Returns true if no route matched, and the value is the fallback of
+    /// the router. The pattern is then the prefix of the fallback.
+    pub fn is_fallback(&self) -> bool {
+        self.fallback
+    }
+
+    /// Returns the extensions of the matched route, if the search was made by
+    /// a router and the route has any.
+    pub fn extensions(&self) -> Option<&Extensions> {
+        self.extensions.as_deref()
+    }
+
+    /// Consumes the result, returning the value and the parameters.
+    pub fn into_parts(self) -> (V, Params) {
+        (self.value, self.params)
+    }
+
+    /// Consumes the result, returning the value.
+    pub fn into_value(self) -> V {
+        self.value
+    }
+
+    /// Parses the value of the parameter, such as `result.param::<u64>("id")`.
+    pub fn param<T: FromStr>(&self, name: &str) -> Result<T, ParamError>
+    where
+        T::Err: fmt::Display,
+    {
+        self.params.parse(name)
+    }
+
+    /// Deserializes the parameters into a struct whose fields are named after
+    /// them, or into a tuple of their values. Values are parsed for numbers,
+    /// booleans and characters, and missing `Option` fields are None.
+    #[cfg(feature = "serde")]
+    pub fn parse_params<T: serde_core::de::DeserializeOwned>(&self) -> Result<T, ParamError> {
+        T::deserialize(crate::de::ParamsDeserializer::new(&self.params.0))
+    }
+}
+
+/// Param is a single path parameter, consisting of a name and a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    name: String,
+    value: String,
+}
 
 impl Param {
     pub fn name(&self) -> &String {
@@ -433,36 +2134,256 @@ impl Param {
         &self.value
     }
 
-    fn new(name: String, value: String) -> Self {
+    pub(crate) fn new(name: String, value: String) -> Self {
         Self { name, value }
     }
 }
 
+/// The path parameters of a matched route, in the order they appear in the path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(Vec<Param>);
+
+impl Params {
+    pub fn iter(&self) -> core::slice::Iter<'_, Param> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the value of the first parameter with the name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.iter()
+            .find(|param| param.name == name)
+            .map(|param| param.value.as_str())
+    }
+
+    /// Parses the value of the first parameter with the name.
+    pub fn parse<T: FromStr>(&self, name: &str) -> Result<T, ParamError>
+    where
+        T::Err: fmt::Display,
+    {
+        let value = self.get(name).ok_or_else(|| ParamError::Missing(name.to_string()))?;
+        value.parse().map_err(|error| ParamError::invalid(name, value, error))
+    }
+}
+
+impl From<Vec<Param>> for Params {
+    fn from(params: Vec<Param>) -> Self {
+        Self(params)
+    }
+}
+
+impl core::ops::Index<&str> for Params {
+    type Output = str;
+
+    /// Returns the value of the parameter. It panics if there is no parameter
+    /// with the name.
+    fn index(&self, name: &str) -> &str {
+        match self.get(name) {
+            Some(value) => value,
+            None => panic!("No path parameter named {:?}.", name),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Params {
+    type Item = (&'a str, &'a str);
+    type IntoIter = core::iter::Map<core::slice::Iter<'a, Param>, fn(&'a Param) -> (&'a str, &'a str)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter().map(|param| (param.name.as_str(), param.value.as_str()))
+    }
+}
+
+impl IntoIterator for Params {
+    type Item = (String, String);
+    type IntoIter = core::iter::Map<alloc::vec::IntoIter<Param>, fn(Param) -> (String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter().map(|param| (param.name, param.value))
+    }
+}
+
+/// Returns true if the handler of the key can match, given the pattern of its
+/// leaf and the parameters.
+type Accept<'f, K> = dyn Fn(&str, &K, &Params) -> bool + 'f;
+
+/// Decides which handlers of the leaves can match during a search.
+pub(crate) struct LeafFilter<'f, K> {
+    accept: Option<&'f Accept<'f, K>>,
+    /// The nodes from the root to the searched node, only kept when filtering
+    route: Vec<usize>,
+    /// The values of the parameters of the route, only kept when filtering
+    values: Vec<String>,
+}
+
+impl<'f, K> LeafFilter<'f, K> {
+    /// A filter which accepts every handler, for plain searches.
+    fn off() -> Self {
+        Self { accept: None, route: Vec::new(), values: Vec::new() }
+    }
+
+    fn new(accept: &'f Accept<'f, K>) -> Self {
+        Self { accept: Some(accept), route: Vec::new(), values: Vec::new() }
+    }
+
+    fn enter(&mut self, id: usize) {
+        if self.accept.is_some() {
+            self.route.push(id);
+        }
+    }
+
+    fn leave(&mut self) {
+        self.route.pop();
+    }
+
+    fn push_value(&mut self, value: &str) {
+        if self.accept.is_some() {
+            self.values.push(value.to_string());
+        }
+    }
+
+    fn pop_value(&mut self) {
+        self.values.pop();
+    }
+}
+
 /// The response returned when getting the value for a specific path.
 struct MatchResult<V> {
-    value: Option<V>,
+    value: V,
     /// The names of the path parameters
     param_names: Vec<String>,
-    /// The values of the path parameters
-    param_values: Vec<String>,
+    /// The start and end of the values of the path parameters, counted from
+    /// the end of the path
+    param_offsets: Vec<(usize, usize)>,
+    /// The matched nodes, from the leaf up to the root
+    route: Vec<usize>,
 }
 
-impl<V> MatchResult<V> {
-    fn from_params(&self) -> Vec<Param> {
-        let mut params = Vec::new();
-        for (index, name) in self.param_names.iter().enumerate() {
-            let value = self.param_values.get(index).unwrap();
-            params.push(Param::new(name.clone(), value.clone()));
-        }
+/// The matched leaf and handler of a search, from which its result can be
+/// rebuilt for the same path as long as the tree is unchanged.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub(crate) struct Matched {
+    /// The method of the matched handler
+    key: Method,
+    leaf: usize,
+    /// The pattern of the matched route
+    pattern: String,
+    /// The start and end of the values of the path parameters, counted from
+    /// the end of the path
+    param_offsets: Vec<(usize, usize)>,
+}
 
-        params
+/// Returns the parameters whose values are at the offsets, counted from the
+/// end of the path.
+fn offset_params(names: &[String], offsets: &[(usize, usize)], path: &str) -> Vec<Param> {
+    names.iter()
+        .zip(offsets)
+        .map(|(name, &(start, end))| Param::new(name.clone(), path[path.len() - start..path.len() - end].to_string()))
+        .collect()
+}
+
+/// Returns the rest of the prefix after the text of a child node, or None if
+/// no pattern under the child can start with the prefix. A parameter may be
+/// named differently in each route, so any parameter matches the rest of the
+/// prefix starting with `$`.
+fn prefix_rest<'a>(prefix: &'a str, text: &str) -> Option<&'a str> {
+    if text.starts_with('$') {
+        (prefix.is_empty() || prefix.starts_with('$')).then_some("")
+    } else {
+        prefix.strip_prefix(text).or_else(|| text.starts_with(prefix).then_some(""))
     }
 }
 
-fn strip_start_slash(path: String) -> String {
-    if path.starts_with("/") {
-        path.strip_prefix("/").unwrap().to_string()
+/// Returns the pattern of a leaf with its own parameter names, since a param
+/// node is named after the first route inserted through it.
+fn leaf_pattern(pattern: &str, param_names: Option<&[String]>) -> String {
+    match param_names {
+        Some(names) => {
+            let mut route_pattern = RoutePattern::parse(pattern).unwrap_or_default();
+            route_pattern.rename_params(names);
+            route_pattern.to_string()
+        }
+        None => pattern.to_string(),
+    }
+}
+
+pub(crate) const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+
+/// Hashes the bytes with 64-bit FNV-1a, starting from `hash`.
+pub(crate) fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// Appends the name of a parameter to the names along the path of a node.
+pub(crate) fn push_name(param_names: Option<Vec<String>>, name: &str) -> Vec<String> {
+    let mut param_names = param_names.unwrap_or_default();
+    param_names.push(name.to_string());
+    param_names
+}
+
+/// Returns the pattern of a wildcard node from its path.
+pub(crate) fn wildcard_pattern(path: &str) -> String {
+    if path.is_empty() {
+        "*".to_string()
     } else {
-        path
+        format!("${}", path)
     }
 }
+
+/// Returns the edit distance between the segments of a pattern and of a
+/// path, where a parameter matches any non-empty segment and a wildcard any
+/// number of segments.
+fn segment_distance(pattern: &str, path: &[&str]) -> usize {
+    // The distances between the pattern segments so far and each prefix of the path
+    let mut distances: Vec<usize> = (0..=path.len()).collect();
+
+    for segment in pattern.split('/') {
+        let greedy = segment == "*" || pattern::wildcard(segment).is_some_and(|(_, len)| len == segment.len());
+        let param = !greedy && segment.contains('$') && pattern::static_len(segment, true) < segment.len();
+        let literal = pattern::unescape(segment);
+        let skip_cost = if greedy { 0 } else { 1 };
+
+        let mut previous = distances[0];
+        distances[0] += skip_cost;
+        for (j, path_segment) in path.iter().enumerate() {
+            let replace_cost = if greedy {
+                0
+            } else if param {
+                path_segment.is_empty() as usize
+            } else {
+                (*path_segment != literal) as usize
+            };
+
+            // A wildcard takes the path segment and may take more of them,
+            // while other segments insert the path segment.
+            let distance = (previous + replace_cost)
+                .min(distances[j + 1] + skip_cost)
+                .min(distances[j] + skip_cost);
+            previous = distances[j + 1];
+            distances[j + 1] = distance;
+        }
+    }
+
+    distances[path.len()]
+}
+
+/// Returns the length in bytes of the longest common prefix of the strings,
+/// which always ends at a character boundary of both.
+pub(crate) fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, l), r)| l != r)
+        .map_or(a.len().min(b.len()), |((i, _), _)| i)
+}
+
+pub(crate) fn strip_start_slash(path: &str) -> &str {
+    path.strip_prefix('/').unwrap_or(path)
+}
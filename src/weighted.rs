@@ -0,0 +1,87 @@
+use alloc::vec::Vec;
+use crate::tree::{fnv1a, FNV_OFFSET};
+
+/// Several values of a route with their weights, such as a stable and a
+/// canary version of a handler, which `Router::search_weighted` picks from.
+///
+/// A value is picked with a probability of its weight over the total weight.
+/// Values with a weight of 0 are never picked.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Weighted<V> {
+    choices: Vec<(V, u32)>,
+    total: u64,
+}
+
+impl<V> Weighted<V> {
+    pub fn new() -> Self {
+        Self {
+            choices: Vec::new(),
+            total: 0,
+        }
+    }
+
+    /// Adds a value with its weight.
+    pub fn with(mut self, value: V, weight: u32) -> Self {
+        self.push(value, weight);
+        self
+    }
+
+    pub fn push(&mut self, value: V, weight: u32) {
+        self.choices.push((value, weight));
+        self.total += weight as u64;
+    }
+
+    /// Returns the values and their weights, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = (&V, u32)> {
+        self.choices.iter().map(|(value, weight)| (value, *weight))
+    }
+
+    pub fn len(&self) -> usize {
+        self.choices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.choices.is_empty()
+    }
+
+    /// Returns the sum of the weights.
+    pub fn total_weight(&self) -> u64 {
+        self.total
+    }
+
+    /// Picks the value at `point` modulo the total weight, so a random
+    /// number picks a value by weight. Returns None if the total weight is 0.
+    pub fn pick(&self, point: u64) -> Option<&V> {
+        let mut point = point.checked_rem(self.total)?;
+        for (value, weight) in self.choices.iter() {
+            match point.checked_sub(*weight as u64) {
+                Some(rest) => point = rest,
+                None => return Some(value),
+            }
+        }
+
+        None
+    }
+
+    /// Picks a value by the hash of a key, such as a user id, so the same
+    /// key always gets the same value while the weights don't change.
+    pub fn pick_by_key(&self, key: &[u8]) -> Option<&V> {
+        self.pick(Self::hash_key(key))
+    }
+
+    /// Returns the point picked by `pick_by_key` for the key.
+    pub fn hash_key(key: &[u8]) -> u64 {
+        fnv1a(FNV_OFFSET, key)
+    }
+}
+
+impl<V> FromIterator<(V, u32)> for Weighted<V> {
+    fn from_iter<I: IntoIterator<Item = (V, u32)>>(iter: I) -> Self {
+        let mut weighted = Self::new();
+        for (value, weight) in iter {
+            weighted.push(value, weight);
+        }
+
+        weighted
+    }
+}
@@ -0,0 +1,83 @@
+use radixtree::{ConflictKind, ConflictPolicy, Method, Router};
+
+fn patterns(router: &Router<&'static str>) -> Vec<(Method, String)> {
+    router.iter().map(|(method, pattern, _)| (method.clone(), pattern)).collect()
+}
+
+#[test]
+fn aliases_match_under_each_pattern_and_are_listed_once() {
+    let mut router = Router::new();
+    router.insert_alias(&["/users/$id", "/u/$id", "/people/$id"], Method::GET, "USER").unwrap();
+    router.insert(Method::GET, "/posts", "POSTS").unwrap();
+
+    for path in ["/users/1", "/u/1", "/people/1"] {
+        let result = router.search(Method::GET, path).unwrap();
+        assert_eq!((result.value(), &result.params()["id"]), (&"USER", "1"));
+    }
+    assert_eq!(patterns(&router), [(Method::GET, "/posts".to_string()), (Method::GET, "/users/$id".to_string())]);
+}
+
+#[test]
+fn removing_an_alias_removes_the_whole_route() {
+    for removed in ["/users/$id", "/u/$id"] {
+        let mut router = Router::new();
+        router.insert_alias(&["/users/$id", "/u/$id"], Method::GET, "USER").unwrap();
+        router.insert(Method::GET, "/posts", "POSTS").unwrap();
+
+        assert_eq!(router.remove(removed), Some(vec![(Method::GET, "USER")]));
+        assert!(router.search(Method::GET, "/users/1").is_none());
+        assert!(router.search(Method::GET, "/u/1").is_none());
+        assert_eq!(patterns(&router), [(Method::GET, "/posts".to_string())]);
+    }
+}
+
+#[test]
+fn aliases_are_grouped_by_method() {
+    let mut router = Router::new();
+    router.insert_alias(&["/users/$id", "/u/$id"], Method::GET, "GET").unwrap();
+    router.insert(Method::DELETE, "/u/$id", "DELETE").unwrap();
+    assert_eq!(router.iter().count(), 2);
+
+    router.remove_methods(Method::DELETE, "/u/$id");
+    assert_eq!(router.search(Method::GET, "/u/1").unwrap().value(), &"GET");
+
+    router.remove_methods(Method::GET, "/u/$id");
+    assert!(router.search(Method::GET, "/users/1").is_none());
+    assert!(router.is_empty());
+}
+
+#[test]
+fn aliases_are_checked_together() {
+    let mut router = Router::new();
+    router.set_conflict_policy(ConflictPolicy::Error);
+    router.insert(Method::GET, "/posts", "POSTS").unwrap();
+
+    // The patterns of a group conflict with each other.
+    let result = router.insert_alias(&["/users/$id", "/users/$name"], Method::GET, "USER");
+    assert!(matches!(result, Err(ConflictKind::AmbiguousParams(_))));
+    assert!(router.search(Method::GET, "/users/1").is_none());
+
+    // A pattern conflicts with a registered route, and nothing is inserted.
+    let result = router.insert_alias(&["/p", "/posts"], Method::GET, "OTHER");
+    assert_eq!(result, Err(ConflictKind::Duplicate));
+    assert!(router.search(Method::GET, "/p").is_none());
+    assert_eq!(router.search(Method::GET, "/posts").unwrap().value(), &"POSTS");
+}
+
+#[test]
+fn aliases_follow_the_conflict_policy() {
+    let mut router = Router::new();
+    router.set_conflict_policy(ConflictPolicy::Overwrite);
+    router.insert(Method::GET, "/posts", "POSTS").unwrap();
+
+    router.insert_alias(&["/p", "/posts"], Method::GET, "ALIAS").unwrap();
+    assert_eq!(router.search(Method::GET, "/posts").unwrap().value(), &"ALIAS");
+    assert_eq!(patterns(&router), [(Method::GET, "/p".to_string())]);
+}
+
+#[test]
+fn an_empty_alias_group_inserts_nothing() {
+    let mut router: Router<&str> = Router::new();
+    router.insert_alias(&[], Method::GET, "NOTHING").unwrap();
+    assert!(router.is_empty());
+}
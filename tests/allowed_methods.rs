@@ -0,0 +1,59 @@
+use radixtree::{Method, MethodSet, Node, Router};
+
+#[test]
+fn allowed_methods_of_a_path() {
+    let mut tree = Node::new();
+    tree.insert_methods(Method::GET | Method::PUT, "/users/$id", 1);
+    tree.insert(Method::DELETE, "/users/$id", 2);
+    tree.insert(Method::GET, "/users", 3);
+
+    assert_eq!(tree.allowed_methods("/users/7"), Method::GET | Method::PUT | Method::DELETE);
+    assert_eq!(tree.allowed_methods("/users"), MethodSet::from(Method::GET));
+    assert!(tree.allowed_methods("/posts").is_empty());
+    assert!(tree.allowed_methods("/users/7/posts").is_empty());
+}
+
+#[test]
+fn allowed_methods_gathers_every_matching_route() {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/files/readme", 1);
+    tree.insert(Method::PUT, "/files/$name", 2);
+    tree.insert(Method::DELETE, "/files/*", 3);
+
+    // Each method is searched on its own, so lower priority routes count.
+    assert_eq!(tree.allowed_methods("/files/readme"), Method::GET | Method::PUT | Method::DELETE);
+    assert_eq!(tree.allowed_methods("/files/a/b"), MethodSet::from(Method::DELETE));
+}
+
+#[test]
+fn allowed_methods_of_optional_wildcards() {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/files", 1);
+    tree.insert(Method::POST, "/files/$path*", 2);
+
+    // `$path*` also matches an empty rest.
+    assert_eq!(tree.allowed_methods("/files/"), MethodSet::from(Method::POST));
+    assert_eq!(tree.allowed_methods("/files/a/b"), MethodSet::from(Method::POST));
+    assert_eq!(tree.allowed_methods("/files"), MethodSet::from(Method::GET));
+}
+
+#[test]
+fn allowed_methods_include_any_but_not_extensions() {
+    let mut tree = Node::new();
+    tree.insert(Method::ANY, "/health", 1);
+    tree.insert(Method::extension("PURGE").unwrap(), "/cache", 2);
+
+    assert_eq!(tree.allowed_methods("/health"), MethodSet::from(Method::ANY));
+    assert!(tree.allowed_methods("/cache").is_empty());
+}
+
+#[test]
+fn routers_allow_head_when_it_falls_back_to_get() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users", 1).unwrap();
+    assert_eq!(router.allowed_methods("/users"), MethodSet::from(Method::GET));
+
+    router.set_head_can_use_get(true);
+    assert_eq!(router.allowed_methods("/users"), Method::GET | Method::HEAD);
+    assert!(router.allowed_methods("/posts").is_empty());
+}
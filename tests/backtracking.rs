@@ -0,0 +1,65 @@
+use radixtree::{Method, Node};
+
+fn tree(routes: &[(&str, i32)]) -> Node<i32> {
+    let mut tree = Node::new();
+    for &(pattern, value) in routes {
+        tree.insert(Method::GET, pattern, value);
+    }
+    tree
+}
+
+fn matched(tree: &Node<i32>, path: &str) -> Option<(i32, String)> {
+    tree.search(Method::GET, path).map(|result| (*result.value(), result.pattern().to_string()))
+}
+
+#[test]
+fn static_routes_take_precedence_over_params() {
+    let tree = tree(&[("/users/new", 1), ("/users/$id", 2)]);
+    assert_eq!(matched(&tree, "/users/new"), Some((1, "/users/new".to_string())));
+    assert_eq!(matched(&tree, "/users/42"), Some((2, "/users/$id".to_string())));
+    assert_eq!(matched(&tree, "/users/newer"), Some((2, "/users/$id".to_string())));
+    assert_eq!(matched(&tree, "/users/ne"), Some((2, "/users/$id".to_string())));
+    assert_eq!(matched(&tree, "/users/new/x"), None);
+}
+
+#[test]
+fn params_match_when_a_deeper_static_branch_fails() {
+    let tree = tree(&[("/users/new/edit", 1), ("/users/$id/posts", 2), ("/users/$id", 3)]);
+    assert_eq!(matched(&tree, "/users/new/edit"), Some((1, "/users/new/edit".to_string())));
+    assert_eq!(matched(&tree, "/users/new/posts"), Some((2, "/users/$id/posts".to_string())));
+    assert_eq!(matched(&tree, "/users/new"), Some((3, "/users/$id".to_string())));
+
+    let result = tree.search(Method::GET, "/users/new/posts").unwrap();
+    assert_eq!(&result.params()["id"], "new");
+}
+
+#[test]
+fn wildcards_match_when_the_static_and_param_branches_fail() {
+    let tree = tree(&[("/files/static/app.js", 1), ("/files/$dir/index", 2), ("/files/*", 3)]);
+    assert_eq!(matched(&tree, "/files/static/app.js"), Some((1, "/files/static/app.js".to_string())));
+    assert_eq!(matched(&tree, "/files/static/index"), Some((2, "/files/$dir/index".to_string())));
+    assert_eq!(matched(&tree, "/files/static/app.css"), Some((3, "/files/*".to_string())));
+    assert_eq!(matched(&tree, "/files/static"), Some((3, "/files/*".to_string())));
+}
+
+#[test]
+fn backtracking_skips_routes_of_other_methods() {
+    let mut tree = tree(&[("/users/$id", 2)]);
+    tree.insert(Method::POST, "/users/new", 1);
+    assert_eq!(matched(&tree, "/users/new"), Some((2, "/users/$id".to_string())));
+    assert_eq!(*tree.search(Method::POST, "/users/new").unwrap().value(), 1);
+    assert!(tree.search(Method::POST, "/users/42").is_none());
+}
+
+#[test]
+fn params_with_suffixes_backtrack_to_shorter_values() {
+    let tree = tree(&[("/files/$name.json", 1), ("/files/$name.tar.gz", 2), ("/files/$name", 3)]);
+    let result = tree.search(Method::GET, "/files/a.b.json").unwrap();
+    assert_eq!((*result.value(), &result.params()["name"]), (1, "a.b"));
+
+    let result = tree.search(Method::GET, "/files/a.tar.gz").unwrap();
+    assert_eq!((*result.value(), &result.params()["name"]), (2, "a"));
+
+    let result = tree.search(Method::GET, "/files/a.tar").unwrap();
+    assert_eq!((*result.value(), &result.params()["name"]), (3, "a.tar"));
+}
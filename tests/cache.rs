@@ -0,0 +1,62 @@
+use radixtree::{Method, Router};
+
+fn routes(router: &mut Router<&'static str>) {
+    router.insert(Method::GET, "/users/new", "NEW").unwrap();
+    router.insert(Method::GET, "/users/$id", "USER").unwrap();
+    router.insert(Method::GET, "/files/$dir/$path*", "FILE").unwrap();
+    router.insert(Method::ANY, "/any", "ANY").unwrap();
+}
+
+#[test]
+fn cached_searches_match_uncached_searches() {
+    let (mut plain, mut cached) = (Router::new(), Router::new());
+    routes(&mut plain);
+    routes(&mut cached);
+    cached.set_cache_capacity(2);
+
+    let paths = ["/users/new", "/users/7", "/files/a/b/c", "/files/a/", "/any", "/missing", "users/8"];
+    for _ in 0..3 {
+        for path in paths {
+            for method in [Method::GET, Method::POST] {
+                let expected = plain.search(method.clone(), path).map(|result| (*result.value(), result.pattern().to_string(), result.params().clone()));
+                let found = cached.search(method.clone(), path).map(|result| (*result.value(), result.pattern().to_string(), result.params().clone()));
+                assert_eq!(found, expected, "{} {}", method, path);
+            }
+        }
+    }
+}
+
+#[test]
+fn changes_empty_the_cache() {
+    let mut router = Router::new();
+    router.set_cache_capacity(8);
+    router.insert(Method::GET, "/users/$id", "USER").unwrap();
+    assert_eq!(*router.search(Method::GET, "/users/new").unwrap().value(), "USER");
+
+    router.insert(Method::GET, "/users/new", "NEW").unwrap();
+    assert_eq!(*router.search(Method::GET, "/users/new").unwrap().value(), "NEW");
+    assert_eq!(router.search(Method::GET, "/users/7").unwrap().params().get("id"), Some("7"));
+}
+
+#[test]
+fn concurrent_searches_share_the_cache() {
+    let (mut plain, mut cached) = (Router::new(), Router::new());
+    routes(&mut plain);
+    routes(&mut cached);
+    cached.set_cache_capacity(20);
+
+    let paths: Vec<String> = (0..64).map(|i| format!("/users/{}", i)).chain(["/users/new".to_string(), "/files/a/b".to_string(), "/any".to_string(), "/missing".to_string()]).collect();
+    std::thread::scope(|scope| {
+        for thread in 0..8 {
+            let (plain, cached, paths) = (&plain, &cached, &paths);
+            scope.spawn(move || {
+                for round in 0..200 {
+                    let path = &paths[(thread * 7 + round * 13) % paths.len()];
+                    let expected = plain.search(Method::GET, path).map(|result| (*result.value(), result.pattern().to_string(), result.params().clone()));
+                    let found = cached.search(Method::GET, path).map(|result| (*result.value(), result.pattern().to_string(), result.params().clone()));
+                    assert_eq!(found, expected, "{}", path);
+                }
+            });
+        }
+    });
+}
@@ -0,0 +1,60 @@
+use radixtree::{ConflictKind, ConflictPolicy, Method, Router, Syntax};
+
+fn translate(syntax: Syntax, pattern: &str) -> Option<String> {
+    syntax.translate(pattern).ok().map(|native| native.into_owned())
+}
+
+#[test]
+fn axum_patterns_are_translated() {
+    assert_eq!(translate(Syntax::Axum, "/users/{id}/posts/{*rest}").as_deref(), Some("/users/$id/posts/$rest+"));
+    assert_eq!(translate(Syntax::Axum, "/files/{name}.{ext}").as_deref(), Some("/files/$name.$ext"));
+    assert_eq!(translate(Syntax::Axum, "/{{literal}}/$5/*").as_deref(), Some("/{literal}/\\$5/\\*"));
+    assert_eq!(translate(Syntax::Braces, "/users/{id}"), translate(Syntax::Axum, "/users/{id}"));
+}
+
+#[test]
+fn invalid_axum_patterns_are_errors() {
+    for pattern in ["/users/{id", "/users/id}", "/users/{}", "/users/{*}", "/users/{a-b}", "/users/{id}{name}"] {
+        assert_eq!(translate(Syntax::Axum, pattern), None, "{}", pattern);
+    }
+}
+
+#[test]
+fn actix_patterns_are_translated() {
+    assert_eq!(translate(Syntax::Actix, "/users/{id}").as_deref(), Some("/users/$id"));
+    assert_eq!(translate(Syntax::Actix, "/users/{id:[^/]+}").as_deref(), Some("/users/$id"));
+    assert_eq!(translate(Syntax::Actix, "/files/{tail:.+}").as_deref(), Some("/files/$tail+"));
+    assert_eq!(translate(Syntax::Actix, "/files/{tail:.*}").as_deref(), Some("/files/$tail*"));
+}
+
+#[test]
+fn unsupported_actix_regexes_are_errors() {
+    for pattern in ["/users/{id:\\d+}", "/years/{year:\\d{4}}", "/users/{:.+}", "/users/{id", "/users/{id:[a-z]+"] {
+        assert_eq!(translate(Syntax::Actix, pattern), None, "{}", pattern);
+    }
+}
+
+#[test]
+fn insert_compat_registers_the_translation() {
+    let mut router = Router::new();
+    router.insert_compat(Syntax::Axum, Method::GET, "/users/{id}/posts/{*rest}", "AXUM").unwrap();
+    router.insert_compat(Syntax::Actix, Method::GET, "/files/{tail:.*}", "ACTIX").unwrap();
+
+    let result = router.search(Method::GET, "/users/7/posts/a/b").unwrap();
+    assert_eq!((*result.value(), result.params().get("id"), result.params().get("rest")), ("AXUM", Some("7"), Some("a/b")));
+    assert_eq!(result.pattern(), "/users/$id/posts/$rest+");
+    assert_eq!(router.search(Method::GET, "/files/").unwrap().params().get("tail"), Some(""));
+}
+
+#[test]
+fn insert_compat_reports_translation_errors() {
+    let mut router = Router::new();
+    router.set_conflict_policy(ConflictPolicy::Error);
+    assert!(matches!(router.insert_compat(Syntax::Actix, Method::GET, "/users/{id:\\d+}", 1), Err(ConflictKind::InvalidPattern(_))));
+    assert!(matches!(router.insert_compat(Syntax::Axum, Method::GET, "/users/{id", 1), Err(ConflictKind::InvalidPattern(_))));
+
+    router.insert_compat(Syntax::Axum, Method::GET, "/users/{id}", 1).unwrap();
+    assert_eq!(router.insert_compat(Syntax::Actix, Method::GET, "/users/{id}", 2), Err(ConflictKind::Duplicate));
+    assert_eq!(router.insert_compat(Syntax::Actix, Method::GET, "/users/{name}", 2), Err(ConflictKind::AmbiguousParams(vec!["id".to_string()])));
+    assert_eq!(router.len(), 1);
+}
@@ -0,0 +1,66 @@
+use radixtree::{Method, Node, Params};
+
+const ROUTES: &[(&str, &str, i32)] = &[
+    ("GET", "/", 1),
+    ("GET", "/users", 2),
+    ("GET", "/users/new", 3),
+    ("GET", "/users/$id", 4),
+    ("POST", "/users/$id", 5),
+    ("GET", "/users/$id/posts/$post", 6),
+    ("GET", "/files/$name.json", 7),
+    ("GET", "/files/$path+", 8),
+    ("GET", "/docs/*", 9),
+    ("GET", "/*/edit", 10),
+    ("ANY", "/health", 11),
+    ("GET", "/assets/$file*", 12),
+];
+
+const SEGMENTS: &[&str] = &["", "users", "new", "42", "posts", "files", "a.json", "docs", "edit", "health", "assets", "x.y"];
+
+fn tree() -> Node<i32> {
+    let mut tree = Node::new();
+    for &(method, pattern, value) in ROUTES {
+        tree.insert(method.parse().unwrap(), pattern, value);
+    }
+    tree
+}
+
+/// Returns every path of up to three segments.
+fn paths() -> Vec<String> {
+    let mut paths = vec![String::new()];
+    let mut last = vec![String::new()];
+    for _ in 0..3 {
+        last = last.iter().flat_map(|path| SEGMENTS.iter().map(move |segment| format!("{}/{}", path, segment))).collect();
+        paths.extend(last.iter().cloned());
+    }
+    paths
+}
+
+#[test]
+fn compiled_routers_match_like_the_tree() {
+    let tree = tree();
+    let compiled = tree.compile();
+
+    for path in paths() {
+        for method in [Method::GET, Method::POST, Method::DELETE] {
+            let expected: Option<(i32, String, Params)> = tree.search(method.clone(), &path)
+                .map(|result| (*result.value(), result.pattern().to_string(), result.params().clone()));
+            let result = compiled.search(method.clone(), &path);
+            let actual = result.map(|result| (*result.value(), result.pattern().to_string(), result.params().clone()));
+            assert_eq!(actual, expected, "{} {}", method, path);
+        }
+    }
+}
+
+#[test]
+fn compiled_routers_reflect_removals() {
+    let mut tree = tree();
+    tree.remove("/users/new");
+    tree.remove_method(Method::POST, "/users/$id");
+    let compiled = tree.compile();
+
+    let result = compiled.search(Method::GET, "/users/new").unwrap();
+    assert_eq!((*result.value(), result.pattern()), (4, "/users/$id"));
+    assert!(compiled.search(Method::POST, "/users/1").is_none());
+    assert_eq!(*compiled.search(Method::PUT, "/health").unwrap().value(), 11);
+}
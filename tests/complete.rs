@@ -0,0 +1,46 @@
+use radixtree::{Method, Node, Router};
+
+fn tree() -> Node<u32> {
+    let mut tree = Node::new();
+    for (i, pattern) in ["/users", "/users/$id", "/user", "/static/*", "/api/v1/items", "/apis", "/files/$path*"].iter().enumerate() {
+        tree.insert(Method::GET, pattern, i as u32);
+    }
+    tree.insert(Method::POST, "/users", 10);
+    tree
+}
+
+#[test]
+fn complete_lists_the_patterns_extending_the_prefix() {
+    let tree = tree();
+    assert_eq!(tree.complete("/us"), ["/user", "/users", "/users/$id"]);
+    assert_eq!(tree.complete("/users/"), ["/users/$id"]);
+    assert_eq!(tree.complete("/users/$"), ["/users/$id"]);
+    assert_eq!(tree.complete("/orders"), Vec::<String>::new());
+}
+
+#[test]
+fn complete_compares_the_text_of_the_patterns() {
+    let tree = tree();
+    assert_eq!(tree.complete("/api"), ["/api/v1/items", "/apis"]);
+    assert_eq!(tree.complete("/api/"), ["/api/v1/items"]);
+    assert_eq!(tree.complete("/files/$p"), ["/files/$path*"]);
+    assert_eq!(tree.complete("/static/*"), ["/static/*"]);
+}
+
+#[test]
+fn complete_lists_each_pattern_once() {
+    let tree = tree();
+    // `/users` has two methods.
+    assert_eq!(tree.complete("").len(), 7);
+    assert_eq!(tree.complete("/"), tree.complete(""));
+    assert_eq!(tree.complete("us"), tree.complete("/us"));
+}
+
+#[test]
+fn routers_complete_patterns() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users/$id", 1).unwrap();
+    router.insert(Method::GET, "/users/$id/posts", 2).unwrap();
+    router.insert(Method::GET, "/orders", 3).unwrap();
+    assert_eq!(router.complete("/users"), ["/users/$id", "/users/$id/posts"]);
+}
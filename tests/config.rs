@@ -0,0 +1,56 @@
+#![cfg(feature = "config")]
+
+use radixtree::{ConfigError, ConfigFormat, ConflictKind, Method, Node};
+
+fn resolve(key: &str) -> Option<&'static str> {
+    match key {
+        "list_users" => Some("LIST"),
+        "show_user" => Some("SHOW"),
+        "files" => Some("FILES"),
+        _ => None,
+    }
+}
+
+#[test]
+fn from_config_loads_the_routes() {
+    let config = r#"{"routes": [
+        {"method": "GET", "pattern": "/users", "key": "list_users"},
+        {"method": "GET", "pattern": "/users/$id", "key": "show_user"},
+        {"method": "ANY", "pattern": "/files/$path*", "key": "files"}
+    ]}"#;
+    let tree = Node::from_config(config.as_bytes(), ConfigFormat::Json, resolve).unwrap();
+
+    assert_eq!(*tree.search(Method::GET, "/users").unwrap().value(), "LIST");
+    let result = tree.search(Method::GET, "/users/7").unwrap();
+    assert_eq!((*result.value(), result.params().get("id")), ("SHOW", Some("7")));
+    let result = tree.search(Method::DELETE, "/files/a/b").unwrap();
+    assert_eq!((*result.value(), result.params().get("path")), ("FILES", Some("a/b")));
+    assert!(tree.search(Method::POST, "/users").is_none());
+}
+
+#[test]
+fn from_config_methods_are_case_sensitive() {
+    let config = r#"{"routes": [{"method": "get", "pattern": "/users", "key": "list_users"}]}"#;
+    let tree = Node::from_config(config.as_bytes(), ConfigFormat::Json, resolve).unwrap();
+
+    assert!(tree.search(Method::GET, "/users").is_none());
+    assert_eq!(*tree.search("get".parse().unwrap(), "/users").unwrap().value(), "LIST");
+}
+
+#[test]
+fn from_config_reports_invalid_tables() {
+    let load = |config: &str| Node::from_config(config.as_bytes(), ConfigFormat::Json, resolve).unwrap_err();
+
+    assert!(matches!(load("routes = []"), ConfigError::Parse(_)));
+    assert_eq!(load(r#"{"paths": []}"#), ConfigError::MissingRoutes);
+    assert_eq!(load(r#"{"routes": [{"method": "GET", "key": "list_users"}]}"#), ConfigError::InvalidRoute(0, "pattern".to_string()));
+    assert_eq!(load(r#"{"routes": [{"method": "GE T", "pattern": "/users", "key": "list_users"}]}"#), ConfigError::InvalidRoute(0, "method".to_string()));
+    assert_eq!(load(r#"{"routes": [{"method": "GET", "pattern": "/users", "key": "missing"}]}"#), ConfigError::UnknownKey("missing".to_string()));
+    assert_eq!(
+        load(r#"{"routes": [
+            {"method": "GET", "pattern": "/users", "key": "list_users"},
+            {"method": "GET", "pattern": "/users", "key": "show_user"}
+        ]}"#),
+        ConfigError::Conflict(1, ConflictKind::Duplicate)
+    );
+}
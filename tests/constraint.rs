@@ -0,0 +1,42 @@
+use radixtree::{Method, Router};
+
+fn matched(router: &Router<&'static str>, path: &str) -> Option<(&'static str, String)> {
+    let result = router.search(Method::GET, path)?;
+    Some((*result.value(), result.pattern().to_string()))
+}
+
+#[test]
+fn rejected_constraints_fall_through_to_other_routes() {
+    let mut router = Router::new();
+    router.register_constraint("num", |value: &str| value.bytes().all(|b| b.is_ascii_digit()));
+    router.register_constraint("lower", |value: &str| value.bytes().all(|b| b.is_ascii_lowercase()));
+    router.insert(Method::GET, "/users/$id:num", "ID").unwrap();
+    router.insert(Method::GET, "/users/$name:lower/profile", "PROFILE").unwrap();
+    router.insert(Method::GET, "/users/*", "REST").unwrap();
+
+    assert_eq!(matched(&router, "/users/42"), Some(("ID", "/users/$id".to_string())));
+    assert_eq!(matched(&router, "/users/alice/profile"), Some(("PROFILE", "/users/$name/profile".to_string())));
+    assert_eq!(matched(&router, "/users/Alice/profile"), Some(("REST", "/users/*".to_string())));
+    assert_eq!(matched(&router, "/users/4a"), Some(("REST", "/users/*".to_string())));
+}
+
+#[test]
+fn constraints_are_checked_for_each_method() {
+    let mut router = Router::new();
+    router.register_constraint("num", |value: &str| value.bytes().all(|b| b.is_ascii_digit()));
+    router.insert(Method::GET, "/items/$id:num", "GET").unwrap();
+    router.insert(Method::POST, "/items/$id", "POST").unwrap();
+
+    assert!(router.search(Method::GET, "/items/x").is_none());
+    assert_eq!(*router.search(Method::POST, "/items/x").unwrap().value(), "POST");
+}
+
+#[test]
+fn unregistered_constraints_are_static_text() {
+    let mut router = Router::new();
+    router.register_constraint("num", |value: &str| value.bytes().all(|b| b.is_ascii_digit()));
+    router.insert(Method::GET, "/users/$id:uuid", "USER").unwrap();
+
+    assert_eq!(matched(&router, "/users/1:uuid"), Some(("USER", "/users/$id:uuid".to_string())));
+    assert_eq!(matched(&router, "/users/1"), None);
+}
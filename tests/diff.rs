@@ -0,0 +1,74 @@
+use radixtree::{Method, Node, RouteChange, Router};
+
+fn tree(routes: &[(Method, &str, u32)]) -> Node<u32> {
+    let mut tree = Node::new();
+    for (method, pattern, value) in routes {
+        tree.insert(method.clone(), pattern, *value);
+    }
+    tree
+}
+
+#[test]
+fn diff_reports_added_removed_and_changed_routes() {
+    let old = tree(&[(Method::GET, "/users", 1), (Method::POST, "/users", 2), (Method::GET, "/users/$id", 3), (Method::GET, "/legacy", 4)]);
+    let new = tree(&[(Method::GET, "/users", 1), (Method::POST, "/users", 20), (Method::GET, "/users/$id", 3), (Method::DELETE, "/users/$id", 5)]);
+
+    assert_eq!(old.diff(&new), [
+        RouteChange::Removed { method: Method::GET, pattern: "/legacy".to_string(), value: 4 },
+        RouteChange::Changed { method: Method::POST, pattern: "/users".to_string(), old: 2, new: 20 },
+        RouteChange::Added { method: Method::DELETE, pattern: "/users/$id".to_string(), value: 5 },
+    ]);
+}
+
+#[test]
+fn diff_is_empty_for_the_same_routes() {
+    let a = tree(&[(Method::GET, "/a", 1), (Method::GET, "/b", 2)]);
+    let b = tree(&[(Method::GET, "/b", 2), (Method::GET, "/a", 1)]);
+    assert!(a.diff(&b).is_empty());
+    assert!(a.diff(&a).is_empty());
+    assert!(Node::<u32>::new().diff(&Node::new()).is_empty());
+}
+
+#[test]
+fn diff_matches_routes_by_their_pattern_text() {
+    let old = tree(&[(Method::GET, "/users/$id", 1)]);
+    let new = tree(&[(Method::GET, "/users/$name", 1)]);
+    assert_eq!(old.diff(&new), [
+        RouteChange::Removed { method: Method::GET, pattern: "/users/$id".to_string(), value: 1 },
+        RouteChange::Added { method: Method::GET, pattern: "/users/$name".to_string(), value: 1 },
+    ]);
+}
+
+#[test]
+fn diffs_are_symmetric() {
+    let old = tree(&[(Method::GET, "/a", 1), (Method::GET, "/b", 2)]);
+    let new = tree(&[(Method::GET, "/b", 3), (Method::GET, "/c", 4)]);
+
+    let forward = old.diff(&new);
+    let backward = new.diff(&old);
+    assert_eq!(forward.len(), backward.len());
+    for change in forward {
+        let reversed = match change {
+            RouteChange::Added { method, pattern, value } => RouteChange::Removed { method, pattern, value },
+            RouteChange::Removed { method, pattern, value } => RouteChange::Added { method, pattern, value },
+            RouteChange::Changed { method, pattern, old, new } => RouteChange::Changed { method, pattern, old: new, new: old },
+        };
+        assert!(backward.contains(&reversed), "{:?}", reversed);
+    }
+}
+
+#[test]
+fn routers_diff_their_routes() {
+    let mut old = Router::new();
+    old.insert(Method::GET, "/users", 1).unwrap();
+    old.insert(Method::GET, "/legacy", 2).unwrap();
+    let mut new = Router::new();
+    new.insert(Method::GET, "/users", 10).unwrap();
+    new.insert(Method::POST, "/users", 3).unwrap();
+
+    assert_eq!(old.diff(&new), [
+        RouteChange::Removed { method: Method::GET, pattern: "/legacy".to_string(), value: 2 },
+        RouteChange::Changed { method: Method::GET, pattern: "/users".to_string(), old: 1, new: 10 },
+        RouteChange::Added { method: Method::POST, pattern: "/users".to_string(), value: 3 },
+    ]);
+}
@@ -0,0 +1,57 @@
+use radixtree::{Discriminator, Node, UpdateError};
+
+/// A key with more indexed keys than fit in the bitset of a leaf.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Key(u8);
+
+const KEYS: [Key; 40] = {
+    let mut keys = [Key(0); 40];
+    let mut i = 0;
+    while i < keys.len() {
+        keys[i] = Key(i as u8);
+        i += 1;
+    }
+    keys
+};
+
+impl Discriminator for Key {
+    fn indexed() -> &'static [Self] {
+        &KEYS
+    }
+}
+
+#[test]
+fn keys_past_the_bitset_go_to_the_overflow_list() {
+    let mut tree = Node::new();
+    tree.insert(Key(35), "/shared", 35);
+    tree.insert(Key(3), "/shared", 3);
+    for key in KEYS {
+        tree.insert(key, "/all", key.0);
+    }
+
+    assert_eq!(*tree.search(Key(35), "/shared").unwrap().value(), 35);
+    assert_eq!(*tree.search(Key(3), "/shared").unwrap().value(), 3);
+    assert!(tree.search(Key(39), "/shared").is_none());
+
+    let keys: Vec<u8> = tree.iter().filter(|(_, pattern, _)| pattern == "/all").map(|(key, _, _)| key.0).collect();
+    assert_eq!(keys, (0..40).collect::<Vec<_>>());
+
+    assert_eq!(tree.remove_method(Key(35), "/shared"), Some(35));
+    assert_eq!(*tree.search(Key(3), "/shared").unwrap().value(), 3);
+    assert_eq!(tree.len(), 41);
+}
+
+#[test]
+fn keyed_trees_update_and_remove_several_keys() {
+    let mut tree = Node::new();
+    tree.insert_methods([Key(1), Key(2), Key(35)], "/shared", 0);
+
+    assert_eq!(tree.update(Key(35), "/shared", 35), Ok(0));
+    assert_eq!(tree.update(Key(3), "/shared", 3), Err(UpdateError::MethodNotFound(Key(3))));
+    assert_eq!(tree.update_methods([Key(1), Key(2)], "/shared", 12), Ok(vec![0, 0]));
+    assert_eq!(tree.update(Key(1), "/missing", 1), Err(UpdateError::PathNotFound));
+
+    assert_eq!(tree.remove_methods([Key(2), Key(35)], "/shared"), Some(vec![(Key(2), 12), (Key(35), 35)]));
+    assert_eq!(*tree.search(Key(1), "/shared").unwrap().value(), 12);
+    assert_eq!(tree.len(), 1);
+}
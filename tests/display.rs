@@ -0,0 +1,50 @@
+use radixtree::{Method, Node};
+
+#[test]
+fn display_renders_the_radix_structure() {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/api/users", 1);
+    tree.insert(Method::POST, "/api/users", 2);
+    tree.insert(Method::GET, "/api/users/$id", 3);
+    tree.insert(Method::ANY, "/*", 4);
+
+    let expected = "\
+/
+├── api
+│   └── /
+│       └── users [GET, POST]
+│           └── /
+│               └── $id [GET]
+└── * [ANY]";
+    assert_eq!(tree.to_string(), expected);
+}
+
+#[test]
+fn display_shows_the_splits_of_static_fragments() {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/user", 1);
+    tree.insert(Method::GET, "/users", 2);
+    tree.insert(Method::GET, "/files/$path*", 3);
+
+    let expected = "\
+/
+├── files
+│   └── /
+│       └── $path* [GET]
+└── user [GET]
+    └── s [GET]";
+    assert_eq!(tree.to_string(), expected);
+
+    tree.remove("/user");
+    assert!(tree.to_string().ends_with("└── users [GET]"));
+}
+
+#[test]
+fn display_of_an_empty_tree_and_a_root_route() {
+    let mut tree = Node::new();
+    assert_eq!(tree.to_string(), "/");
+
+    tree.insert(Method::GET, "/", 1);
+    tree.insert(Method::DELETE, "/", 2);
+    assert_eq!(tree.to_string(), "/ [GET, DELETE]");
+}
@@ -0,0 +1,66 @@
+use radixtree::{ConflictKind, DumpError, Method, Node};
+
+fn tree() -> Node<usize> {
+    let mut tree = Node::new();
+    tree.insert(Method::POST, "/users", 1);
+    tree.insert(Method::GET, "/users/$id", 2);
+    tree.insert(Method::GET, "/users", 3);
+    tree.insert(Method::ANY, "/files/$path*", 4);
+    tree.insert("PURGE".parse().unwrap(), "/cache/*", 5);
+    tree.insert(Method::GET, "/price/\\$5", 6);
+    tree
+}
+
+#[test]
+fn dump_is_sorted_by_pattern_and_method() {
+    assert_eq!(tree().dump(), "\
+PURGE /cache/*
+ANY /files/$path*
+GET /price/\\$5
+GET /users
+POST /users
+GET /users/$id
+");
+}
+
+#[test]
+fn dump_does_not_depend_on_the_insertion_order() {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/price/\\$5", 6);
+    tree.insert(Method::GET, "/users", 3);
+    tree.insert("PURGE".parse().unwrap(), "/cache/*", 5);
+    tree.insert(Method::GET, "/users/$id", 2);
+    tree.insert(Method::ANY, "/files/$path*", 4);
+    tree.insert(Method::POST, "/users", 1);
+    assert_eq!(tree.dump(), self::tree().dump());
+}
+
+#[test]
+fn dumps_round_trip() {
+    let dump = tree().dump();
+    let parsed = Node::from_dump(&dump, |method, pattern| format!("{} {}", method, pattern)).unwrap();
+    assert_eq!(parsed.dump(), dump);
+
+    assert_eq!(parsed.search(Method::GET, "/users/7").unwrap().value(), "GET /users/$id");
+    assert_eq!(parsed.search(Method::DELETE, "/files/a/b").unwrap().value(), "ANY /files/$path*");
+    assert_eq!(parsed.search(Method::GET, "/price/$5").unwrap().value(), "GET /price/\\$5");
+    assert_eq!(parsed.search("PURGE".parse().unwrap(), "/cache/x").unwrap().value(), "PURGE /cache/*");
+}
+
+#[test]
+fn from_dump_skips_comments_and_blank_lines() {
+    let text = "# users\r\nGET /users\r\n\r\n# posts\nGET /posts/$id\n";
+    let tree = Node::from_dump(text, |_, pattern| pattern.to_string()).unwrap();
+    assert_eq!(tree.dump(), "GET /posts/$id\nGET /users\n");
+}
+
+#[test]
+fn from_dump_reports_the_line_of_an_error() {
+    let parse = |text: &str| Node::from_dump(text, |_, _| ()).unwrap_err();
+
+    assert_eq!(parse("GET /users\n/posts\n"), DumpError::InvalidLine(2));
+    assert_eq!(parse("GE(T /users\n"), DumpError::InvalidLine(1));
+    assert_eq!(parse("GET /users\n\nGET /users\n"), DumpError::Conflict(3, ConflictKind::Duplicate));
+    assert_eq!(parse("GET /users/$id\nGET /users/$name\n"), DumpError::Conflict(2, ConflictKind::AmbiguousParams(vec!["id".to_string()])));
+    assert!(matches!(parse("GET /users/$\n"), DumpError::Conflict(1, ConflictKind::InvalidPattern(_))));
+}
@@ -0,0 +1,64 @@
+use radixtree::{ConflictKind, Entry, Method, Node, Router};
+
+#[test]
+fn insert_replace_returns_the_displaced_value() {
+    let mut tree = Node::new();
+    assert_eq!(tree.insert_replace(Method::GET, "/users/$id", 1), None);
+    assert_eq!(tree.insert_replace(Method::GET, "/users/$id", 2), Some(1));
+    assert_eq!(tree.insert_replace(Method::POST, "/users/$id", 3), None);
+
+    assert_eq!(tree.search(Method::GET, "/users/7").unwrap().value(), &2);
+    assert_eq!(tree.len(), 2);
+}
+
+#[test]
+fn entries_insert_or_modify_values() {
+    let mut tree = Node::new();
+    *tree.entry(Method::GET, "/hits").unwrap().or_insert(0) += 1;
+    *tree.entry(Method::GET, "/hits").unwrap().or_insert(0) += 1;
+    tree.entry(Method::GET, "/hits").unwrap().and_modify(|hits| *hits *= 10).or_insert_with(|| unreachable!());
+    tree.entry(Method::POST, "/hits").unwrap().and_modify(|hits| *hits *= 10).or_insert_with(|| 7);
+
+    assert_eq!(tree.search(Method::GET, "/hits").unwrap().value(), &20);
+    assert_eq!(tree.search(Method::POST, "/hits").unwrap().value(), &7);
+}
+
+#[test]
+fn entries_tell_whether_the_route_is_registered() {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/users/$id", 1);
+
+    match tree.entry(Method::GET, "/users/$id").unwrap() {
+        Entry::Occupied(mut entry) => {
+            assert_eq!(entry.get(), &1);
+            assert_eq!(entry.insert(2), 1);
+        }
+        Entry::Vacant(_) => panic!("the route is registered"),
+    }
+    assert!(matches!(tree.entry(Method::DELETE, "/users/$id"), Ok(Entry::Vacant(_))));
+    assert_eq!(tree.search(Method::GET, "/users/1").unwrap().value(), &2);
+}
+
+#[test]
+fn entries_return_invalid_patterns_and_conflicts() {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/users/$id", 1);
+    tree.insert(Method::GET, "/files/*", 2);
+
+    assert!(matches!(tree.entry(Method::GET, "/users/$"), Err(ConflictKind::InvalidPattern(_))));
+    assert_eq!(tree.entry(Method::POST, "/users/$name").err(), Some(ConflictKind::AmbiguousParams(vec!["id".to_string()])));
+    assert_eq!(tree.entry(Method::POST, "/files/$path+").err(), Some(ConflictKind::Shadowing("*".to_string())));
+    assert_eq!(tree.len(), 2);
+}
+
+#[test]
+fn routers_return_entries_in_their_syntax() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users", 1).unwrap();
+    *router.entry(Method::GET, "/users").unwrap().or_insert(0) += 10;
+    router.entry(Method::POST, "users").unwrap().or_insert(2);
+
+    assert_eq!(router.search(Method::GET, "/users").unwrap().value(), &11);
+    assert_eq!(router.search(Method::POST, "/users").unwrap().value(), &2);
+    assert!(matches!(router.entry(Method::GET, "/a/$"), Err(ConflictKind::InvalidPattern(_))));
+}
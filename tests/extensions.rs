@@ -0,0 +1,91 @@
+use radixtree::{Extensions, Method, Router};
+
+#[derive(Clone, Debug, PartialEq)]
+struct Scope(&'static str);
+
+#[derive(Clone, Debug, PartialEq)]
+struct Timeout(u32);
+
+#[test]
+fn extensions_hold_one_value_of_each_type() {
+    let mut extensions = Extensions::new();
+    assert!(extensions.is_empty());
+    assert_eq!(extensions.insert(Scope("read")), None);
+    assert_eq!(extensions.insert(Timeout(30)), None);
+    assert_eq!(extensions.insert(Scope("admin")), Some(Scope("read")));
+    assert_eq!(extensions.len(), 2);
+    assert_eq!(extensions.get::<Scope>(), Some(&Scope("admin")));
+
+    extensions.get_mut::<Timeout>().unwrap().0 = 60;
+    assert_eq!(extensions.remove::<Timeout>(), Some(Timeout(60)));
+    assert!(!extensions.contains::<Timeout>());
+    assert_eq!(extensions.get::<u32>(), None);
+
+    let mut other = Extensions::new();
+    other.insert(Scope("other"));
+    other.insert(Timeout(5));
+    extensions.extend(other);
+    assert_eq!((extensions.get::<Scope>(), extensions.get::<Timeout>()), (Some(&Scope("other")), Some(&Timeout(5))));
+
+    extensions.clear();
+    assert!(extensions.is_empty());
+}
+
+#[test]
+fn searches_return_the_extensions_of_the_matched_route() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users/$id", "GET").unwrap();
+    router.insert(Method::DELETE, "/users/$id", "DELETE").unwrap();
+    router.extensions_mut(Method::DELETE, "/users/$id").unwrap().insert(Scope("admin"));
+
+    let result = router.search(Method::DELETE, "/users/1").unwrap();
+    assert_eq!(result.extensions().unwrap().get::<Scope>(), Some(&Scope("admin")));
+    assert!(router.search(Method::GET, "/users/1").unwrap().extensions().is_none());
+
+    // The parameter names don't need to match to find the route.
+    assert!(router.extensions(Method::DELETE, "/users/$name").unwrap().contains::<Scope>());
+    assert!(router.extensions(Method::GET, "/users/$id").is_none());
+}
+
+#[test]
+fn extensions_need_a_registered_route() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users", "USERS").unwrap();
+    assert!(router.extensions_mut(Method::POST, "/users").is_none());
+    assert!(router.extensions_mut(Method::GET, "/orders").is_none());
+    assert!(router.extensions(Method::GET, "/orders").is_none());
+}
+
+#[test]
+fn extensions_are_dropped_with_their_route() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users", "USERS").unwrap();
+    router.extensions_mut(Method::GET, "/users").unwrap().insert(Timeout(30));
+
+    router.remove("/users");
+    router.insert(Method::GET, "/users", "USERS").unwrap();
+    assert!(router.extensions(Method::GET, "/users").is_none());
+    assert!(router.search(Method::GET, "/users").unwrap().extensions().is_none());
+}
+
+#[test]
+fn searches_falling_back_to_any_return_its_extensions() {
+    let mut router = Router::new();
+    router.insert(Method::ANY, "/health", "HEALTH").unwrap();
+    router.extensions_mut(Method::ANY, "/health").unwrap().insert(Timeout(1));
+
+    let result = router.search(Method::POST, "/health").unwrap();
+    assert_eq!(result.extensions().unwrap().get::<Timeout>(), Some(&Timeout(1)));
+}
+
+#[test]
+fn cloned_routers_keep_their_own_extensions() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users", "USERS").unwrap();
+    router.extensions_mut(Method::GET, "/users").unwrap().insert(Timeout(30));
+
+    let mut clone = router.clone();
+    clone.extensions_mut(Method::GET, "/users").unwrap().insert(Timeout(60));
+    assert_eq!(router.extensions(Method::GET, "/users").unwrap().get::<Timeout>(), Some(&Timeout(30)));
+    assert_eq!(clone.extensions(Method::GET, "/users").unwrap().get::<Timeout>(), Some(&Timeout(60)));
+}
@@ -0,0 +1,128 @@
+use std::sync::{Arc, Mutex};
+
+use radixtree::{Method, Metrics, Router};
+
+#[derive(Default)]
+struct Counts {
+    matches: Mutex<Vec<String>>,
+    misses: Mutex<Vec<String>>,
+}
+
+impl Metrics for Counts {
+    fn on_match(&self, _: &Method, pattern: &str) {
+        self.matches.lock().unwrap().push(pattern.to_string());
+    }
+
+    fn on_miss(&self, _: &Method, path: &str) {
+        self.misses.lock().unwrap().push(path.to_string());
+    }
+}
+
+#[test]
+fn the_fallback_of_the_longest_prefix_wins() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/api/users", "USERS").unwrap();
+    router.insert_fallback("/", "NOT FOUND");
+    router.insert_fallback("/api/", "API");
+    router.insert_fallback("/api/admin", "ADMIN");
+
+    let result = router.search(Method::GET, "/api/users").unwrap();
+    assert!(!result.is_fallback());
+    assert_eq!(result.value(), &"USERS");
+
+    let result = router.search(Method::GET, "/api/posts/1").unwrap();
+    assert!(result.is_fallback());
+    assert_eq!((result.value(), result.pattern()), (&"API", "/api"));
+    assert_eq!(router.search(Method::POST, "/api/users").unwrap().value(), &"API");
+    assert_eq!(router.search(Method::GET, "/api/admin/x").unwrap().value(), &"ADMIN");
+    assert_eq!(router.search(Method::GET, "/about").unwrap().value(), &"NOT FOUND");
+}
+
+#[test]
+fn fallbacks_apply_at_segment_boundaries() {
+    let mut router = Router::new();
+    router.insert_fallback("/api", "API");
+
+    assert_eq!(router.search(Method::GET, "/api").unwrap().value(), &"API");
+    assert_eq!(router.search(Method::GET, "/api/").unwrap().value(), &"API");
+    assert!(router.search(Method::GET, "/apis").is_none());
+}
+
+#[test]
+fn fallbacks_of_prefixes_with_parameters() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users/$id/profile", "PROFILE").unwrap();
+    router.insert_fallback("/users/$id", "USER NOT FOUND");
+
+    let result = router.search(Method::GET, "/users/7/posts").unwrap();
+    assert!(result.is_fallback());
+    assert_eq!(result.value(), &"USER NOT FOUND");
+    assert_eq!(&result.params()["id"], "7");
+}
+
+#[test]
+fn fallbacks_are_replaced_and_removed() {
+    let mut router = Router::new();
+    assert_eq!(router.insert_fallback("/api", "OLD"), None);
+    assert_eq!(router.insert_fallback("/api/", "NEW"), Some("OLD"));
+    assert_eq!(router.search(Method::GET, "/api/x").unwrap().value(), &"NEW");
+
+    assert_eq!(router.remove_fallback("/api"), Some("NEW"));
+    assert_eq!(router.remove_fallback("/api"), None);
+    assert!(router.search(Method::GET, "/api/x").is_none());
+}
+
+#[test]
+fn metrics_count_fallbacks_as_misses() {
+    let counts = Arc::new(Counts::default());
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users", "USERS").unwrap();
+    router.insert_fallback("/", "NOT FOUND");
+    router.set_metrics(counts.clone());
+
+    router.search(Method::GET, "/users");
+    router.search(Method::GET, "/posts");
+
+    assert_eq!(*counts.matches.lock().unwrap(), ["/users"]);
+    assert_eq!(*counts.misses.lock().unwrap(), ["/posts"]);
+}
+
+#[test]
+fn method_fallbacks_are_returned_instead_of_none() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users", "USERS").unwrap();
+    assert_eq!(router.set_fallback(Method::POST, "POST NOT FOUND"), None);
+    router.set_fallback(Method::ANY, "NOT FOUND");
+
+    let result = router.search(Method::POST, "/posts").unwrap();
+    assert!(result.is_fallback());
+    assert_eq!(result.value(), &"POST NOT FOUND");
+    assert_eq!(router.search(Method::POST, "/users").unwrap().value(), &"POST NOT FOUND");
+    assert_eq!(router.search(Method::DELETE, "/users").unwrap().value(), &"NOT FOUND");
+    assert!(!router.search(Method::GET, "/users").unwrap().is_fallback());
+}
+
+#[test]
+fn prefix_fallbacks_take_precedence_over_method_fallbacks() {
+    let mut router = Router::new();
+    router.set_fallback(Method::GET, "GET NOT FOUND");
+    router.insert_fallback("/api", "API");
+
+    assert_eq!(router.search(Method::GET, "/api/x").unwrap().value(), &"API");
+    assert_eq!(router.search(Method::GET, "/x").unwrap().value(), &"GET NOT FOUND");
+    assert!(router.search(Method::POST, "/x").is_none());
+}
+
+#[test]
+fn method_fallbacks_are_replaced_and_unset() {
+    let mut router = Router::new();
+    router.set_fallback(Method::GET, "OLD");
+    assert_eq!(router.set_fallback(Method::GET, "NEW"), Some("OLD"));
+    assert_eq!(router.unset_fallback(Method::GET), Some("NEW"));
+    assert_eq!(router.unset_fallback(Method::GET), None);
+    assert!(router.search(Method::GET, "/x").is_none());
+
+    // The fallback of ANY is the fallback of the `/` prefix.
+    router.set_fallback(Method::ANY, "ANY");
+    assert_eq!(router.remove_fallback("/"), Some("ANY"));
+}
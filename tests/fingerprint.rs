@@ -0,0 +1,48 @@
+use radixtree::{Method, Node, Router};
+
+fn tree(routes: &[(Method, &str, u32)]) -> Node<u32> {
+    let mut tree = Node::new();
+    for (method, pattern, value) in routes {
+        tree.insert(method.clone(), pattern, *value);
+    }
+    tree
+}
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+
+#[test]
+fn fingerprint_is_stable() {
+    let route = fnv1a(fnv1a(FNV_OFFSET, b"GET"), b"/users/$id");
+    let expected = fnv1a(FNV_OFFSET, &route.to_le_bytes());
+    assert_eq!(tree(&[(Method::GET, "/users/$id", 1)]).fingerprint(), expected);
+    assert_eq!(Node::<u32>::new().fingerprint(), fnv1a(FNV_OFFSET, &0u64.to_le_bytes()));
+}
+
+#[test]
+fn fingerprint_ignores_the_order_and_the_values() {
+    let a = tree(&[(Method::GET, "/a", 1), (Method::GET, "/ab", 2), (Method::POST, "/a", 3)]);
+    let b = tree(&[(Method::POST, "/a", 30), (Method::GET, "/ab", 20), (Method::GET, "/a", 10)]);
+    assert_eq!(a.fingerprint(), b.fingerprint());
+}
+
+#[test]
+fn fingerprint_depends_on_the_methods_and_patterns() {
+    let a = tree(&[(Method::GET, "/a", 1)]);
+    assert_ne!(a.fingerprint(), tree(&[(Method::POST, "/a", 1)]).fingerprint());
+    assert_ne!(a.fingerprint(), tree(&[(Method::GET, "/b", 1)]).fingerprint());
+    assert_ne!(a.fingerprint(), tree(&[(Method::GET, "/a", 1), (Method::GET, "/b", 1)]).fingerprint());
+    assert_ne!(a.fingerprint(), Node::<u32>::new().fingerprint());
+}
+
+#[test]
+fn routers_fingerprint_their_routes() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/a", 1).unwrap();
+    router.insert(Method::POST, "/a", 3).unwrap();
+    let expected = tree(&[(Method::POST, "/a", 3), (Method::GET, "/a", 1)]).fingerprint();
+    assert_eq!(router.fingerprint(), expected);
+}
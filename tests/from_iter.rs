@@ -0,0 +1,52 @@
+use radixtree::{ConflictKind, Method, Node};
+
+#[test]
+fn routes_are_collected_into_a_tree() {
+    let tree: Node<u32> = [(Method::GET, "/users", 1), (Method::GET, "/users/$id", 2), (Method::POST, "/users", 3)]
+        .into_iter()
+        .collect();
+
+    assert_eq!(tree.len(), 3);
+    assert_eq!(tree.search(Method::GET, "/users/7").unwrap().value(), &2);
+    assert_eq!(tree.search(Method::POST, "/users").unwrap().value(), &3);
+}
+
+#[test]
+fn trees_are_extended_with_owned_patterns() {
+    let mut tree: Node<u32> = Node::new();
+    tree.insert(Method::GET, "/", 0);
+    tree.extend((1..=3).map(|i| (Method::GET, format!("/v{}", i), i)));
+
+    assert_eq!(tree.len(), 4);
+    assert_eq!(tree.search(Method::GET, "/v2").unwrap().value(), &2);
+}
+
+#[test]
+#[should_panic]
+fn collecting_conflicting_routes_panics() {
+    let _: Node<u32> = [(Method::GET, "/users/$id", 1), (Method::GET, "/users/$name", 2)].into_iter().collect();
+}
+
+#[test]
+fn try_from_iter_returns_the_first_conflict() {
+    let routes = [(Method::GET, "/a", 1), (Method::GET, "/a", 2)];
+    assert_eq!(Node::try_from_iter(routes), Err(ConflictKind::Duplicate));
+
+    let routes = [(Method::GET, "/a", 1), (Method::GET, "/b/$", 2)];
+    assert!(matches!(Node::try_from_iter(routes), Err(ConflictKind::InvalidPattern(_))));
+
+    let tree = Node::try_from_iter([(Method::GET, "/a", 1), (Method::POST, "/a", 2)]).unwrap();
+    assert_eq!(tree.len(), 2);
+}
+
+#[test]
+fn try_extend_keeps_the_routes_before_the_conflict() {
+    let mut tree: Node<u32> = Node::new();
+    tree.insert(Method::GET, "/b", 0);
+
+    let result = tree.try_extend([(Method::GET, "/a", 1), (Method::GET, "/b", 2), (Method::GET, "/c", 3)]);
+    assert_eq!(result, Err(ConflictKind::Duplicate));
+    assert_eq!(tree.search(Method::GET, "/a").unwrap().value(), &1);
+    assert_eq!(tree.search(Method::GET, "/b").unwrap().value(), &0);
+    assert!(tree.search(Method::GET, "/c").is_none());
+}
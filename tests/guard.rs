@@ -0,0 +1,61 @@
+use radixtree::{Extensions, MatchContext, Method, Router};
+
+#[derive(Clone)]
+struct Version(u32);
+
+fn matched(router: &Router<&'static str>, path: &str, request: &Extensions) -> Option<(&'static str, String)> {
+    let result = router.search_with_context(Method::GET, path, request)?;
+    Some((*result.value(), result.pattern().to_string()))
+}
+
+fn versioned(version: u32) -> Extensions {
+    let mut request = Extensions::new();
+    request.insert(Version(version));
+    request
+}
+
+#[test]
+fn rejected_guards_fall_through_to_params_wildcards_and_fallbacks() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users/new", "NEW").unwrap();
+    router.insert(Method::GET, "/users/$id", "USER").unwrap();
+    router.insert(Method::GET, "/users/*", "REST").unwrap();
+    router.insert_fallback("/users", "FALLBACK");
+    router.set_guard(Method::GET, "/users/new", |context: &MatchContext| context.request().get::<Version>().is_some_and(|v| v.0 >= 2));
+    router.set_guard(Method::GET, "/users/$id", |context: &MatchContext| context.params().get("id") != Some("root"));
+    router.set_guard(Method::GET, "/users/*", |context: &MatchContext| context.request().contains::<Version>());
+
+    let none = Extensions::new();
+    assert_eq!(matched(&router, "/users/new", &versioned(2)), Some(("NEW", "/users/new".to_string())));
+    assert_eq!(matched(&router, "/users/new", &versioned(1)), Some(("USER", "/users/$id".to_string())));
+    assert_eq!(matched(&router, "/users/root", &versioned(1)), Some(("REST", "/users/*".to_string())));
+    assert_eq!(matched(&router, "/users/root", &none), Some(("FALLBACK", "/users".to_string())));
+    assert!(router.search_with_context(Method::GET, "/users/root", &none).unwrap().is_fallback());
+}
+
+#[test]
+fn guards_see_the_matched_route() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users/$id/posts/$post", "POST").unwrap();
+    router.set_guard(Method::GET, "/users/$id/posts/$post", |context: &MatchContext| {
+        context.method() == &Method::GET
+            && context.path() == "/users/1/posts/2"
+            && context.pattern() == "/users/$id/posts/$post"
+            && context.params().len() == 2
+    });
+
+    assert!(router.search(Method::GET, "/users/1/posts/2").is_some());
+    assert!(router.search(Method::GET, "/users/1/posts/3").is_none());
+}
+
+#[test]
+fn removed_guards_stop_rejecting() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/admin", "ADMIN").unwrap();
+    assert!(router.set_guard(Method::GET, "/admin", |_: &MatchContext| false));
+    assert!(!router.set_guard(Method::GET, "/missing", |_: &MatchContext| false));
+    assert!(router.search(Method::GET, "/admin").is_none());
+
+    assert!(router.remove_guard(Method::GET, "/admin"));
+    assert!(router.search(Method::GET, "/admin").is_some());
+}
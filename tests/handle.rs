@@ -0,0 +1,81 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use radixtree::{Method, Router, RouterHandle};
+
+fn router(value: usize) -> Router<usize> {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/version", value).unwrap();
+    router
+}
+
+fn version(handle: &RouterHandle<usize>) -> usize {
+    *handle.load().search(Method::GET, "/version").unwrap().value()
+}
+
+#[test]
+fn stores_replace_the_loaded_router() {
+    let handle = RouterHandle::new(router(1));
+    let snapshot = handle.load();
+    handle.store(router(2));
+
+    assert_eq!(version(&handle), 2);
+    assert_eq!(*snapshot.search(Method::GET, "/version").unwrap().value(), 1);
+
+    let previous = handle.swap(router(3));
+    assert_eq!(*previous.search(Method::GET, "/version").unwrap().value(), 2);
+    assert_eq!(version(&handle), 3);
+}
+
+#[test]
+fn updates_change_a_copy() {
+    let handle = RouterHandle::from(router(1));
+    let snapshot = handle.load();
+    let result = handle.update(|router| router.insert(Method::GET, "/new", 2));
+
+    assert_eq!(result, Ok(()));
+    assert!(handle.load().search(Method::GET, "/new").is_some());
+    assert!(snapshot.search(Method::GET, "/new").is_none());
+}
+
+#[test]
+fn replaced_routers_are_released() {
+    let handle = RouterHandle::new(router(1));
+    let previous = handle.swap(router(2));
+    assert_eq!(Arc::strong_count(&previous), 1);
+
+    let snapshot = handle.load();
+    assert_eq!(Arc::strong_count(&snapshot), 2);
+    drop(handle);
+    assert_eq!(Arc::strong_count(&snapshot), 1);
+}
+
+#[test]
+fn loads_see_whole_routers_while_writers_store() {
+    let handle = Arc::new(RouterHandle::new(router(0)));
+    let done = Arc::new(AtomicBool::new(false));
+
+    let readers: Vec<_> = (0..4).map(|_| {
+        let (handle, done) = (handle.clone(), done.clone());
+        thread::spawn(move || {
+            let mut last = 0;
+            while !done.load(Ordering::Relaxed) {
+                let router = handle.load();
+                let version = *router.search(Method::GET, "/version").unwrap().value();
+                assert!(version >= last);
+                assert_eq!(router.len(), 1);
+                last = version;
+            }
+        })
+    }).collect();
+
+    for value in 1..=200 {
+        handle.store(router(value));
+    }
+    done.store(true, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert_eq!(version(&handle), 200);
+}
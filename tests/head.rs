@@ -0,0 +1,47 @@
+use radixtree::{Method, Router};
+
+#[test]
+fn head_searches_dont_use_get_by_default() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users", "GET").unwrap();
+    assert!(!router.head_can_use_get());
+    assert!(router.search(Method::HEAD, "/users").is_none());
+}
+
+#[test]
+fn head_searches_fall_back_to_get() {
+    let mut router = Router::new();
+    router.set_head_can_use_get(true);
+    router.insert(Method::GET, "/users/$id", "GET").unwrap();
+
+    let result = router.search(Method::HEAD, "/users/7").unwrap();
+    assert_eq!((result.value(), &result.params()["id"]), (&"GET", "7"));
+    assert!(router.search(Method::OPTIONS, "/users/7").is_none());
+}
+
+#[test]
+fn explicit_head_routes_take_precedence() {
+    let mut router = Router::new();
+    router.set_head_can_use_get(true);
+    router.insert(Method::GET, "/users", "GET").unwrap();
+    router.insert(Method::HEAD, "/users", "HEAD").unwrap();
+    router.insert(Method::GET, "/posts", "GET").unwrap();
+    router.insert(Method::ANY, "/posts", "ANY").unwrap();
+    router.insert(Method::ANY, "/health", "ANY").unwrap();
+
+    assert_eq!(router.search(Method::HEAD, "/users").unwrap().value(), &"HEAD");
+    // GET comes before ANY.
+    assert_eq!(router.search(Method::HEAD, "/posts").unwrap().value(), &"GET");
+    assert_eq!(router.search(Method::HEAD, "/health").unwrap().value(), &"ANY");
+}
+
+#[test]
+fn the_fallback_can_be_disabled_again() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users", "GET").unwrap();
+    router.set_head_can_use_get(true);
+    assert!(router.search(Method::HEAD, "/users").is_some());
+
+    router.set_head_can_use_get(false);
+    assert!(router.search(Method::HEAD, "/users").is_none());
+}
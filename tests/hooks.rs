@@ -0,0 +1,58 @@
+use std::sync::{Arc, Mutex};
+
+use radixtree::{Method, RouteEvent, Router};
+
+type Events = Arc<Mutex<Vec<(RouteEvent, Method, String)>>>;
+
+fn router() -> (Router<&'static str>, Events) {
+    let events = Events::default();
+    let mut router = Router::new();
+    let sink = events.clone();
+    router.on_change(move |event, method, pattern| sink.lock().unwrap().push((event, method.clone(), pattern.to_string())));
+    (router, events)
+}
+
+#[test]
+fn changes_report_inserts_updates_and_removes() {
+    let (mut router, events) = router();
+    router.insert(Method::GET, "/users/$id", "USER").unwrap();
+    router.insert(Method::POST, "/users/$id", "CREATE").unwrap();
+    router.update(Method::GET, "/users/$id", "UPDATED").unwrap();
+    router.remove_method(Method::POST, "/users/$id");
+    router.remove("/users/$id");
+
+    let pattern = || "/users/$id".to_string();
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            (RouteEvent::Insert, Method::GET, pattern()),
+            (RouteEvent::Insert, Method::POST, pattern()),
+            (RouteEvent::Update, Method::GET, pattern()),
+            (RouteEvent::Remove, Method::POST, pattern()),
+            (RouteEvent::Remove, Method::GET, pattern()),
+        ]
+    );
+}
+
+#[test]
+fn failed_changes_are_not_reported() {
+    let (mut router, events) = router();
+    router.insert(Method::GET, "/a", "A").unwrap();
+    events.lock().unwrap().clear();
+
+    assert!(router.update(Method::POST, "/a", "B").is_err());
+    assert!(router.remove("/b").is_none());
+    assert!(router.remove_method(Method::POST, "/a").is_none());
+    assert!(events.lock().unwrap().is_empty());
+}
+
+#[test]
+fn replacing_a_value_is_an_update() {
+    let (mut router, events) = router();
+    router.insert_replace(Method::GET, "/a", "A");
+    router.insert_replace(Method::GET, "/a", "B");
+    assert_eq!(
+        *events.lock().unwrap(),
+        [(RouteEvent::Insert, Method::GET, "/a".to_string()), (RouteEvent::Update, Method::GET, "/a".to_string())]
+    );
+}
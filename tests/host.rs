@@ -0,0 +1,82 @@
+use radixtree::{ConflictKind, HostRouter, Method};
+
+#[test]
+fn insert_accepts_wildcard_hosts() {
+    let mut router = HostRouter::new();
+    router.insert(Method::GET, "*.example.com/", "ANY").unwrap();
+
+    let result = router.search("a.b.example.com", Method::GET, "/").unwrap();
+    assert_eq!(*result.value(), "ANY");
+}
+
+#[test]
+fn insert_returns_invalid_patterns() {
+    let mut router = HostRouter::new();
+    let invalid = ["$.example.com/", "$tenant.example.com/$tenant", "example.com/$"];
+    for pattern in invalid {
+        let error = router.insert(Method::GET, pattern, ()).unwrap_err();
+        assert!(matches!(error, ConflictKind::InvalidPattern(_)), "{}", pattern);
+    }
+    assert_eq!(router.hosts().count(), 0);
+}
+
+#[test]
+fn insert_returns_conflicting_hosts() {
+    let mut router = HostRouter::new();
+    router.insert(Method::GET, "$tenant.example.com/", ()).unwrap();
+
+    let error = router.insert(Method::GET, "$name.example.com/", ()).unwrap_err();
+    assert!(matches!(error, ConflictKind::AmbiguousParams(_)));
+    assert_eq!(router.hosts().collect::<Vec<_>>(), ["$tenant.example.com"]);
+}
+
+#[test]
+fn search_falls_through_to_less_specific_hosts() {
+    let mut router = HostRouter::new();
+    router.insert(Method::GET, "*.example.com/", "WILDCARD").unwrap();
+    router.insert(Method::GET, "$tenant.example.com/dash", "DASH").unwrap();
+    router.insert(Method::GET, "/health", "HEALTH").unwrap();
+
+    let result = router.search("a.example.com", Method::GET, "/dash").unwrap();
+    assert_eq!(*result.value(), "DASH");
+    assert_eq!(result.params().get("tenant"), Some("a"));
+
+    let result = router.search("a.example.com", Method::GET, "/").unwrap();
+    assert_eq!(*result.value(), "WILDCARD");
+    assert_eq!(result.params().get("tenant"), None);
+
+    assert_eq!(*router.search("a.example.com", Method::GET, "/health").unwrap().value(), "HEALTH");
+    assert!(router.search("a.example.com", Method::GET, "/missing").is_none());
+}
+
+#[test]
+fn search_ignores_ports_and_case() {
+    let mut router = HostRouter::new();
+    router.insert(Method::GET, "API.Example.com/", "API").unwrap();
+
+    for host in ["api.example.com", "API.EXAMPLE.COM:8080", "api.example.com.", "Api.Example.Com.:443"] {
+        assert_eq!(*router.search(host, Method::GET, "/").unwrap().value(), "API", "{}", host);
+    }
+    assert!(router.search("api.example.org", Method::GET, "/").is_none());
+}
+
+#[test]
+fn search_keeps_the_brackets_of_ipv6_hosts() {
+    let mut router = HostRouter::new();
+    router.insert(Method::GET, "[::1]/", "LOOPBACK").unwrap();
+    router.insert(Method::GET, "/", "DEFAULT").unwrap();
+
+    assert_eq!(*router.search("[::1]:8080", Method::GET, "/").unwrap().value(), "LOOPBACK");
+    assert_eq!(*router.search("[::1]", Method::GET, "/").unwrap().value(), "LOOPBACK");
+    assert_eq!(*router.search("[::2]", Method::GET, "/").unwrap().value(), "DEFAULT");
+}
+
+#[test]
+fn search_returns_host_params_before_path_params() {
+    let mut router = HostRouter::new();
+    router.insert(Method::GET, "$tenant.$region.example.com/projects/$id", ()).unwrap();
+
+    let result = router.search("acme.eu.example.com", Method::GET, "/projects/7").unwrap();
+    let names: Vec<_> = result.params().iter().map(|param| param.name().as_str()).collect();
+    assert_eq!(names, ["tenant", "region", "id"]);
+}
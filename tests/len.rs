@@ -0,0 +1,99 @@
+use radixtree::{ConflictPolicy, Method, Node, Resolution, Router};
+
+/// Checks the counters against the routes listed by `iter`.
+fn assert_counts(tree: &Node<u32>) {
+    assert_eq!(tree.len(), tree.iter().count());
+    assert_eq!(tree.is_empty(), tree.iter().next().is_none());
+    for method in [Method::GET, Method::POST, Method::ANY, "PURGE".parse().unwrap()] {
+        let count = tree.iter().filter(|(key, ..)| **key == method).count();
+        assert_eq!(tree.count_method(method.clone()), count, "{}", method);
+    }
+}
+
+#[test]
+fn len_counts_each_method_of_a_path() {
+    let mut tree = Node::new();
+    assert!(tree.is_empty());
+    assert_counts(&tree);
+
+    tree.insert(Method::GET, "/users", 1);
+    tree.insert(Method::POST, "/users", 2);
+    tree.insert_methods([Method::GET, Method::POST], "/users/$id", 3);
+    tree.insert(Method::ANY, "/files/*", 4);
+    tree.insert("PURGE".parse().unwrap(), "/cache", 5);
+    assert_eq!(tree.len(), 6);
+    assert_eq!(tree.count_method(Method::GET), 2);
+    assert_eq!(tree.count_method(Method::PUT), 0);
+    assert_counts(&tree);
+}
+
+#[test]
+fn counts_follow_the_changes() {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/users", 1);
+    assert_eq!(tree.insert_replace(Method::GET, "/users", 2), Some(1));
+    assert_eq!(tree.insert_replace(Method::POST, "/users", 3), None);
+    assert_counts(&tree);
+
+    tree.entry(Method::GET, "/users/$id").unwrap().or_insert(4);
+    tree.entry(Method::GET, "/users/$id").unwrap().or_insert(5);
+    assert_eq!(tree.len(), 3);
+    assert_counts(&tree);
+
+    assert_eq!(tree.remove_method(Method::GET, "/users"), Some(2));
+    assert_eq!(tree.remove_method(Method::GET, "/users"), None);
+    assert_counts(&tree);
+
+    tree.retain(|method, _, _| *method != Method::POST);
+    assert_eq!(tree.len(), 1);
+    assert_counts(&tree);
+
+    let other = Node::from_iter([(Method::GET, "/users/$id", 6), (Method::ANY, "/files/*", 7)]);
+    tree.merge(other, |_, incoming| Resolution::Merged(*incoming)).unwrap();
+    assert_eq!(tree.len(), 2);
+    assert_counts(&tree);
+
+    let split = tree.split_off("/files");
+    assert_counts(&tree);
+    assert_counts(&split);
+    assert_eq!((tree.len(), split.len()), (1, 1));
+
+    tree.clear();
+    assert!(tree.is_empty());
+    assert_counts(&tree);
+}
+
+#[test]
+fn counts_survive_snapshots_and_removals_of_whole_paths() {
+    let mut tree = Node::new();
+    for i in 0..50 {
+        tree.insert(Method::GET, &format!("/items/{}", i), i);
+        if i % 3 == 0 {
+            tree.insert(Method::POST, &format!("/items/{}", i), i);
+        }
+    }
+    assert_counts(&tree);
+
+    for i in (0..50).step_by(2) {
+        tree.remove(&format!("/items/{}", i));
+    }
+    assert_counts(&tree);
+    // The odd items 1 and 11 to 19, and the POST of 15.
+    assert_eq!(tree.remove_prefix("/items/1").len(), 7);
+    assert_counts(&tree);
+
+    let bytes = tree.to_bytes(|value, out| out.extend(value.to_le_bytes()));
+    let restored = Node::from_bytes(&bytes, |bytes| Some(u32::from_le_bytes(bytes.try_into().ok()?))).unwrap();
+    assert_counts(&restored);
+}
+
+#[test]
+fn router_counts_use_the_tree() {
+    let mut router = Router::new();
+    router.set_conflict_policy(ConflictPolicy::Overwrite);
+    router.insert(Method::GET, "/users", 1).unwrap();
+    router.insert(Method::GET, "/users", 2).unwrap();
+    router.insert(Method::POST, "/users", 3).unwrap();
+    assert_eq!((router.len(), router.count_method(Method::GET)), (2, 1));
+    assert!(!router.is_empty());
+}
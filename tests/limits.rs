@@ -0,0 +1,63 @@
+use radixtree::{ConflictKind, ConflictPolicy, LimitError, Limits, Method, Node, Router};
+
+fn router(limits: Limits) -> Router<&'static str> {
+    let mut router = Router::new();
+    router.set_conflict_policy(ConflictPolicy::Error);
+    router.set_limits(limits);
+    router
+}
+
+#[test]
+fn patterns_exceeding_the_length_are_rejected() {
+    let mut router = router(Limits::new().max_path_len(10));
+    router.insert(Method::GET, "/users/$id", "USER").unwrap();
+    assert_eq!(router.insert(Method::GET, "/users/$id/x", "X"), Err(ConflictKind::LimitExceeded(LimitError::PathLength(10))));
+    assert_eq!(router.len(), 1);
+}
+
+#[test]
+fn patterns_exceeding_the_segments_are_rejected() {
+    let mut router = router(Limits::new().max_segments(2));
+    router.insert(Method::GET, "/users/$id", "USER").unwrap();
+    assert_eq!(router.insert(Method::GET, "/users/$id/posts", "POSTS"), Err(ConflictKind::LimitExceeded(LimitError::Segments(2))));
+    assert!(router.search(Method::GET, "/users/7/posts").is_none());
+}
+
+#[test]
+fn patterns_deepening_the_tree_are_rejected() {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/users/$id", ());
+    let mut router = router(Limits::new().max_depth(tree.depth()));
+    router.insert(Method::GET, "/users/$id", "USER").unwrap();
+    router.insert(Method::GET, "/users/$id/a", "A").unwrap_err();
+
+    // The rejected route is removed, and the others are kept.
+    assert!(router.search(Method::GET, "/users/7/a").is_none());
+    assert_eq!(*router.search(Method::GET, "/users/7").unwrap().value(), "USER");
+    assert_eq!(router.len(), 1);
+    assert!(matches!(router.insert(Method::GET, "/users/$id/a/b/c", "ABC"), Err(ConflictKind::LimitExceeded(LimitError::Depth(_)))));
+
+    // Another method of a registered pattern doesn't deepen the tree.
+    router.insert(Method::POST, "/users/$id", "UPDATE").unwrap();
+}
+
+#[test]
+fn paths_exceeding_a_limit_match_nothing() {
+    let mut router = router(Limits::new().max_path_len(16).max_segments(3));
+    router.insert(Method::GET, "/files/*", "FILES").unwrap();
+
+    assert!(router.search(Method::GET, "/files/a/b").is_some());
+    assert!(router.search(Method::GET, "/files/a/b/c").is_none());
+    assert!(router.search(Method::GET, "/files/abcdefghijk").is_none());
+    assert_eq!(router.try_search(Method::GET, "/files/a/b/c").unwrap_err(), LimitError::Segments(3));
+    assert_eq!(router.try_search(Method::GET, "/files/abcdefghijk").unwrap_err(), LimitError::PathLength(16));
+    assert_eq!(router.try_search(Method::GET, "/files/a").unwrap().map(|result| *result.value()), Some("FILES"));
+}
+
+#[test]
+fn limits_are_unbounded_by_default() {
+    let mut router = router(Limits::default());
+    let pattern = "/a".repeat(200);
+    router.insert(Method::GET, &pattern, "DEEP").unwrap();
+    assert_eq!(*router.search(Method::GET, &pattern).unwrap().value(), "DEEP");
+}
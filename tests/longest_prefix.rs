@@ -0,0 +1,56 @@
+use radixtree::{Method, Node, Router};
+
+fn tree() -> Node<u32> {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/api", 1);
+    tree.insert(Method::GET, "/api/users/$id", 2);
+    tree.insert(Method::GET, "/static/*", 3);
+    tree.insert(Method::POST, "/api/users", 4);
+    tree
+}
+
+#[test]
+fn search_longest_prefix_returns_the_deepest_route() {
+    let tree = tree();
+    assert_eq!(tree.search_longest_prefix(Method::GET, "/api/users/1/posts"), Some(("/api/users/$id".to_string(), &2, "/posts")));
+    assert_eq!(tree.search_longest_prefix(Method::GET, "/api/users"), Some(("/api".to_string(), &1, "/users")));
+    assert_eq!(tree.search_longest_prefix(Method::GET, "/api"), Some(("/api".to_string(), &1, "")));
+    assert_eq!(tree.search_longest_prefix(Method::POST, "/api/users/1"), Some(("/api/users".to_string(), &4, "/1")));
+}
+
+#[test]
+fn search_longest_prefix_stops_at_segment_boundaries() {
+    let tree = tree();
+    assert_eq!(tree.search_longest_prefix(Method::GET, "/apis"), None);
+    assert_eq!(tree.search_longest_prefix(Method::GET, "/other/api"), None);
+    assert_eq!(tree.search_longest_prefix(Method::DELETE, "/api/users"), None);
+}
+
+#[test]
+fn search_longest_prefix_with_wildcards_and_a_root_route() {
+    let mut tree = tree();
+    assert_eq!(tree.search_longest_prefix(Method::GET, "/static/css/site.css"), Some(("/static/*".to_string(), &3, "")));
+
+    // A `/` route matches any path, with the whole path as the rest.
+    tree.insert(Method::GET, "/", 0);
+    assert_eq!(tree.search_longest_prefix(Method::GET, "/other/api"), Some(("/".to_string(), &0, "/other/api")));
+    assert_eq!(tree.search_longest_prefix(Method::GET, "/api/x"), Some(("/api".to_string(), &1, "/x")));
+}
+
+#[test]
+fn search_longest_prefix_falls_back_to_any() {
+    let mut tree = tree();
+    tree.insert(Method::ANY, "/api/users", 5);
+    assert_eq!(tree.search_longest_prefix(Method::DELETE, "/api/users/1"), Some(("/api/users".to_string(), &5, "/1")));
+    assert_eq!(tree.search_longest_prefix(Method::POST, "/api/users/1"), Some(("/api/users".to_string(), &4, "/1")));
+}
+
+#[test]
+fn routers_search_the_longest_prefix_of_head_requests_with_get() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/mount", 1).unwrap();
+    assert_eq!(router.search_longest_prefix(Method::HEAD, "/mount/a"), None);
+
+    router.set_head_can_use_get(true);
+    assert_eq!(router.search_longest_prefix(Method::HEAD, "/mount/a"), Some(("/mount".to_string(), &1, "/a")));
+}
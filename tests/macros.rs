@@ -0,0 +1,49 @@
+use radixtree::{routes, Method};
+
+fn index() -> &'static str {
+    "index"
+}
+
+fn show_user() -> &'static str {
+    "show_user"
+}
+
+#[test]
+fn routes_builds_the_tree() {
+    let tree = routes![
+        GET "/" => index as fn() -> &'static str,
+        GET "/users/$id" => show_user,
+        POST "users/$id" => show_user,
+        ANY "/users" => index,
+    ];
+
+    assert_eq!((tree.search(Method::GET, "/").unwrap().value())(), "index");
+    let result = tree.search(Method::POST, "/users/7").unwrap();
+    assert_eq!(((result.value())(), result.params().get("id")), ("show_user", Some("7")));
+    assert_eq!((tree.search(Method::DELETE, "/users").unwrap().value())(), "index");
+    assert!(tree.search(Method::PUT, "/users/7").is_none());
+}
+
+#[test]
+fn routes_accepts_an_empty_table() {
+    let tree: radixtree::Node<u32> = routes![];
+    assert!(tree.search(Method::GET, "/").is_none());
+}
+
+#[test]
+#[should_panic]
+fn routes_panics_on_ambiguous_params() {
+    let _ = routes![
+        GET "/users/$id" => 1,
+        GET "/users/$name" => 2,
+    ];
+}
+
+#[test]
+#[should_panic]
+fn routes_panics_on_shadowing_wildcards() {
+    let _ = routes![
+        GET "/files/*" => 1,
+        GET "/files/$path+" => 2,
+    ];
+}
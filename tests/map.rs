@@ -0,0 +1,110 @@
+use radixtree::RadixMap;
+
+fn entries(map: &RadixMap<u32>) -> Vec<(Vec<u8>, u32)> {
+    map.iter().map(|(key, &value)| (key, value)).collect()
+}
+
+#[test]
+fn insert_get_and_replace() {
+    let mut map = RadixMap::new();
+    assert!(map.is_empty());
+    assert_eq!(map.insert(b"romane", 1), None);
+    assert_eq!(map.insert(b"romanus", 2), None);
+    assert_eq!(map.insert(b"romulus", 3), None);
+    assert_eq!(map.insert(b"rom", 4), None);
+    assert_eq!(map.insert(b"romane", 5), Some(1));
+
+    assert_eq!(map.len(), 4);
+    assert_eq!(map.get(b"romane"), Some(&5));
+    assert_eq!(map.get(b"rom"), Some(&4));
+    assert_eq!(map.get(b"roma"), None);
+    assert_eq!(map.get(b"romanes"), None);
+    assert!(map.contains_key(b"romulus"));
+
+    *map.get_mut(b"romulus").unwrap() += 10;
+    assert_eq!(map.get(b"romulus"), Some(&13));
+}
+
+#[test]
+fn keys_have_no_routing_semantics() {
+    let mut map = RadixMap::new();
+    map.insert(b"/users/$id", 1);
+    map.insert(b"/files/*", 2);
+    map.insert(&[0xff, 0x00], 3);
+
+    assert_eq!(map.get(b"/users/7"), None);
+    assert_eq!(map.get(b"/users/$id"), Some(&1));
+    assert_eq!(map.get(b"/files/a"), None);
+    assert_eq!(map.get(&[0xff, 0x00]), Some(&3));
+}
+
+#[test]
+fn the_empty_key_is_a_key() {
+    let mut map = RadixMap::new();
+    assert_eq!(map.longest_prefix(b"abc"), None);
+    assert_eq!(map.insert(b"", 1), None);
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get(b""), Some(&1));
+    assert_eq!(map.longest_prefix(b"abc"), Some((&b""[..], &1)));
+    assert_eq!(entries(&map), vec![(Vec::new(), 1)]);
+
+    assert_eq!(map.remove(b""), Some(1));
+    assert_eq!(map.remove(b""), None);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn remove_merges_the_nodes() {
+    let mut map = RadixMap::new();
+    map.insert(b"team", 1);
+    map.insert(b"tea", 2);
+    map.insert(b"ten", 3);
+    map.insert(b"teapot", 4);
+
+    assert_eq!(map.remove(b"te"), None);
+    assert_eq!(map.remove(b"teams"), None);
+    assert_eq!(map.remove(b"tea"), Some(2));
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.get(b"team"), Some(&1));
+    assert_eq!(map.get(b"teapot"), Some(&4));
+    assert_eq!(map.longest_prefix(b"teapots"), Some((&b"teapot"[..], &4)));
+    assert_eq!(map.longest_prefix(b"teak"), None);
+
+    assert_eq!(map.remove(b"team"), Some(1));
+    assert_eq!(map.remove(b"ten"), Some(3));
+    assert_eq!(entries(&map), vec![(b"teapot".to_vec(), 4)]);
+
+    // The removed nodes are reused by new keys.
+    map.insert(b"tea", 5);
+    map.insert(b"toast", 6);
+    assert_eq!(entries(&map), vec![(b"tea".to_vec(), 5), (b"teapot".to_vec(), 4), (b"toast".to_vec(), 6)]);
+}
+
+#[test]
+fn longest_prefix_finds_the_deepest_key() {
+    let mut map = RadixMap::new();
+    map.insert(b"/api", 1);
+    map.insert(b"/api/v1", 2);
+    map.insert(b"/api/v1/users", 3);
+
+    assert_eq!(map.longest_prefix(b"/api/v1/users/7"), Some((&b"/api/v1/users"[..], &3)));
+    assert_eq!(map.longest_prefix(b"/api/v1/use"), Some((&b"/api/v1"[..], &2)));
+    assert_eq!(map.longest_prefix(b"/api/v2"), Some((&b"/api"[..], &1)));
+    assert_eq!(map.longest_prefix(b"/ap"), None);
+}
+
+#[test]
+fn iter_is_in_byte_order() {
+    let mut map = RadixMap::new();
+    for (value, key) in [&b"b"[..], b"abc", b"a", b"ab", b"", b"ba"].into_iter().enumerate() {
+        map.insert(key, value as u32);
+    }
+
+    let keys: Vec<Vec<u8>> = map.keys().collect();
+    assert_eq!(keys, vec![b"".to_vec(), b"a".to_vec(), b"ab".to_vec(), b"abc".to_vec(), b"b".to_vec(), b"ba".to_vec()]);
+    assert_eq!((&map).into_iter().count(), map.len());
+
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(map.iter().count(), 0);
+}
@@ -0,0 +1,47 @@
+use radixtree::{Method, Node};
+
+#[test]
+fn an_empty_tree_has_its_root() {
+    let stats = Node::<u32>::new().memory_usage();
+    assert_eq!(stats.node_count(), 1);
+    assert_eq!(stats.path_bytes(), 1);
+    assert_eq!(stats.leaf_map_bytes(), 0);
+    assert!(stats.heap_bytes() > 0);
+}
+
+#[test]
+fn stats_count_the_nodes_and_their_paths() {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/users", 1u32);
+    tree.insert(Method::GET, "/uploads", 2);
+    tree.insert(Method::POST, "/users/$id", 3);
+
+    // `/`, `u`, `sers`, `ploads`, `/` and `$id`.
+    let stats = tree.memory_usage();
+    assert_eq!(stats.node_count(), 6);
+    assert_eq!(stats.path_bytes(), "/".len() + "u".len() + "sers".len() + "ploads".len() + "/".len() + "id".len());
+    assert!(stats.leaf_map_bytes() >= 3 * size_of::<u32>());
+    assert!(stats.heap_bytes() > stats.path_bytes() + stats.leaf_map_bytes());
+}
+
+#[test]
+fn stats_grow_with_the_routes_and_shrink_after_removals() {
+    let mut tree = Node::new();
+    let empty = tree.memory_usage();
+    for i in 0..1000 {
+        tree.insert(Method::GET, &format!("/items/{}/details", i), i);
+    }
+
+    let full = tree.memory_usage();
+    assert!(full.node_count() > 1000);
+    assert!(full.path_bytes() > empty.path_bytes());
+    assert!(full.heap_bytes() > 1000 * size_of::<u32>());
+
+    for i in 0..1000 {
+        tree.remove(&format!("/items/{}/details", i));
+    }
+    let removed = tree.memory_usage();
+    assert_eq!(removed.node_count(), 1);
+    assert_eq!(removed.path_bytes(), 1);
+    assert_eq!(removed.leaf_map_bytes(), 0);
+}
@@ -0,0 +1,74 @@
+use radixtree::{ConflictKind, Method, Node, Resolution, Router};
+
+fn tree(routes: &[(Method, &str, u32)]) -> Node<u32> {
+    let mut tree = Node::new();
+    for (method, pattern, value) in routes {
+        tree.insert(method.clone(), pattern, *value);
+    }
+    tree
+}
+
+fn routes(tree: &Node<u32>) -> Vec<(String, u32)> {
+    tree.iter().map(|(method, pattern, value)| (format!("{} {}", method, pattern), *value)).collect()
+}
+
+#[test]
+fn merge_grafts_the_other_routes() {
+    let mut users = tree(&[(Method::GET, "/users", 1), (Method::GET, "/users/$id", 2)]);
+    let posts = tree(&[(Method::GET, "/posts/$id", 3), (Method::POST, "/users", 4)]);
+    users.merge(posts, |_, _| unreachable!()).unwrap();
+
+    assert_eq!(routes(&users), [("GET /posts/$id".to_string(), 3), ("GET /users".to_string(), 1), ("POST /users".to_string(), 4), ("GET /users/$id".to_string(), 2)]);
+    assert_eq!(users.search(Method::GET, "/posts/9").unwrap().params().get("id"), Some("9"));
+    assert!(users.validate().is_ok());
+}
+
+#[test]
+fn the_resolver_picks_the_value_of_a_collision() {
+    let existing = || tree(&[(Method::GET, "/users", 1), (Method::GET, "/a", 10)]);
+    let incoming = || tree(&[(Method::GET, "/users", 2), (Method::GET, "/b", 20)]);
+
+    let mut kept = existing();
+    let mut calls = Vec::new();
+    kept.merge(incoming(), |existing, incoming| {
+        calls.push((*existing, *incoming));
+        Resolution::Existing
+    }).unwrap();
+    assert_eq!(calls, [(1, 2)]);
+    assert_eq!(*kept.search(Method::GET, "/users").unwrap().value(), 1);
+    assert_eq!(*kept.search(Method::GET, "/b").unwrap().value(), 20);
+
+    let mut replaced = existing();
+    replaced.merge(incoming(), |_, _| Resolution::Incoming).unwrap();
+    assert_eq!(*replaced.search(Method::GET, "/users").unwrap().value(), 2);
+
+    let mut merged = existing();
+    merged.merge(incoming(), |existing, incoming| Resolution::Merged(existing + incoming)).unwrap();
+    assert_eq!(*merged.search(Method::GET, "/users").unwrap().value(), 3);
+    assert_eq!(merged.len(), 3);
+}
+
+#[test]
+fn ambiguous_routes_are_errors_before_any_route_moves() {
+    let mut tree = self::tree(&[(Method::GET, "/users/$id", 1), (Method::GET, "/files/*", 2)]);
+    let before = routes(&tree);
+
+    let other = self::tree(&[(Method::GET, "/a", 3), (Method::POST, "/users/$name", 4)]);
+    assert_eq!(tree.merge(other, |_, _| Resolution::Incoming), Err(ConflictKind::AmbiguousParams(vec!["id".to_string()])));
+    let other = self::tree(&[(Method::GET, "/a", 3), (Method::GET, "/files/$path+", 4)]);
+    assert_eq!(tree.merge(other, |_, _| Resolution::Incoming), Err(ConflictKind::Shadowing("*".to_string())));
+    assert_eq!(routes(&tree), before);
+}
+
+#[test]
+fn router_merge_keeps_the_names() {
+    let mut router = Router::new();
+    router.insert_named(Method::GET, "user", "/users/$id", 1).unwrap();
+    let mut other = Router::new();
+    other.insert_named(Method::GET, "post", "/posts/$id", 2).unwrap();
+    router.merge(other, |_, _| Resolution::Existing).unwrap();
+
+    assert_eq!(router.path_for("post", &[("id", "9")]).unwrap(), "/posts/9");
+    assert_eq!(router.path_for("user", &[("id", "7")]).unwrap(), "/users/7");
+    assert_eq!(*router.search(Method::GET, "/posts/9").unwrap().value(), 2);
+}
@@ -0,0 +1,56 @@
+use radixtree::Method;
+
+#[test]
+fn methods_round_trip_through_display() {
+    let methods = [Method::GET, Method::UNLOCK, Method::ANY, Method::extension("PURGE").unwrap()];
+    for method in methods {
+        assert_eq!(method.to_string().parse::<Method>().unwrap(), method);
+        assert_eq!(Method::try_from(method.as_str().as_bytes()).unwrap(), method);
+    }
+}
+
+#[test]
+fn parsing_is_case_sensitive() {
+    let get: Method = "get".parse().unwrap();
+    assert_ne!(get, Method::GET);
+    assert_eq!(get.as_str(), "get");
+    assert_eq!(Method::from_str_lossy("get"), get);
+}
+
+#[test]
+fn invalid_names_are_rejected() {
+    for name in ["", "GET /", "GÉT", "A:B"] {
+        assert!(name.parse::<Method>().is_err(), "{:?}", name);
+    }
+    assert!(Method::try_from(&b"\xFF"[..]).is_err());
+    assert_eq!(Method::from_str_lossy("GET /"), Method::GET);
+}
+
+#[test]
+fn webdav_methods_are_standard_methods() {
+    let methods = [
+        (Method::PROPFIND, "PROPFIND"),
+        (Method::PROPPATCH, "PROPPATCH"),
+        (Method::MKCOL, "MKCOL"),
+        (Method::COPY, "COPY"),
+        (Method::MOVE, "MOVE"),
+        (Method::LOCK, "LOCK"),
+        (Method::UNLOCK, "UNLOCK"),
+    ];
+    for (method, name) in methods {
+        assert_eq!(method.as_str(), name);
+        assert_eq!(name.parse::<Method>().unwrap(), method);
+        assert_eq!(Method::extension(name).unwrap(), method);
+    }
+}
+
+#[test]
+fn webdav_methods_are_routed() {
+    let mut tree = radixtree::Node::new();
+    tree.insert(Method::PROPFIND, "/dav/*", "PROPFIND");
+    tree.insert(Method::MKCOL, "/dav/*", "MKCOL");
+
+    assert_eq!(tree.search(Method::PROPFIND, "/dav/a/b").unwrap().value(), &"PROPFIND");
+    assert_eq!(tree.search(Method::MKCOL, "/dav/a").unwrap().value(), &"MKCOL");
+    assert!(tree.search(Method::extension("PROPFINDX").unwrap(), "/dav/a").is_none());
+}
@@ -0,0 +1,59 @@
+use radixtree::{Method, MethodSet, Node, Router};
+
+#[test]
+fn sets_hold_standard_methods_and_any() {
+    let mut set = MethodSet::new();
+    assert!(set.is_empty());
+    assert!(set.insert(Method::POST));
+    assert!(set.insert(Method::GET));
+    assert!(set.insert(Method::ANY));
+    assert!(set.insert(Method::GET));
+    assert!(!set.insert(Method::extension("PURGE").unwrap()));
+
+    assert_eq!(set.len(), 3);
+    assert_eq!(set.iter().collect::<Vec<_>>(), [Method::GET, Method::POST, Method::ANY]);
+    assert!(!set.contains(&Method::extension("PURGE").unwrap()));
+
+    assert!(set.remove(&Method::ANY));
+    assert!(!set.remove(&Method::ANY));
+    assert_eq!(set, Method::GET | Method::POST);
+}
+
+#[test]
+fn all_has_every_standard_method() {
+    let all = MethodSet::all();
+    assert_eq!(all.len(), 16);
+    assert!(all.contains(&Method::UNLOCK));
+    assert!(!all.contains(&Method::ANY));
+}
+
+#[test]
+fn set_operations() {
+    let read = Method::GET | Method::HEAD;
+    let write = MethodSet::from([Method::POST, Method::PUT, Method::GET]);
+
+    assert_eq!(read.union(write), Method::GET | Method::HEAD | Method::POST | Method::PUT);
+    assert_eq!(read.intersection(write), MethodSet::from(Method::GET));
+    assert_eq!(write.difference(read), Method::POST | Method::PUT);
+
+    let mut set = read;
+    set |= Method::DELETE;
+    set.extend([Method::PATCH]);
+    assert_eq!(set.into_iter().collect::<Vec<_>>(), [Method::GET, Method::HEAD, Method::PATCH, Method::DELETE]);
+
+    let methods = [Method::GET, Method::extension("PURGE").unwrap()];
+    assert_eq!(MethodSet::from(&methods[..]), MethodSet::from(Method::GET));
+}
+
+#[test]
+fn routes_are_inserted_for_a_set_of_methods() {
+    let mut tree = Node::new();
+    tree.insert_methods(Method::GET | Method::HEAD, "/users", 1);
+    assert_eq!(tree.search(Method::HEAD, "/users").unwrap().value(), &1);
+    assert!(tree.search(Method::POST, "/users").is_none());
+
+    let mut router = Router::new();
+    router.insert_methods(Method::PUT | Method::PATCH, "/users/$id", 2).unwrap();
+    assert_eq!(router.search(Method::PATCH, "/users/1").unwrap().value(), &2);
+    assert_eq!(router.remove_methods(Method::PUT | Method::DELETE, "/users/$id"), Some(vec![(Method::PUT, 2)]));
+}
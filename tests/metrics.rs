@@ -0,0 +1,59 @@
+use std::sync::{Arc, Mutex};
+
+use radixtree::{Method, Metrics, Router};
+
+#[derive(Default)]
+struct Counts {
+    matches: Mutex<Vec<(Method, String)>>,
+    misses: Mutex<Vec<(Method, String)>>,
+}
+
+impl Metrics for Counts {
+    fn on_match(&self, method: &Method, pattern: &str) {
+        self.matches.lock().unwrap().push((method.clone(), pattern.to_string()));
+    }
+
+    fn on_miss(&self, method: &Method, path: &str) {
+        self.misses.lock().unwrap().push((method.clone(), path.to_string()));
+    }
+}
+
+/// Metrics which only count the matches.
+#[derive(Default)]
+struct Matches(Mutex<usize>);
+
+impl Metrics for Matches {
+    fn on_match(&self, _: &Method, _: &str) {
+        *self.0.lock().unwrap() += 1;
+    }
+}
+
+#[test]
+fn searches_report_the_matched_pattern_or_the_missed_path() {
+    let counts = Arc::new(Counts::default());
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users/$id", "USER").unwrap();
+    router.set_metrics(counts.clone());
+
+    router.search(Method::GET, "/users/7");
+    router.search(Method::POST, "/users/7");
+    router.search(Method::GET, "/posts");
+
+    assert_eq!(*counts.matches.lock().unwrap(), [(Method::GET, "/users/$id".to_string())]);
+    assert_eq!(
+        *counts.misses.lock().unwrap(),
+        [(Method::POST, "/users/7".to_string()), (Method::GET, "/posts".to_string())]
+    );
+}
+
+#[test]
+fn misses_are_optional() {
+    let matches = Arc::new(Matches::default());
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users/$id", "USER").unwrap();
+    router.set_metrics(matches.clone());
+
+    router.search(Method::GET, "/users/7");
+    router.search(Method::GET, "/posts");
+    assert_eq!(*matches.0.lock().unwrap(), 1);
+}
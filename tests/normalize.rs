@@ -0,0 +1,91 @@
+use radixtree::{DotSegments, Method, Router};
+
+fn router() -> Router<&'static str> {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/static/*", "STATIC").unwrap();
+    router.insert(Method::GET, "/secret", "SECRET").unwrap();
+    router.insert(Method::GET, "/users/$id", "USER").unwrap();
+    router.insert(Method::GET, "/", "INDEX").unwrap();
+    router
+}
+
+fn value(router: &Router<&'static str>, path: &str) -> Option<&'static str> {
+    router.search(Method::GET, path).map(|result| *result.value())
+}
+
+#[test]
+fn dot_segments_are_kept_by_default() {
+    let router = router();
+    assert_eq!(value(&router, "/static/../secret"), Some("STATIC"));
+    assert_eq!(value(&router, "/static/%2e%2e/secret"), Some("STATIC"));
+}
+
+#[test]
+fn normalized_dot_segments_cant_escape_a_wildcard() {
+    let mut router = router();
+    router.set_dot_segments(DotSegments::Normalize);
+
+    assert_eq!(value(&router, "/static/../secret"), Some("SECRET"));
+    assert_eq!(value(&router, "/static/%2e%2e/secret"), Some("SECRET"));
+    assert_eq!(value(&router, "/static/.%2E/secret"), Some("SECRET"));
+    assert_eq!(value(&router, "/static/css/../../secret"), Some("SECRET"));
+    assert_eq!(value(&router, "/static/./css/../app.js"), Some("STATIC"));
+
+    // A `..` at the root is dropped.
+    assert_eq!(value(&router, "/../../secret"), Some("SECRET"));
+    assert_eq!(router.search(Method::GET, "/users/7/../8").unwrap().params().get("id"), Some("8"));
+    assert_eq!(value(&router, "/static/.."), Some("INDEX"));
+}
+
+#[test]
+fn other_dot_names_are_not_dot_segments() {
+    let mut router = router();
+    router.set_dot_segments(DotSegments::Reject);
+
+    assert_eq!(value(&router, "/static/.hidden"), Some("STATIC"));
+    assert_eq!(value(&router, "/static/.../x"), Some("STATIC"));
+    assert_eq!(value(&router, "/static/a..b"), Some("STATIC"));
+    assert_eq!(value(&router, "/static/%2e%2e%2e"), Some("STATIC"));
+    assert_eq!(value(&router, "/users/..x"), Some("USER"));
+}
+
+#[test]
+fn rejected_dot_segments_match_nothing() {
+    let mut router = router();
+    router.set_dot_segments(DotSegments::Reject);
+
+    assert_eq!(value(&router, "/static/../secret"), None);
+    assert_eq!(value(&router, "/static/%2E%2e/secret"), None);
+    assert_eq!(value(&router, "/static/./app.js"), None);
+    assert_eq!(value(&router, "/static/app.js"), Some("STATIC"));
+}
+
+#[test]
+fn slashes_are_not_merged_by_default() {
+    let router = router();
+    assert_eq!(value(&router, "//secret"), None);
+    assert_eq!(value(&router, "/users//7"), None);
+}
+
+#[test]
+fn merged_slashes_match_the_single_slash_route() {
+    let mut router = router();
+    router.set_merge_slashes(true);
+
+    assert_eq!(value(&router, "//secret"), Some("SECRET"));
+    assert_eq!(value(&router, "///"), Some("INDEX"));
+    let result = router.search(Method::GET, "/users///42").unwrap();
+    assert_eq!((*result.value(), result.params().get("id")), ("USER", Some("42")));
+    assert_eq!(value(&router, "/static//css///app.js"), Some("STATIC"));
+}
+
+#[test]
+fn slashes_are_merged_before_dot_segments_are_removed() {
+    let mut router = router();
+    router.set_merge_slashes(true);
+    router.set_dot_segments(DotSegments::Normalize);
+
+    // Without merging, `..` would only remove the empty segment.
+    assert_eq!(value(&router, "/static//..//secret"), Some("SECRET"));
+    assert_eq!(value(&router, "//static/./..//users//7"), Some("USER"));
+}
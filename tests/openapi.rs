@@ -0,0 +1,151 @@
+#![cfg(feature = "openapi")]
+
+use radixtree::openapi::{self, Info};
+use radixtree::{Method, Node, OpenApiError};
+use serde_json::{json, Value};
+
+fn tree() -> Node<&'static str> {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/users", "list_users");
+    tree.insert(Method::POST, "/users", "create_user");
+    tree.insert(Method::GET, "/users/$id/posts/$post", "show_post");
+    tree.insert(Method::ANY, "/users/$id", "user");
+    tree.insert(Method::DELETE, "/users/$id", "delete_user");
+    tree.insert(Method::GET, "/files/$path*", "files");
+    tree.insert(Method::GET, "/static/*", "static");
+    tree.insert("PURGE".parse().unwrap(), "/cache", "purge");
+    tree
+}
+
+fn routes<V: Clone + Ord>(tree: &Node<V>) -> Vec<(String, String, V)> {
+    let mut routes: Vec<_> = tree.iter().map(|(method, pattern, value)| (method.to_string(), pattern, value.clone())).collect();
+    routes.sort();
+    routes
+}
+
+#[test]
+fn document_describes_the_routes() {
+    let document = openapi::document(&tree(), &Info::new("Users", "1.0"));
+    assert_eq!(document["openapi"], "3.0.3");
+    assert_eq!(document["info"], json!({"title": "Users", "version": "1.0"}));
+
+    let paths = document["paths"].as_object().unwrap();
+    let mut templates: Vec<&str> = paths.keys().map(String::as_str).collect();
+    templates.sort();
+    assert_eq!(templates, ["/files/{path}", "/static/{wildcard}", "/users", "/users/{id}", "/users/{id}/posts/{post}"]);
+
+    let post = &paths["/users/{id}/posts/{post}"]["get"];
+    let names: Vec<&Value> = post["parameters"].as_array().unwrap().iter().map(|parameter| &parameter["name"]).collect();
+    assert_eq!(names, ["id", "post"]);
+    assert_eq!(post["parameters"][0]["in"], "path");
+    assert!(post.get("x-radixtree-pattern").is_none());
+    assert!(paths["/users"]["get"].get("parameters").is_none());
+}
+
+#[test]
+fn document_lists_any_under_the_other_operations() {
+    let document = openapi::document(&tree(), &Info::new("Users", "1.0"));
+    let item = document["paths"]["/users/{id}"].as_object().unwrap();
+
+    let mut methods: Vec<&str> = item.keys().map(String::as_str).collect();
+    methods.sort();
+    assert_eq!(methods, ["delete", "get", "head", "options", "patch", "post", "put", "trace"]);
+}
+
+#[test]
+fn document_keeps_the_patterns_of_wildcards() {
+    let document = openapi::document(&tree(), &Info::new("Users", "1.0"));
+    let files = &document["paths"]["/files/{path}"]["get"];
+    assert_eq!(files["x-radixtree-pattern"], "/files/$path*");
+    assert_eq!(files["parameters"][0]["description"], "The rest of the path");
+    assert_eq!(document["paths"]["/static/{wildcard}"]["get"]["x-radixtree-pattern"], "/static/*");
+}
+
+#[test]
+fn document_with_merges_the_metadata() {
+    let document = openapi::document_with(&tree(), &Info::new("Users", "1.0"), |method, pattern, value| {
+        (*method == Method::GET && pattern == "/users").then(|| json!({"summary": value, "tags": ["users"]}))
+    });
+
+    assert_eq!(document["paths"]["/users"]["get"]["summary"], "list_users");
+    assert_eq!(document["paths"]["/users"]["get"]["tags"], json!(["users"]));
+    assert!(document["paths"]["/users"]["post"].get("summary").is_none());
+}
+
+#[test]
+fn from_openapi_registers_the_operations() {
+    let spec = json!({
+        "paths": {
+            "/users/{id}": {
+                "get": {"operationId": "showUser"},
+                "delete": {"operationId": "deleteUser"},
+                "parameters": [],
+            },
+            "/files/{name}.{ext}": {
+                "get": {"operationId": "download"},
+            },
+        },
+    });
+    let tree = Node::from_openapi(&spec, |operation| {
+        assert_eq!(operation.path.replace('{', "$").replace('}', ""), operation.pattern);
+        operation.operation["operationId"].as_str().unwrap().to_string()
+    }).unwrap();
+
+    let result = tree.search(Method::GET, "/users/7").unwrap();
+    assert_eq!((result.value().as_str(), result.params().get("id")), ("showUser", Some("7")));
+    assert_eq!(tree.search(Method::DELETE, "/users/7").unwrap().value(), "deleteUser");
+    let result = tree.search(Method::GET, "/files/report.pdf").unwrap();
+    assert_eq!((result.params().get("name"), result.params().get("ext")), (Some("report"), Some("pdf")));
+    assert!(tree.search(Method::PUT, "/users/7").is_none());
+}
+
+#[test]
+fn from_openapi_rejects_invalid_documents() {
+    let load = |spec: Value| Node::from_openapi(&spec, |_| ()).unwrap_err();
+
+    assert_eq!(load(json!({"openapi": "3.0.3"})), OpenApiError::MissingPaths);
+    assert_eq!(load(json!({"paths": {"/users/{id": {}}})), OpenApiError::UnsupportedTemplate("/users/{id".to_string()));
+    assert_eq!(load(json!({"paths": {"/users/{}": {}}})), OpenApiError::UnsupportedTemplate("/users/{}".to_string()));
+    assert_eq!(load(json!({"paths": {"/users/{a}{b}": {}}})), OpenApiError::UnsupportedTemplate("/users/{a}{b}".to_string()));
+}
+
+#[test]
+fn from_openapi_ignores_a_pattern_of_another_path() {
+    let spec = json!({
+        "paths": {
+            "/files/{path}": {
+                "get": {"x-radixtree-pattern": "/static/*"},
+            },
+        },
+    });
+    let tree = Node::from_openapi(&spec, |operation| operation.pattern.to_string()).unwrap();
+    assert_eq!(tree.search(Method::GET, "/files/a").unwrap().value(), "/files/$path");
+    assert!(tree.search(Method::GET, "/files/a/b").is_none());
+}
+
+#[test]
+fn documents_round_trip() {
+    let tree = tree();
+    let document = openapi::document(&tree, &Info::new("Users", "1.0"));
+    let imported = Node::from_openapi(&document, |operation| operation.pattern.to_string()).unwrap();
+
+    // ANY is listed under every operation, and PURGE can't be described.
+    let mut expected: Vec<(String, String, String)> = routes(&tree).into_iter()
+        .filter(|(method, ..)| method != "PURGE")
+        .flat_map(|(method, pattern, _)| {
+            let methods = if method == "ANY" {
+                ["GET", "PUT", "POST", "OPTIONS", "HEAD", "PATCH", "TRACE"].map(String::from).to_vec()
+            } else {
+                vec![method]
+            };
+            methods.into_iter().map(move |method| (method, pattern.clone(), pattern.clone()))
+        })
+        .collect();
+    expected.sort();
+    assert_eq!(routes(&imported), expected);
+
+    let result = imported.search(Method::GET, "/files/a/b/c").unwrap();
+    assert_eq!(result.params().get("path"), Some("a/b/c"));
+    assert_eq!(imported.search(Method::GET, "/static/x/y").unwrap().value(), "/static/*");
+    assert_eq!(openapi::document(&imported, &Info::new("Users", "1.0")), document);
+}
@@ -0,0 +1,57 @@
+use radixtree::{ConflictKind, Method, PathForError, Resolution, Router};
+
+fn router() -> Router<()> {
+    let mut router = Router::new();
+    router.insert_named(Method::GET, "user", "/users/$id", ()).unwrap();
+    router.insert_named(Method::GET, "file", "/files/$path+", ()).unwrap();
+    router.insert_named(Method::GET, "docs", "/docs/$page*", ()).unwrap();
+    router.insert_named(Method::GET, "static", "/static/*", ()).unwrap();
+    router
+}
+
+#[test]
+fn values_are_percent_encoded() {
+    let router = router();
+    assert_eq!(router.path_for("user", &[("id", "a?b#c%d e")]).unwrap(), "/users/a%3Fb%23c%25d%20e");
+    assert_eq!(router.path_for("user", &[("id", "café")]).unwrap(), "/users/caf%C3%A9");
+    assert_eq!(router.path_for("user", &[("id", "a-b.c_d~e:f@g")]).unwrap(), "/users/a-b.c_d~e:f@g");
+    assert_eq!(router.path_for("file", &[("path", "a b/c?d")]).unwrap(), "/files/a%20b/c%3Fd");
+    assert_eq!(router.path_for("static", &[("*", "css/site.css")]).unwrap(), "/static/css/site.css");
+}
+
+#[test]
+fn empty_values_are_rejected() {
+    let router = router();
+    let invalid = |name: &str| Err(PathForError::InvalidParam(name.to_string()));
+    assert_eq!(router.path_for("user", &[("id", "")]), invalid("id"));
+    assert_eq!(router.path_for("file", &[("path", "")]), invalid("path"));
+    assert_eq!(router.path_for("static", &[("*", "")]), invalid("*"));
+    assert_eq!(router.path_for("docs", &[("page", "")]).unwrap(), "/docs/");
+}
+
+#[test]
+fn names_of_other_patterns_are_taken() {
+    let mut router = router();
+    let error = router.insert_named(Method::POST, "user", "/people/$id", ()).unwrap_err();
+    assert_eq!(error, ConflictKind::NameTaken("/users/$id".to_string()));
+    assert!(router.search(Method::POST, "/people/1").is_none());
+    router.insert_named(Method::POST, "user", "/users/$id", ()).unwrap();
+
+    let mut nested = Router::new();
+    nested.insert_named(Method::GET, "user", "/$id", ()).unwrap();
+    nested.insert(Method::GET, "/", ()).unwrap();
+    let error = router.nest("/people", nested).unwrap_err();
+    assert_eq!(error, ConflictKind::NameTaken("/users/$id".to_string()));
+    assert!(router.search(Method::GET, "/people").is_none());
+}
+
+#[test]
+fn merge_returns_taken_names() {
+    let mut router = router();
+    let mut other = Router::new();
+    other.insert_named(Method::GET, "user", "/people/$id", ()).unwrap();
+
+    let error = router.merge(other, |_, _| Resolution::Existing).unwrap_err();
+    assert_eq!(error, ConflictKind::NameTaken("/users/$id".to_string()));
+    assert!(router.search(Method::GET, "/people/1").is_none());
+}
@@ -0,0 +1,124 @@
+use radixtree::PathTree;
+
+type Params = Vec<(String, String)>;
+
+fn found(tree: &PathTree<u32>, path: &str) -> Option<(u32, String, Params)> {
+    let result = tree.search(path)?;
+    let params = result.params().iter().map(|param| (param.name().clone(), param.value().clone())).collect();
+    Some((*result.value(), result.pattern().to_string(), params))
+}
+
+fn params(pairs: &[(&str, &str)]) -> Params {
+    pairs.iter().map(|&(name, value)| (name.to_string(), value.to_string())).collect()
+}
+
+#[test]
+fn slash_separated_patterns() {
+    let mut tree = PathTree::new();
+    tree.insert("/orders/$id", 1);
+    tree.insert("orders/new", 2);
+    tree.insert("/events/*", 3);
+
+    assert_eq!(found(&tree, "/orders/7"), Some((1, "/orders/$id".to_string(), params(&[("id", "7")]))));
+    assert_eq!(found(&tree, "orders/new").map(|(value, ..)| value), Some(2));
+    assert_eq!(found(&tree, "/events/a/b").map(|(value, ..)| value), Some(3));
+    assert_eq!(found(&tree, "/orders"), None);
+    assert_eq!(tree.len(), 3);
+}
+
+#[test]
+fn custom_separator_patterns() {
+    let mut tree = PathTree::with_separator(".");
+    tree.insert("$tenant.example.com", 1);
+    tree.insert("www.example.com", 2);
+    tree.insert("cdn.*", 3);
+
+    assert_eq!(found(&tree, "acme.example.com"), Some((1, "$tenant.example.com".to_string(), params(&[("tenant", "acme")]))));
+    assert_eq!(found(&tree, "www.example.com").map(|(value, ..)| value), Some(2));
+    assert_eq!(found(&tree, "cdn.eu.example.org").map(|(value, ..)| value), Some(3));
+    assert_eq!(found(&tree, "a.b.example.com"), None);
+}
+
+#[test]
+fn multi_character_separators() {
+    let mut tree = PathTree::with_separator("::");
+    tree.insert("db::$action", 1);
+    tree.insert("db::migrate::$version", 2);
+
+    assert_eq!(found(&tree, "db::seed"), Some((1, "db::$action".to_string(), params(&[("action", "seed")]))));
+    assert_eq!(found(&tree, "db::migrate::42"), Some((2, "db::migrate::$version".to_string(), params(&[("version", "42")]))));
+    assert_eq!(found(&tree, "db:seed"), None);
+}
+
+#[test]
+fn a_literal_slash_stays_inside_its_segment() {
+    let mut tree = PathTree::with_separator(".");
+    tree.insert("a/b.$rest", 1);
+    tree.insert("$name.txt", 2);
+
+    assert_eq!(found(&tree, "a/b.c/d"), Some((1, "a/b.$rest".to_string(), params(&[("rest", "c/d")]))));
+    assert_eq!(found(&tree, "x/y.txt"), Some((2, "$name.txt".to_string(), params(&[("name", "x/y")]))));
+    assert_eq!(found(&tree, "a.b.c"), None);
+}
+
+#[test]
+fn escapes_round_trip() {
+    // The private use character which escapes `/` is an ordinary character
+    // of the patterns and paths.
+    let mut tree = PathTree::with_separator(".");
+    tree.insert("\u{E000}0.$name", 1);
+    tree.insert("\u{E000}1/.x", 2);
+
+    assert_eq!(found(&tree, "\u{E000}0.a\u{E000}/"), Some((1, "\u{E000}0.$name".to_string(), params(&[("name", "a\u{E000}/")]))));
+    assert_eq!(found(&tree, "\u{E000}1/.x"), Some((2, "\u{E000}1/.x".to_string(), params(&[]))));
+    assert_eq!(found(&tree, "/0.a"), None);
+    assert_eq!(found(&tree, "\u{E000}1\u{E000}0.x"), None);
+}
+
+#[test]
+fn remove_and_insert_replace() {
+    let mut tree = PathTree::new();
+    tree.insert("/orders/$id", 1);
+    assert_eq!(tree.insert_replace("orders/$id", 2), Some(1));
+    assert_eq!(tree.insert_replace("/orders/new", 3), None);
+    assert_eq!(found(&tree, "/orders/7").map(|(value, ..)| value), Some(2));
+    assert_eq!(tree.len(), 2);
+
+    assert_eq!(tree.remove("/orders/new"), Some(3));
+    assert_eq!(tree.remove("/orders/new"), None);
+    assert_eq!(found(&tree, "/orders/new").map(|(value, ..)| value), Some(2));
+    assert_eq!(tree.remove("orders/$id"), Some(2));
+    assert!(tree.is_empty());
+    assert_eq!(found(&tree, "/orders/7"), None);
+}
+
+#[test]
+fn remove_and_insert_replace_with_a_separator() {
+    let mut tree = PathTree::with_separator(".");
+    tree.insert("$tenant.example.com", 1);
+    assert_eq!(tree.insert_replace("$tenant.example.com", 2), Some(1));
+    assert_eq!(tree.insert_replace("www.example.com", 3), None);
+    assert_eq!(found(&tree, "acme.example.com").map(|(value, ..)| value), Some(2));
+    assert_eq!(tree.len(), 2);
+
+    assert_eq!(tree.remove("www.example.com"), Some(3));
+    assert_eq!(tree.remove("www.example.com"), None);
+    assert_eq!(found(&tree, "www.example.com").map(|(value, ..)| value), Some(2));
+    assert_eq!(tree.remove("$tenant.example.com"), Some(2));
+    assert!(tree.is_empty());
+    assert_eq!(found(&tree, "acme.example.com"), None);
+}
+
+#[test]
+#[should_panic]
+fn insert_panics_on_a_duplicate() {
+    let mut tree = PathTree::new();
+    tree.insert("/orders", 1);
+    tree.insert("orders", 2);
+}
+
+#[test]
+#[should_panic]
+fn with_separator_rejects_pattern_characters() {
+    let _ = PathTree::<u32>::with_separator("$");
+}
@@ -0,0 +1,72 @@
+use radixtree::{Method, Node, Params, PersistentNode};
+
+/// A xorshift generator, so that the routes are the same on every run.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[(self.next() % items.len() as u64) as usize]
+    }
+
+    fn path(&mut self, segments: &[&str]) -> String {
+        (0..self.next() % 3 + 1).map(|_| format!("/{}", self.pick(segments))).collect()
+    }
+}
+
+const PATTERN_SEGMENTS: [&str; 14] = ["a", "ab", "b", "", "$x", "$y", "*", "$x.b", "a$x", "$x.$y", ".b", "a*", "$p+", "$q*"];
+const PATH_SEGMENTS: [&str; 10] = ["a", "ab", "b", "a.b", "ab.b", ".b", "a.", "", "a*", "aa.b.b"];
+const METHODS: [Method; 2] = [Method::GET, Method::POST];
+
+fn search(tree: &Node<usize>, persistent: &PersistentNode<usize>, method: Method, path: &str) -> [Option<(usize, String, Params)>; 2] {
+    let node = tree.search(method.clone(), path).map(|result| (*result.value(), result.pattern().to_string(), result.params().clone()));
+    let shared = persistent.search(method, path).map(|result| (*result.value(), result.pattern().to_string(), result.params().clone()));
+    [node, shared]
+}
+
+#[test]
+fn persistent_trees_match_like_trees() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..2000 {
+        let (mut tree, mut persistent) = (Node::new(), PersistentNode::new());
+        let mut routes = Vec::new();
+        for value in 0..rng.next() % 6 + 1 {
+            let (method, pattern) = (rng.pick(&METHODS).clone(), rng.path(&PATTERN_SEGMENTS));
+            let inserted = tree.try_extend([(method.clone(), pattern.as_str(), value as usize)]);
+            match persistent.try_insert(method.clone(), &pattern, value as usize) {
+                Ok(next) => {
+                    assert_eq!(inserted, Ok(()), "{}", pattern);
+                    persistent = next;
+                    routes.push((method, pattern));
+                }
+                Err(error) => assert_eq!(inserted, Err(error), "{}", pattern),
+            }
+        }
+        assert_eq!(tree.len(), persistent.len());
+
+        for _ in 0..10 {
+            let (method, path) = (rng.pick(&METHODS).clone(), rng.path(&PATH_SEGMENTS));
+            let [node, shared] = search(&tree, &persistent, method, &path);
+            assert_eq!(node, shared, "{} in {:?}", path, routes);
+        }
+
+        if routes.is_empty() {
+            continue;
+        }
+
+        let (method, pattern) = rng.pick(&routes).clone();
+        tree.remove_method(method.clone(), &pattern);
+        persistent = persistent.remove_method(method, &pattern);
+        assert_eq!(tree.len(), persistent.len());
+        for (method, pattern) in routes.iter() {
+            let [node, shared] = search(&tree, &persistent, method.clone(), pattern);
+            assert_eq!(node, shared, "{} in {:?}", pattern, routes);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use radixtree::{Method, Node, Router};
+
+fn tree() -> Node<u32> {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/api/users", 1);
+    tree.insert(Method::POST, "/api/users", 2);
+    tree.insert(Method::GET, "/api/users/$id", 3);
+    tree.insert(Method::GET, "/apis", 4);
+    tree.insert(Method::GET, "/api/files/*", 5);
+    tree.insert(Method::GET, "/admin", 6);
+    tree.insert(Method::GET, "/", 7);
+    tree
+}
+
+fn patterns<'a>(routes: impl Iterator<Item = (&'a Method, String, &'a u32)>) -> Vec<String> {
+    routes.map(|(method, pattern, _)| format!("{} {}", method, pattern)).collect()
+}
+
+#[test]
+fn iter_prefix_compares_the_text_of_the_patterns() {
+    let tree = tree();
+    assert_eq!(patterns(tree.iter_prefix("/api/")), ["GET /api/files/*", "GET /api/users", "POST /api/users", "GET /api/users/$id"]);
+    assert_eq!(patterns(tree.iter_prefix("/api")).len(), 5);
+    assert_eq!(patterns(tree.iter_prefix("/api/users/$")), ["GET /api/users/$id"]);
+    assert_eq!(patterns(tree.iter_prefix("api/f")), ["GET /api/files/*"]);
+    assert!(patterns(tree.iter_prefix("/missing")).is_empty());
+}
+
+#[test]
+fn iter_prefix_splits_inside_a_node() {
+    let tree = tree();
+    // `/a` ends inside the static text shared by `/api` and `/admin`.
+    assert_eq!(patterns(tree.iter_prefix("/ad")), ["GET /admin"]);
+    assert_eq!(patterns(tree.iter_prefix("/a")).len(), 6);
+    assert_eq!(patterns(tree.iter_prefix("")).len(), tree.len());
+    assert_eq!(patterns(tree.iter_prefix("/")).len(), tree.len());
+}
+
+#[test]
+fn remove_prefix_removes_the_subtree() {
+    let mut tree = tree();
+    let removed: Vec<(Method, String, u32)> = tree.remove_prefix("/api/users");
+    assert_eq!(removed.len(), 3);
+    assert!(removed.contains(&(Method::POST, "/api/users".to_string(), 2)));
+
+    assert!(tree.search(Method::GET, "/api/users").is_none());
+    assert!(tree.search(Method::GET, "/api/users/7").is_none());
+    assert_eq!(*tree.search(Method::GET, "/api/files/a").unwrap().value(), 5);
+    assert_eq!(*tree.search(Method::GET, "/apis").unwrap().value(), 4);
+    assert!(tree.validate().is_ok());
+}
+
+#[test]
+fn remove_prefix_merges_the_remaining_nodes() {
+    let mut tree = tree();
+    tree.remove_prefix("/api/");
+    tree.remove_prefix("/adm");
+
+    let mut fresh = Node::new();
+    fresh.insert(Method::GET, "/apis", 4);
+    fresh.insert(Method::GET, "/", 7);
+    assert!(tree.routes_eq(&fresh));
+    assert_eq!(tree.memory_usage().node_count(), fresh.memory_usage().node_count());
+    assert!(tree.validate().is_ok());
+
+    assert!(tree.remove_prefix("/missing").is_empty());
+    assert_eq!(tree.remove_prefix("").len(), 2);
+    assert!(tree.is_empty());
+}
+
+#[test]
+fn split_off_moves_the_subtree() {
+    let mut tree = tree();
+    let api = tree.split_off("/api/");
+
+    assert_eq!(patterns(api.iter()), ["GET /api/files/*", "GET /api/users", "POST /api/users", "GET /api/users/$id"]);
+    assert_eq!(patterns(tree.iter()), ["GET /", "GET /admin", "GET /apis"]);
+    let result = api.search(Method::GET, "/api/users/7").unwrap();
+    assert_eq!((*result.value(), result.params().get("id")), (3, Some("7")));
+    assert!(tree.search(Method::GET, "/api/users/7").is_none());
+    assert!(tree.validate().is_ok() && api.validate().is_ok());
+}
+
+#[test]
+fn split_off_trees_are_independent() {
+    let mut tree = tree();
+    let mut admin = tree.split_off("/admin");
+    admin.insert(Method::POST, "/admin", 8);
+    tree.insert(Method::PUT, "/admin", 9);
+
+    assert_eq!(admin.len(), 2);
+    assert!(admin.search(Method::PUT, "/admin").is_none());
+    assert!(tree.search(Method::POST, "/admin").is_none());
+    assert!(tree.split_off("/missing").is_empty());
+}
+
+#[test]
+fn router_split_off_moves_the_names() {
+    let mut router = Router::new();
+    router.insert_named(Method::GET, "user", "/api/users/$id", 1).unwrap();
+    router.insert_named(Method::GET, "home", "/", 2).unwrap();
+
+    let api = router.split_off("/api");
+    assert_eq!(api.path_for("user", &[("id", "7")]).unwrap(), "/api/users/7");
+    assert!(api.path_for("home", &[]).is_err());
+    assert!(router.path_for("user", &[("id", "7")]).is_err());
+    assert_eq!(router.path_for("home", &[]).unwrap(), "/");
+}
@@ -0,0 +1,55 @@
+use radixtree::{Method, Query, Router};
+
+fn router() -> Router<&'static str> {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users/$id", "USER").unwrap();
+    router
+}
+
+fn pairs(query: &Query) -> Vec<(&str, &str)> {
+    query.iter().collect()
+}
+
+#[test]
+fn search_uri_splits_the_query() {
+    let router = router();
+    let (result, query) = router.search_uri(Method::GET, "/users/42?active=true&tag=a&tag=b").unwrap();
+    assert_eq!(result.params().get("id"), Some("42"));
+    assert_eq!(pairs(&query), [("active", "true"), ("tag", "a"), ("tag", "b")]);
+    assert_eq!(query.get("tag"), Some("a"));
+    assert_eq!(query.get_all("tag").collect::<Vec<_>>(), ["a", "b"]);
+    assert_eq!(query.get("missing"), None);
+}
+
+#[test]
+fn search_uri_drops_the_fragment() {
+    let router = router();
+    let (result, query) = router.search_uri(Method::GET, "/users/42#profile").unwrap();
+    assert_eq!(result.params().get("id"), Some("42"));
+    assert!(query.is_empty());
+
+    // A `?` in the fragment doesn't start a query.
+    let (result, query) = router.search_uri(Method::GET, "/users/42#top?a=1").unwrap();
+    assert_eq!(result.params().get("id"), Some("42"));
+    assert!(query.is_empty());
+
+    let (_, query) = router.search_uri(Method::GET, "/users/42?a=1#b=2").unwrap();
+    assert_eq!(pairs(&query), [("a", "1")]);
+}
+
+#[test]
+fn search_uri_matches_the_path_only() {
+    let router = router();
+    assert!(router.search_uri(Method::GET, "/users?id=42").is_none());
+    assert!(router.search_uri(Method::POST, "/users/42?a=1").is_none());
+
+    let (_, query) = router.search_uri(Method::GET, "/users/42?").unwrap();
+    assert!(query.is_empty());
+}
+
+#[test]
+fn query_pairs_are_decoded() {
+    let query = Query::parse("name=J%C3%B6rg+M&flag&=empty&bad=%zz&&x=%41");
+    assert_eq!(pairs(&query), [("name", "Jörg M"), ("flag", ""), ("", "empty"), ("bad", "%zz"), ("x", "A")]);
+    assert_eq!(query.len(), 5);
+}
@@ -0,0 +1,70 @@
+use radixtree::{Method, Node, Router};
+
+type Route = (Method, String, u32);
+
+fn tree(patterns: &[&str]) -> Node<u32> {
+    let mut tree = Node::new();
+    for (i, pattern) in patterns.iter().enumerate() {
+        tree.insert(Method::GET, pattern, i as u32);
+    }
+    tree
+}
+
+fn routes<'a>(routes: impl Iterator<Item = (&'a Method, String, &'a u32)>) -> Vec<Route> {
+    routes.map(|(method, pattern, value)| (method.clone(), pattern, *value)).collect()
+}
+
+fn patterns<'a>(routes: impl Iterator<Item = (&'a Method, String, &'a u32)>) -> Vec<String> {
+    routes.map(|(_, pattern, _)| pattern).collect()
+}
+
+const PATTERNS: [&str; 7] = ["/users/$id", "/b", "/api/v1", "/a", "/users", "/static/*", "/ab"];
+
+#[test]
+fn iter_is_in_the_order_of_the_patterns() {
+    let expected = ["/a", "/ab", "/api/v1", "/b", "/static/*", "/users", "/users/$id"];
+    assert_eq!(patterns(tree(&PATTERNS).iter()), expected);
+
+    let mut reversed = PATTERNS;
+    reversed.reverse();
+    assert_eq!(patterns(tree(&reversed).iter()), expected);
+}
+
+#[test]
+fn range_takes_any_bounds() {
+    let tree = tree(&PATTERNS);
+    assert_eq!(patterns(tree.range("/a".."/b")), ["/a", "/ab", "/api/v1"]);
+    assert_eq!(patterns(tree.range("/a"..="/b")), ["/a", "/ab", "/api/v1", "/b"]);
+    assert_eq!(patterns(tree.range("/s"..)), ["/static/*", "/users", "/users/$id"]);
+    assert_eq!(patterns(tree.range(.."/ab")), ["/a"]);
+    assert_eq!(patterns(tree.range(..)), patterns(tree.iter()));
+    assert!(tree.range("/c".."/d").next().is_none());
+}
+
+#[test]
+fn range_orders_the_methods_of_a_pattern() {
+    let mut tree = Node::new();
+    tree.insert(Method::extension("PURGE").unwrap(), "/a", 1);
+    tree.insert(Method::DELETE, "/a", 2);
+    tree.insert(Method::extension("BAN").unwrap(), "/a", 3);
+    tree.insert(Method::GET, "/a", 4);
+    tree.insert(Method::GET, "/", 5);
+
+    assert_eq!(routes(tree.range("/a"..)), [
+        (Method::GET, "/a".to_string(), 4),
+        (Method::DELETE, "/a".to_string(), 2),
+        (Method::extension("BAN").unwrap(), "/a".to_string(), 3),
+        (Method::extension("PURGE").unwrap(), "/a".to_string(), 1),
+    ]);
+}
+
+#[test]
+fn routers_range_over_their_routes_without_aliases() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/a", 1).unwrap();
+    router.insert(Method::GET, "/c", 2).unwrap();
+    router.insert_alias(&["/b", "/b/index"], Method::GET, 3).unwrap();
+
+    assert_eq!(patterns(router.range("/a".."/c")), ["/a", "/b"]);
+    assert_eq!(patterns(router.iter()), ["/a", "/b", "/c"]);
+}
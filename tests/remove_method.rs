@@ -0,0 +1,53 @@
+use radixtree::{Method, Node, Router};
+
+#[test]
+fn remove_method_keeps_the_other_methods() {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/users/$id", 1);
+    tree.insert(Method::DELETE, "/users/$id", 2);
+
+    assert_eq!(tree.remove_method(Method::DELETE, "/users/$id"), Some(2));
+    assert_eq!(tree.remove_method(Method::DELETE, "/users/$id"), None);
+    assert!(tree.search(Method::DELETE, "/users/1").is_none());
+    assert_eq!(tree.search(Method::GET, "/users/1").unwrap().value(), &1);
+}
+
+#[test]
+fn remove_method_only_removes_registered_methods() {
+    let mut tree = Node::new();
+    tree.insert(Method::ANY, "/health", 1);
+
+    // A search of GET falls back to ANY, but GET isn't registered.
+    assert_eq!(tree.remove_method(Method::GET, "/health"), None);
+    assert_eq!(tree.remove_method(Method::GET, "/missing"), None);
+    assert_eq!(tree.remove_method(Method::ANY, "/health"), Some(1));
+    assert!(tree.is_empty());
+}
+
+#[test]
+fn removing_the_last_method_prunes_and_merges_the_nodes() {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/users", 1);
+    let single = tree.to_string();
+
+    tree.insert(Method::GET, "/user", 2);
+    tree.insert(Method::POST, "/user", 3);
+    tree.insert(Method::GET, "/users/$id/posts", 4);
+    assert_eq!(tree.remove_method(Method::GET, "/user"), Some(2));
+    assert_eq!(tree.remove_method(Method::POST, "/user"), Some(3));
+    assert_eq!(tree.remove_method(Method::GET, "/users/$id/posts"), Some(4));
+
+    assert_eq!(tree.to_string(), single);
+    assert_eq!(tree.validate(), Ok(()));
+}
+
+#[test]
+fn routers_remove_a_method_in_their_syntax() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users", 1).unwrap();
+    router.insert(Method::POST, "/users", 2).unwrap();
+
+    assert_eq!(router.remove_method(Method::POST, "users"), Some(2));
+    assert_eq!(router.search(Method::GET, "/users").unwrap().value(), &1);
+    assert_eq!(router.len(), 1);
+}
@@ -0,0 +1,53 @@
+use radixtree::{Method, RequestLineError, Router};
+
+type Found = Option<(&'static str, Vec<(String, String)>)>;
+
+fn router() -> Router<&'static str> {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/", "INDEX").unwrap();
+    router.insert(Method::GET, "/users/$id", "USER").unwrap();
+    router.insert("PURGE".parse().unwrap(), "/cache", "PURGE").unwrap();
+    router
+}
+
+fn search(line: &str) -> Result<Found, RequestLineError> {
+    let found = router().search_request_line(line)?;
+    Ok(found.map(|(result, query)| (*result.value(), query.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect())))
+}
+
+#[test]
+fn origin_form_targets() {
+    assert_eq!(search("GET /users/42 HTTP/1.1"), Ok(Some(("USER", Vec::new()))));
+    assert_eq!(search("GET /users/42?a=1 HTTP/1.0\r\n"), Ok(Some(("USER", vec![("a".to_string(), "1".to_string())]))));
+    assert_eq!(search("GET / HTTP/2.0\n").map(|found| found.map(|(value, _)| value)), Ok(Some("INDEX")));
+    assert_eq!(search("PURGE /cache HTTP/1.1").map(|found| found.map(|(value, _)| value)), Ok(Some("PURGE")));
+    assert_eq!(search("POST /users/42 HTTP/1.1"), Ok(None));
+}
+
+#[test]
+fn absolute_form_targets() {
+    assert_eq!(search("GET http://example.com/users/42 HTTP/1.1").map(|found| found.map(|(value, _)| value)), Ok(Some("USER")));
+    assert_eq!(search("GET HTTPS://example.com:8443/users/42?a=1 HTTP/1.1"), Ok(Some(("USER", vec![("a".to_string(), "1".to_string())]))));
+    // The empty path of an absolute target is `/`.
+    assert_eq!(search("GET http://example.com HTTP/1.1").map(|found| found.map(|(value, _)| value)), Ok(Some("INDEX")));
+    assert_eq!(search("GET http://example.com?a=1 HTTP/1.1").map(|found| found.map(|(value, _)| value)), Ok(Some("INDEX")));
+}
+
+#[test]
+fn other_target_forms_are_rejected() {
+    assert_eq!(search("CONNECT example.com:443 HTTP/1.1"), Err(RequestLineError::Target("example.com:443".to_string())));
+    assert_eq!(search("OPTIONS * HTTP/1.1"), Err(RequestLineError::Target("*".to_string())));
+    assert_eq!(search("GET ftp://example.com/users/42 HTTP/1.1"), Err(RequestLineError::Target("ftp://example.com/users/42".to_string())));
+    assert_eq!(search("GET http:///users HTTP/1.1"), Err(RequestLineError::Target("http:///users".to_string())));
+    assert_eq!(search("GET users/42 HTTP/1.1"), Err(RequestLineError::Target("users/42".to_string())));
+}
+
+#[test]
+fn malformed_lines_are_rejected() {
+    assert_eq!(search("GET /users/42"), Err(RequestLineError::Malformed));
+    assert_eq!(search("GET  /users/42 HTTP/1.1"), Err(RequestLineError::Malformed));
+    assert_eq!(search("GET /users/42 HTTP/1.1 extra"), Err(RequestLineError::Malformed));
+    assert!(matches!(search("G(T /users/42 HTTP/1.1"), Err(RequestLineError::Method(_))));
+    assert_eq!(search("GET /users/42 HTTP/11"), Err(RequestLineError::Version("HTTP/11".to_string())));
+    assert_eq!(search("GET /users/42 http/1.1"), Err(RequestLineError::Version("http/1.1".to_string())));
+}
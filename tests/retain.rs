@@ -0,0 +1,88 @@
+use std::sync::{Arc, Mutex};
+
+use radixtree::{Method, Node, PathForError, Router};
+
+fn tree() -> Node<u32> {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/users", 1);
+    tree.insert(Method::POST, "/users", 2);
+    tree.insert(Method::GET, "/users/$id", 3);
+    tree.insert(Method::GET, "/uploads/*", 4);
+    tree.insert(Method::GET, "/admin/settings", 5);
+    tree
+}
+
+#[test]
+fn clear_removes_every_route() {
+    let mut tree = tree();
+    tree.clear();
+    assert!(tree.is_empty());
+    assert!(tree.search(Method::GET, "/users").is_none());
+    assert_eq!(tree.memory_usage().node_count(), 1);
+
+    // The cleared tree takes new routes.
+    tree.insert(Method::GET, "/users", 6);
+    assert_eq!(*tree.search(Method::GET, "/users").unwrap().value(), 6);
+}
+
+#[test]
+fn retain_keeps_the_accepted_routes() {
+    let mut tree = tree();
+    let mut seen = Vec::new();
+    tree.retain(|method, pattern, value| {
+        seen.push((method.clone(), pattern.to_string()));
+        *method == Method::GET && !pattern.starts_with("/admin") && *value != 4
+    });
+
+    assert_eq!(seen.len(), 5);
+    let routes: Vec<(Method, String, u32)> = tree.iter().map(|(method, pattern, value)| (method.clone(), pattern, *value)).collect();
+    assert_eq!(routes, [(Method::GET, "/users".to_string(), 1), (Method::GET, "/users/$id".to_string(), 3)]);
+    assert!(tree.search(Method::POST, "/users").is_none());
+    assert!(tree.search(Method::GET, "/uploads/a").is_none());
+}
+
+#[test]
+fn retain_compacts_the_tree() {
+    let mut tree = tree();
+    tree.retain(|_, pattern, _| pattern.starts_with("/users"));
+
+    let mut fresh = Node::new();
+    fresh.insert(Method::GET, "/users", 1);
+    fresh.insert(Method::POST, "/users", 2);
+    fresh.insert(Method::GET, "/users/$id", 3);
+    assert!(tree.routes_eq(&fresh));
+    assert_eq!(tree.memory_usage().node_count(), fresh.memory_usage().node_count());
+    assert_eq!(tree.memory_usage().path_bytes(), fresh.memory_usage().path_bytes());
+    assert!(tree.validate().is_ok());
+}
+
+#[test]
+fn router_retain_drops_the_names_of_removed_patterns() {
+    let mut router = Router::new();
+    router.insert_named(Method::GET, "user", "/users/$id", 1).unwrap();
+    router.insert_named(Method::GET, "admin", "/admin", 2).unwrap();
+    router.retain(|_, pattern, _| pattern != "/admin");
+
+    assert_eq!(router.path_for("user", &[("id", "7")]).unwrap(), "/users/7");
+    assert_eq!(router.path_for("admin", &[]), Err(PathForError::UnknownRoute("admin".to_string())));
+
+    router.clear();
+    assert!(router.is_empty());
+    assert_eq!(router.path_for("user", &[("id", "7")]), Err(PathForError::UnknownRoute("user".to_string())));
+}
+
+#[test]
+fn router_clear_and_retain_report_the_removed_routes() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut router = Router::new();
+    let sink = events.clone();
+    router.on_change(move |event, method, pattern| sink.lock().unwrap().push((event, method.clone(), pattern.to_string())));
+    router.insert(Method::GET, "/a", 1).unwrap();
+    router.insert(Method::GET, "/b", 2).unwrap();
+    events.lock().unwrap().clear();
+
+    router.retain(|_, pattern, _| pattern == "/a");
+    router.clear();
+    let removed: Vec<String> = events.lock().unwrap().iter().map(|(event, method, pattern)| format!("{:?} {} {}", event, method, pattern)).collect();
+    assert_eq!(removed, ["Remove GET /b", "Remove GET /a"]);
+}
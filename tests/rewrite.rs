@@ -0,0 +1,49 @@
+use radixtree::{ConflictKind, Method, RedirectKind, Router, Target};
+
+#[test]
+fn invalid_rewrites_are_returned() {
+    let mut router = Router::<()>::new();
+    let invalid = [("/old/$", "/new"), ("/old/$id", "/new/$"), ("/old/$id", "/new/$name"), ("/old/*", "/new/*")];
+    for (from, to) in invalid {
+        let error = router.insert_rewrite(from, to).unwrap_err();
+        assert!(matches!(error, ConflictKind::InvalidPattern(_)), "{} -> {}", from, to);
+    }
+    assert!(router.remove_rewrite("/old/$id").is_none());
+}
+
+#[test]
+fn rewrites_keep_the_values_of_the_path() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/new/$id", "NEW").unwrap();
+    router.insert_rewrite("/old/$id", "/new/$id").unwrap();
+
+    let result = router.search(Method::GET, "/old/a%20b").unwrap();
+    assert_eq!(result.params().get("id"), Some("a%20b"));
+}
+
+#[test]
+fn invalid_redirects_are_returned() {
+    let mut router = Router::<()>::new();
+    let invalid = [("/old/$", "/new"), ("/old/$id", "/new/$name"), ("/old/*", "/new/*")];
+    for (from, to) in invalid {
+        let error = router.insert_redirect(from, to, RedirectKind::Temporary).unwrap_err();
+        assert!(matches!(error, ConflictKind::InvalidPattern(_)), "{} -> {}", from, to);
+    }
+    assert!(router.remove_redirect("/old/$id").is_none());
+}
+
+#[test]
+fn redirect_locations_are_percent_encoded() {
+    let mut router = Router::<()>::new();
+    router.insert_redirect("/users/$id", "/people/$id", RedirectKind::Permanent).unwrap();
+    router.insert_redirect("/files/$path+", "/documents/$path+", RedirectKind::Temporary).unwrap();
+
+    let location = |path: &str| match router.resolve(Method::GET, path) {
+        Some(Target::Redirect(redirect)) => redirect.location,
+        _ => panic!("{} isn't redirected", path),
+    };
+    assert_eq!(location("/users/a b\"c"), "/people/a%20b%22c");
+    assert_eq!(location("/users/a%20b"), "/people/a%20b");
+    assert_eq!(location("/users/100%"), "/people/100%25");
+    assert_eq!(location("/files/a/b c"), "/documents/a/b%20c");
+}
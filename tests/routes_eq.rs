@@ -0,0 +1,51 @@
+use radixtree::{Method, Node};
+
+fn tree(routes: &[(Method, &str, u32)]) -> Node<u32> {
+    let mut tree = Node::new();
+    for (method, pattern, value) in routes {
+        tree.insert(method.clone(), pattern, *value);
+    }
+    tree
+}
+
+#[test]
+fn routes_eq_ignores_how_nodes_were_split() {
+    let routes = [
+        (Method::GET, "/user", 1),
+        (Method::GET, "/users", 2),
+        (Method::GET, "/users/$id", 3),
+        (Method::POST, "/u", 4),
+        (Method::GET, "/static/*", 5),
+    ];
+    let forward = tree(&routes);
+    let mut reversed = routes.clone();
+    reversed.reverse();
+    let backward = tree(&reversed);
+
+    assert!(forward.routes_eq(&backward));
+    assert!(backward.routes_eq(&forward));
+}
+
+#[test]
+fn routes_eq_compares_values() {
+    let a = tree(&[(Method::GET, "/a", 1)]);
+    let b = tree(&[(Method::GET, "/a", 2)]);
+    assert!(!a.routes_eq(&b));
+}
+
+#[test]
+fn routes_eq_compares_methods_and_patterns() {
+    let a = tree(&[(Method::GET, "/a", 1)]);
+    assert!(!a.routes_eq(&tree(&[(Method::POST, "/a", 1)])));
+    assert!(!a.routes_eq(&tree(&[(Method::GET, "/b", 1)])));
+    assert!(!a.routes_eq(&tree(&[(Method::GET, "/a", 1), (Method::GET, "/b", 2)])));
+    assert!(!a.routes_eq(&Node::new()));
+}
+
+#[test]
+fn routes_eq_after_removing_routes() {
+    let mut a = tree(&[(Method::GET, "/a", 1), (Method::GET, "/ab", 2)]);
+    a.remove("/ab");
+    assert!(a.routes_eq(&tree(&[(Method::GET, "/a", 1)])));
+    assert!(Node::<u32>::new().routes_eq(&Node::new()));
+}
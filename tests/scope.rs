@@ -0,0 +1,77 @@
+use radixtree::{ConflictKind, ConflictPolicy, Method, Router};
+
+#[derive(Clone, Debug, PartialEq)]
+struct Role(&'static str);
+
+#[derive(Clone, Debug, PartialEq)]
+struct Timeout(u32);
+
+#[test]
+fn scopes_insert_routes_under_their_prefix() {
+    let mut router = Router::new();
+    let mut admin = router.scope("/admin/");
+    admin.insert(Method::GET, "/users", 1).unwrap();
+    admin.insert(Method::GET, "users/$id", 2).unwrap();
+    admin.insert(Method::GET, "/", 3).unwrap();
+
+    let patterns: Vec<String> = router.iter().map(|(_, pattern, _)| pattern).collect();
+    assert_eq!(patterns, ["/admin", "/admin/users", "/admin/users/$id"]);
+    assert_eq!(router.search(Method::GET, "/admin/users/7").unwrap().value(), &2);
+}
+
+#[test]
+fn routes_take_the_methods_of_the_scope() {
+    let mut router = Router::new();
+    router.scope("/any").route("/ping", "ANY").unwrap();
+    router.scope("/api").methods(Method::GET | Method::POST).route("/users", "USERS").unwrap();
+
+    assert_eq!(router.search(Method::DELETE, "/any/ping").unwrap().value(), &"ANY");
+    assert_eq!(router.search(Method::POST, "/api/users").unwrap().value(), &"USERS");
+    assert!(router.search(Method::DELETE, "/api/users").is_none());
+}
+
+#[test]
+fn scopes_add_their_extensions_to_each_route() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/public", "PUBLIC").unwrap();
+    let mut admin = router.scope("/admin").extension(Role("admin")).extension(Timeout(30));
+    admin.insert(Method::GET, "/users", "USERS").unwrap();
+    admin.insert(Method::DELETE, "/users/$id", "DELETE").unwrap();
+
+    for (method, path) in [(Method::GET, "/admin/users"), (Method::DELETE, "/admin/users/1")] {
+        let result = router.search(method, path).unwrap();
+        let extensions = result.extensions().unwrap();
+        assert_eq!((extensions.get::<Role>(), extensions.get::<Timeout>()), (Some(&Role("admin")), Some(&Timeout(30))));
+    }
+    assert!(router.search(Method::GET, "/public").unwrap().extensions().is_none());
+}
+
+#[test]
+fn nested_scopes_inherit_the_prefix_extensions_and_methods() {
+    let mut router = Router::new();
+    let mut api = router.scope("/api").extension(Role("user")).methods(Method::GET | Method::PUT);
+    let mut v1 = api.scope("/v1").extension(Timeout(5));
+    v1.route("/items", "ITEMS").unwrap();
+    let mut admin = api.scope("admin/").extension(Role("admin"));
+    admin.route("/", "ADMIN").unwrap();
+
+    let result = router.search(Method::PUT, "/api/v1/items").unwrap();
+    let extensions = result.extensions().unwrap();
+    assert_eq!((extensions.get::<Role>(), extensions.get::<Timeout>()), (Some(&Role("user")), Some(&Timeout(5))));
+
+    // The extensions of a nested scope replace the inherited ones of the same type.
+    let result = router.search(Method::GET, "/api/admin").unwrap();
+    assert_eq!(result.extensions().unwrap().get::<Role>(), Some(&Role("admin")));
+    assert!(router.search(Method::POST, "/api/admin").is_none());
+}
+
+#[test]
+fn scopes_resolve_conflicts_with_the_policy_of_the_router() {
+    let mut router = Router::new();
+    router.set_conflict_policy(ConflictPolicy::Error);
+    router.insert(Method::GET, "/admin/users", "USERS").unwrap();
+
+    let mut admin = router.scope("/admin").extension(Role("admin"));
+    assert_eq!(admin.insert(Method::GET, "/users", "OTHER"), Err(ConflictKind::Duplicate));
+    assert!(router.search(Method::GET, "/admin/users").unwrap().extensions().is_none());
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "tower")]
+
+use std::convert::Infallible;
+use std::future::{ready, Future, Ready};
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+use http::{Request, Response, StatusCode};
+use radixtree::{Method, Params, Router, RouterService};
+use tower_service::Service;
+
+/// Answers with its name and the path parameters of the request.
+#[derive(Clone)]
+struct Handler(&'static str);
+
+impl Service<Request<()>> for Handler {
+    type Response = Response<String>;
+    type Error = Infallible;
+    type Future = Ready<Result<Response<String>, Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<()>) -> Self::Future {
+        let params: Vec<String> = req.extensions().get::<Params>().unwrap().iter()
+            .map(|param| format!("{}={}", param.name(), param.value()))
+            .collect();
+        ready(Ok(Response::new(format!("{} {}", self.0, params.join("&")).trim_end().to_string())))
+    }
+}
+
+/// Polls a future which is ready without waiting.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("the future isn't ready"),
+    }
+}
+
+fn call(service: &mut RouterService<Handler>, method: http::Method, uri: &str) -> Response<String> {
+    let req = Request::builder().method(method).uri(uri).body(()).unwrap();
+    block_on(service.call(req)).unwrap()
+}
+
+fn service() -> RouterService<Handler> {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users/$id", Handler("user")).unwrap();
+    router.insert(Method::DELETE, "/users/$id", Handler("delete")).unwrap();
+    router.insert(Method::GET, "/", Handler("home")).unwrap();
+    RouterService::new(router)
+}
+
+#[test]
+fn requests_are_dispatched_to_the_matched_service() {
+    let mut service = service();
+    let response = call(&mut service, http::Method::GET, "/users/42?full=true");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.body(), "user id=42");
+
+    assert_eq!(call(&mut service, http::Method::DELETE, "/users/7").body(), "delete id=7");
+    assert_eq!(call(&mut service, http::Method::GET, "http://example.com/").body(), "home");
+}
+
+#[test]
+fn unmatched_paths_are_not_found() {
+    let mut service = service();
+    let response = call(&mut service, http::Method::GET, "/posts");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.body().is_empty());
+}
+
+#[test]
+fn services_are_built_from_trees() {
+    let mut tree = radixtree::Node::new();
+    tree.insert(Method::GET, "/", Handler("tree"));
+    let mut service = RouterService::from(tree);
+    assert_eq!(call(&mut service.clone(), http::Method::GET, "/").body(), "tree");
+    assert_eq!(call(&mut service, http::Method::GET, "/x").status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn other_methods_are_not_allowed() {
+    let mut service = service();
+    let response = call(&mut service, http::Method::POST, "/users/42");
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(response.headers()[http::header::ALLOW], "GET, DELETE");
+    assert!(response.body().is_empty());
+
+    let response = call(&mut service, http::Method::GET, "/posts");
+    assert!(!response.headers().contains_key(http::header::ALLOW));
+}
+
+#[test]
+fn head_requests_are_allowed_when_they_fall_back_to_get() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users", Handler("users")).unwrap();
+    router.set_head_can_use_get(true);
+    let mut service = RouterService::new(router);
+
+    assert_eq!(call(&mut service, http::Method::HEAD, "/users").body(), "users");
+    let response = call(&mut service, http::Method::PUT, "/users");
+    assert_eq!(response.headers()[http::header::ALLOW], "GET, HEAD");
+}
@@ -0,0 +1,70 @@
+use radixtree::{DotSegments, Method, Router, ShadowedRoute, Shadowing};
+
+fn shadowed(router: &Router<u32>) -> Vec<(Method, String, Shadowing)> {
+    router.shadowed_routes().into_iter()
+        .map(|ShadowedRoute { method, pattern, cause }| (method, pattern, cause))
+        .collect()
+}
+
+#[test]
+fn routes_taking_precedence_are_not_shadowed() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/*", 1).unwrap();
+    router.insert(Method::GET, "/$name", 2).unwrap();
+    router.insert(Method::GET, "/about", 3).unwrap();
+    router.insert(Method::GET, "/users/$id/posts", 4).unwrap();
+    assert!(router.shadowed_routes().is_empty());
+}
+
+#[test]
+fn a_route_matching_every_path_of_another_shadows_it() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/*/posts", 1).unwrap();
+    router.insert(Method::GET, "/$user/*", 2).unwrap();
+    router.insert(Method::POST, "/*/comments", 3).unwrap();
+
+    assert_eq!(shadowed(&router), [(Method::GET, "/*/posts".to_string(), Shadowing::Route("/$user/*".to_string()))]);
+}
+
+#[test]
+fn any_routes_shadow_the_routes_of_every_method() {
+    let mut router = Router::new();
+    router.insert(Method::ANY, "/$user/*", 1).unwrap();
+    router.insert(Method::DELETE, "/*/posts", 2).unwrap();
+
+    assert_eq!(shadowed(&router), [(Method::DELETE, "/*/posts".to_string(), Shadowing::Route("/$user/*".to_string()))]);
+}
+
+#[test]
+fn constrained_routes_shadow_nothing() {
+    let mut router = Router::new();
+    router.register_constraint("num", |value: &str| value.bytes().all(|b| b.is_ascii_digit()));
+    router.insert(Method::GET, "/$user:num/*", 1).unwrap();
+    router.insert(Method::GET, "/*/posts", 2).unwrap();
+    assert!(router.shadowed_routes().is_empty());
+}
+
+#[test]
+fn normalized_paths_shadow_routes() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/a//b", 1).unwrap();
+    router.insert(Method::GET, "/c/../d", 2).unwrap();
+    assert!(router.shadowed_routes().is_empty());
+
+    router.set_merge_slashes(true);
+    router.set_dot_segments(DotSegments::Normalize);
+    assert_eq!(shadowed(&router), [
+        (Method::GET, "/a//b".to_string(), Shadowing::MergedSlashes),
+        (Method::GET, "/c/../d".to_string(), Shadowing::DotSegments),
+    ]);
+}
+
+#[test]
+fn rewritten_paths_shadow_routes() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/old/$id", 1).unwrap();
+    router.insert(Method::GET, "/new/$id", 2).unwrap();
+    router.insert_rewrite("/old/$id", "/new/$id").unwrap();
+
+    assert_eq!(shadowed(&router), [(Method::GET, "/old/$id".to_string(), Shadowing::Rewritten("/old/$id".to_string()))]);
+}
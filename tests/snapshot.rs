@@ -0,0 +1,75 @@
+use radixtree::{Method, Node, SnapshotError};
+
+fn encode(value: &String, out: &mut Vec<u8>) {
+    out.extend_from_slice(value.as_bytes());
+}
+
+fn decode(bytes: &[u8]) -> Option<String> {
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
+fn tree() -> Node<String> {
+    let mut tree = Node::new();
+    for (method, pattern) in [
+        (Method::GET, "/"),
+        (Method::GET, "/users/$id"),
+        (Method::POST, "/users/$id"),
+        (Method::GET, "/users/$id/posts/$post"),
+        (Method::GET, "/files/$name.json"),
+        (Method::GET, "/docs/$path+"),
+        (Method::GET, "/*/edit"),
+        (Method::ANY, "/日本語"),
+        ("PURGE".parse::<Method>().unwrap(), "/cache/*"),
+    ] {
+        let value = format!("{} {}", method, pattern);
+        tree.insert(method, pattern, value);
+    }
+    tree
+}
+
+#[test]
+fn snapshots_round_trip() {
+    let tree = tree();
+    let bytes = tree.to_bytes(encode);
+    let copy = Node::from_bytes(&bytes, decode).unwrap();
+    assert_eq!(copy, tree);
+    assert_eq!(copy.to_bytes(encode), bytes);
+
+    let result = copy.search(Method::GET, "/users/1/posts/2").unwrap();
+    assert_eq!(result.value(), "GET /users/$id/posts/$post");
+    assert_eq!((&result.params()["id"], &result.params()["post"]), ("1", "2"));
+    assert_eq!(copy.search("PURGE".parse::<Method>().unwrap(), "/cache/a/b").unwrap().value(), "PURGE /cache/*");
+}
+
+#[test]
+fn snapshots_of_changed_trees_round_trip() {
+    let mut tree = tree();
+    tree.remove("/users/$id/posts/$post");
+    tree.remove_method(Method::POST, "/users/$id");
+    tree.insert(Method::GET, "/users/new", "GET /users/new".to_string());
+
+    let copy = Node::from_bytes(&tree.to_bytes(encode), decode).unwrap();
+    assert_eq!(copy, tree);
+    assert!(copy.validate().is_ok());
+    assert!(copy.search(Method::GET, "/users/1/posts/2").is_none());
+    assert_eq!(copy.search(Method::GET, "/users/new").unwrap().value(), "GET /users/new");
+
+    let mut copy = copy;
+    copy.insert(Method::DELETE, "/users/$id", "DELETE /users/$id".to_string());
+    assert_eq!(copy.len(), tree.len() + 1);
+}
+
+#[test]
+fn empty_trees_round_trip() {
+    let tree = Node::<String>::new();
+    let copy = Node::from_bytes(&tree.to_bytes(encode), decode).unwrap();
+    assert!(copy.is_empty());
+}
+
+#[test]
+fn invalid_snapshots_are_errors() {
+    let bytes = tree().to_bytes(encode);
+    assert_eq!(Node::from_bytes(&bytes[..bytes.len() - 1], decode), Err(SnapshotError::InvalidFormat));
+    assert_eq!(Node::from_bytes(b"radix", decode), Err(SnapshotError::InvalidFormat));
+    assert_eq!(Node::<String>::from_bytes(&bytes, |_| None), Err(SnapshotError::InvalidValue));
+}
@@ -0,0 +1,50 @@
+use radixtree::{Method, Node, Router};
+
+fn tree() -> Node<u32> {
+    let mut tree = Node::new();
+    for (i, pattern) in ["/users", "/users/$id", "/users/$id/posts", "/static/*", "/api/v1/items"].iter().enumerate() {
+        tree.insert(Method::GET, pattern, i as u32);
+    }
+    tree
+}
+
+#[test]
+fn suggest_returns_the_closest_patterns_first() {
+    let tree = tree();
+    assert_eq!(tree.suggest("/users/1/posts", 2), ["/users/$id/posts", "/static/*", "/users/$id", "/users"]);
+    assert_eq!(tree.suggest("/users/1/posts/2", 1), ["/static/*", "/users/$id/posts"]);
+    assert_eq!(tree.suggest("/users/1/post", 0), Vec::<String>::new());
+}
+
+#[test]
+fn suggest_sorts_patterns_of_the_same_distance() {
+    let tree = tree();
+    assert_eq!(tree.suggest("/api/v2/items", 1), ["/api/v1/items", "/static/*"]);
+    assert_eq!(tree.suggest("/api/v2/items", 0), Vec::<String>::new());
+}
+
+#[test]
+fn suggest_matches_parameters_and_wildcards() {
+    let tree = tree();
+    // A parameter matches any segment, and a wildcard any number of them.
+    assert_eq!(tree.suggest("/users/42", 0), ["/users/$id"]);
+    assert_eq!(tree.suggest("/static/css/site.css", 0), ["/static/*"]);
+    assert_eq!(tree.suggest("/static", 0), ["/static/*"]);
+}
+
+#[test]
+fn suggest_counts_segments() {
+    let tree = tree();
+    // A misspelled segment is one replacement, however many letters differ.
+    assert_eq!(tree.suggest("/usrs", 1), ["/static/*", "/users", "/users/$id"]);
+    assert_eq!(tree.suggest("/xyz/1/posts", 1), ["/static/*", "/users/$id/posts"]);
+}
+
+#[test]
+fn routers_suggest_patterns() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users", 1).unwrap();
+    router.insert(Method::GET, "/orders", 2).unwrap();
+    assert_eq!(router.suggest("/users/1", 1), ["/users"]);
+    assert!(Node::<u32>::new().suggest("/users", 3).is_empty());
+}
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+use std::thread;
+
+use radixtree::{Method, Router, SyncRouter};
+
+#[test]
+fn changes_are_searched() {
+    let router = SyncRouter::new();
+    router.insert(Method::GET, "/users/$id", 1).unwrap();
+    router.insert_methods(Method::PUT | Method::PATCH, "/users/$id", 2).unwrap();
+    assert_eq!(*router.search(Method::GET, "/users/7").unwrap().value(), 1);
+    assert_eq!(router.allowed_methods("/users/7").len(), 3);
+
+    assert_eq!(router.update(Method::GET, "/users/$id", 3), Ok(1));
+    assert_eq!(router.remove_method(Method::PUT, "/users/$id"), Some(2));
+    assert_eq!(*router.search(Method::GET, "/users/7").unwrap().value(), 3);
+    assert_eq!(router.remove("/users/$id").unwrap().len(), 2);
+    assert!(router.search(Method::GET, "/users/7").is_none());
+}
+
+#[test]
+fn snapshots_keep_their_routes() {
+    let router = SyncRouter::from(Router::new());
+    router.insert(Method::GET, "/a", 1).unwrap();
+    let snapshot = router.snapshot();
+    router.write(|router| {
+        router.remove("/a");
+        router.insert(Method::GET, "/b", 2).unwrap();
+    });
+
+    assert!(snapshot.search(Method::GET, "/a").is_some());
+    assert!(router.search(Method::GET, "/a").is_none());
+    assert!(router.search(Method::GET, "/b").is_some());
+}
+
+#[test]
+fn concurrent_snapshots_see_the_inserts_in_order() {
+    let router = Arc::new(SyncRouter::new());
+    let writer = {
+        let router = router.clone();
+        thread::spawn(move || {
+            for i in 0..100 {
+                router.insert(Method::GET, &format!("/items/{}", i), i).unwrap();
+            }
+        })
+    };
+
+    let readers: Vec<_> = (0..4).map(|_| {
+        let router = router.clone();
+        thread::spawn(move || {
+            let mut last = 0;
+            while last < 100 {
+                // Each snapshot has the items inserted before it, in order.
+                let snapshot = router.snapshot();
+                let len = snapshot.len();
+                assert!(len >= last);
+                for i in 0..100 {
+                    assert_eq!(snapshot.search(Method::GET, &format!("/items/{}", i)).is_some(), i < len);
+                }
+                last = len;
+            }
+        })
+    }).collect();
+
+    writer.join().unwrap();
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert_eq!(router.snapshot().len(), 100);
+}
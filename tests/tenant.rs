@@ -0,0 +1,59 @@
+use radixtree::{ConflictKind, Method, TenantRouter};
+
+fn value(router: &TenantRouter<&'static str>, tenant: &str, path: &str) -> Option<&'static str> {
+    router.search(tenant, Method::GET, path).map(|result| *result.value())
+}
+
+#[test]
+fn tenant_routes_replace_shared_routes() {
+    let mut router = TenantRouter::new();
+    router.add_tenant("globex");
+    router.insert_shared(Method::GET, "/users/$id", "SHARED").unwrap();
+    router.insert("acme", Method::GET, "/users/$id", "ACME").unwrap();
+
+    assert_eq!(value(&router, "acme", "/users/1"), Some("ACME"));
+    assert_eq!(value(&router, "globex", "/users/1"), Some("SHARED"));
+    assert!(router.is_shared("globex"));
+    assert!(!router.is_shared("acme"));
+}
+
+#[test]
+fn tenant_routes_survive_shared_changes() {
+    let mut router = TenantRouter::new();
+    router.insert("acme", Method::GET, "/users/$id", "ACME").unwrap();
+    router.insert_shared(Method::GET, "/users/$id", "SHARED").unwrap();
+    assert_eq!(value(&router, "acme", "/users/1"), Some("ACME"));
+
+    router.remove_shared("/users/$id");
+    assert_eq!(value(&router, "acme", "/users/1"), Some("ACME"));
+    assert_eq!(value(&router, "globex", "/users/1"), None);
+}
+
+#[test]
+fn conflicting_shared_routes_change_no_tenant() {
+    let mut router = TenantRouter::new();
+    router.add_tenant("globex");
+    router.insert("acme", Method::GET, "/users/$uid", "ACME").unwrap();
+    router.insert("initech", Method::GET, "/reports", "REPORTS").unwrap();
+
+    let error = router.insert_shared(Method::GET, "/users/$id", "SHARED").unwrap_err();
+    assert!(matches!(error, ConflictKind::AmbiguousParams(_)));
+    assert!(router.shared().is_empty());
+    assert_eq!(router.tenant("initech").unwrap().len(), 1);
+    assert_eq!(value(&router, "globex", "/users/1"), None);
+
+    router.insert_shared(Method::GET, "/health", "HEALTH").unwrap();
+    let error = router.insert_shared(Method::GET, "/health", "AGAIN").unwrap_err();
+    assert!(matches!(error, ConflictKind::Duplicate));
+}
+
+#[test]
+fn conflicting_tenant_routes_add_no_tenant() {
+    let mut router = TenantRouter::new();
+    router.insert_shared(Method::GET, "/files/*", "FILES").unwrap();
+
+    let error = router.insert("acme", Method::GET, "/files/$path+", "ACME").unwrap_err();
+    assert!(matches!(error, ConflictKind::Shadowing(_)));
+    assert!(router.insert("acme", Method::GET, "/$", "ACME").is_err());
+    assert_eq!(router.tenants().count(), 0);
+}
@@ -0,0 +1,124 @@
+use std::sync::{Arc, Mutex};
+
+use radixtree::{ConflictKind, Method, RouteEvent, Router, Syntax, TransactionError, UpdateError};
+
+fn router() -> Router<i32> {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/a", 1).unwrap();
+    router.insert(Method::GET, "/users/$id", 2).unwrap();
+    router
+}
+
+fn value(router: &Router<i32>, method: Method, path: &str) -> Option<i32> {
+    router.search(method, path).map(|result| *result.value())
+}
+
+#[test]
+fn committed_changes_are_applied_in_order() {
+    let mut router = router();
+    let mut transaction = router.transaction();
+    transaction
+        .insert(Method::GET, "/b", 3)
+        .update(Method::GET, "/b", 4)
+        .remove("/a")
+        .insert(Method::POST, "/users/$id", 5)
+        .remove_method(Method::GET, "/users/$id");
+    assert_eq!(transaction.len(), 5);
+    transaction.commit().unwrap();
+
+    assert_eq!(value(&router, Method::GET, "/b"), Some(4));
+    assert_eq!(value(&router, Method::GET, "/a"), None);
+    assert_eq!(value(&router, Method::GET, "/users/1"), None);
+    assert_eq!(value(&router, Method::POST, "/users/1"), Some(5));
+    assert_eq!(router.len(), 2);
+}
+
+#[test]
+fn failed_commits_leave_the_router_unchanged() {
+    let mut router = router();
+    let mut transaction = router.transaction();
+    transaction.insert(Method::GET, "/b", 3).remove("/missing");
+    assert_eq!(transaction.commit(), Err(TransactionError::NotFound(1)));
+
+    let mut transaction = router.transaction();
+    transaction.remove("/a").insert(Method::GET, "/users/$name", 3);
+    let error = transaction.commit().unwrap_err();
+    assert_eq!(error, TransactionError::Conflict(1, ConflictKind::AmbiguousParams(vec!["id".to_string()])));
+
+    let mut transaction = router.transaction();
+    transaction.remove("/a").update(Method::POST, "/users/$id", 3);
+    assert_eq!(transaction.commit(), Err(TransactionError::Update(1, UpdateError::MethodNotFound(Method::POST))));
+
+    let mut transaction = router.transaction();
+    transaction.insert(Method::GET, "/a", 3);
+    assert_eq!(transaction.commit(), Err(TransactionError::Conflict(0, ConflictKind::Duplicate)));
+
+    assert_eq!(value(&router, Method::GET, "/a"), Some(1));
+    assert_eq!(value(&router, Method::GET, "/b"), None);
+    assert_eq!(router.len(), 2);
+}
+
+#[test]
+fn rolled_back_changes_are_discarded() {
+    let mut router = router();
+    let mut transaction = router.transaction();
+    transaction.insert(Method::GET, "/b", 3).remove("/a");
+    transaction.rollback();
+
+    assert_eq!(value(&router, Method::GET, "/a"), Some(1));
+    assert_eq!(value(&router, Method::GET, "/b"), None);
+}
+
+#[test]
+fn invalid_patterns_are_errors() {
+    let mut router = router();
+    let mut transaction = router.transaction();
+    transaction.insert(Method::GET, "/b", 3).insert(Method::GET, "/files/*name", 4);
+    assert!(matches!(transaction.commit(), Err(TransactionError::InvalidPattern(1, _))));
+
+    router.set_syntax(Syntax::Braces);
+    let mut transaction = router.transaction();
+    transaction.remove("/users/{id");
+    assert!(matches!(transaction.commit(), Err(TransactionError::InvalidPattern(0, _))));
+
+    let mut transaction = router.transaction();
+    transaction.insert(Method::GET, "/posts/{id}", 5);
+    transaction.commit().unwrap();
+    assert_eq!(value(&router, Method::GET, "/posts/1"), Some(5));
+    assert_eq!(value(&router, Method::GET, "/b"), None);
+}
+
+#[test]
+fn constrained_patterns_match_like_inserted_routes() {
+    let mut router = router();
+    router.register_constraint("num", |value: &str| value.bytes().all(|b| b.is_ascii_digit()));
+    let mut transaction = router.transaction();
+    transaction.insert(Method::GET, "/b/$id:num", 2).insert(Method::GET, "/b/*", 3);
+    transaction.commit().unwrap();
+
+    let patterns: Vec<String> = router.iter().map(|(_, pattern, _)| pattern).collect();
+    assert!(patterns.contains(&"/b/$id".to_string()), "{:?}", patterns);
+    assert_eq!(value(&router, Method::GET, "/b/1"), Some(2));
+    assert_eq!(value(&router, Method::GET, "/b/x"), Some(3));
+}
+
+#[test]
+fn hooks_are_called_after_the_commit() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut router = router();
+    let sink = events.clone();
+    router.on_change(move |event, method, pattern| sink.lock().unwrap().push((event, method.clone(), pattern.to_string())));
+
+    let mut transaction = router.transaction();
+    transaction.insert(Method::GET, "/b", 3).remove("/missing");
+    assert!(transaction.commit().is_err());
+    assert!(events.lock().unwrap().is_empty());
+
+    let mut transaction = router.transaction();
+    transaction.insert(Method::GET, "/b", 3).remove("/a");
+    transaction.commit().unwrap();
+    assert_eq!(
+        *events.lock().unwrap(),
+        [(RouteEvent::Insert, Method::GET, "/b".to_string()), (RouteEvent::Remove, Method::GET, "/a".to_string())]
+    );
+}
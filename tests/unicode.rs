@@ -0,0 +1,129 @@
+use std::borrow::Cow;
+use radixtree::{FrozenRouter, Method, Node, PersistentNode, Router};
+
+/// Patterns whose common prefixes end inside multi-byte characters, or right
+/// after them.
+const ROUTES: &[(&str, i32)] = &[
+    ("/日本語", 1),
+    ("/日本人", 2),
+    ("/日曜", 3),
+    ("/aé/x", 4),
+    ("/aè/y", 5),
+    ("/é", 6),
+    ("/è", 7),
+    ("/ñandú/$id", 8),
+    ("/files/$name.json", 9),
+    ("/docs/$path+", 10),
+];
+
+fn tree() -> Node<i32> {
+    let mut tree = Node::new();
+    for &(pattern, value) in ROUTES {
+        tree.insert(Method::GET, pattern, value);
+    }
+    tree
+}
+
+#[test]
+fn static_routes_split_at_char_boundaries() {
+    let tree = tree();
+    for &(pattern, value) in ROUTES.iter().filter(|(pattern, _)| !pattern.contains('$')) {
+        let result = tree.search(Method::GET, pattern).unwrap();
+        assert_eq!(*result.value(), value, "{}", pattern);
+        assert_eq!(result.pattern(), pattern);
+    }
+
+    assert!(tree.search(Method::GET, "/日本").is_none());
+    assert!(tree.search(Method::GET, "/a").is_none());
+    assert!(tree.search(Method::GET, "/ê").is_none());
+}
+
+#[test]
+fn params_take_non_ascii_values() {
+    let tree = tree();
+
+    let result = tree.search(Method::GET, "/ñandú/José").unwrap();
+    assert_eq!((*result.value(), &result.params()["id"]), (8, "José"));
+
+    let result = tree.search(Method::GET, "/files/résumé.json").unwrap();
+    assert_eq!((*result.value(), &result.params()["name"]), (9, "résumé"));
+
+    let result = tree.search(Method::GET, "/docs/日本/語").unwrap();
+    assert_eq!((*result.value(), &result.params()["path"]), (10, "日本/語"));
+}
+
+#[test]
+fn routes_are_removed_and_listed() {
+    let mut tree = tree();
+    assert_eq!(tree.remove("/日本人").unwrap(), vec![(Method::GET, 2)]);
+    assert_eq!(*tree.search(Method::GET, "/日本語").unwrap().value(), 1);
+    assert!(tree.search(Method::GET, "/日本人").is_none());
+
+    let patterns: Vec<String> = tree.iter().map(|(_, pattern, _)| pattern).collect();
+    assert_eq!(patterns.len(), ROUTES.len() - 1);
+    assert!(patterns.iter().any(|pattern| pattern == "/aè/y"));
+    assert_eq!(tree.complete("/日"), vec!["/日曜", "/日本語"]);
+}
+
+#[test]
+fn other_trees_agree_with_the_node() {
+    let tree = tree();
+    let mut persistent = PersistentNode::new();
+    let mut router = Router::new();
+    for &(pattern, value) in ROUTES {
+        persistent = persistent.insert(Method::GET, pattern, value);
+        router.insert(Method::GET, pattern, value).unwrap();
+    }
+    let compiled = router.compile();
+    let bytes = compiled.freeze(|value, out| out.extend_from_slice(&value.to_le_bytes()));
+    let frozen = FrozenRouter::from_bytes(&bytes).unwrap();
+
+    let paths = ["/日本語", "/日本人", "/日曜", "/日本", "/aé/x", "/aè/y", "/aê/y", "/é", "/è", "/ñandú/1", "/files/ü.json", "/docs/ß"];
+    for path in paths {
+        let expected = tree.search(Method::GET, path).map(|result| (*result.value(), result.params().clone()));
+
+        assert_eq!(persistent.search(Method::GET, path).map(|result| (*result.value(), result.params().clone())), expected, "{}", path);
+        assert_eq!(router.search(Method::GET, path).map(|result| (*result.value(), result.params().clone())), expected, "{}", path);
+        assert_eq!(compiled.search(Method::GET, path).map(|result| (*result.value(), result.params().clone())), expected, "{}", path);
+        let frozen_value = frozen.search(Method::GET, path).map(|result| i32::from_le_bytes((*result.value()).try_into().unwrap()));
+        assert_eq!(frozen_value, expected.map(|(value, _)| value), "{}", path);
+    }
+}
+
+/// Composes `e` followed by a combining acute accent into `é`, standing in
+/// for the NFC function of a normalization crate.
+fn compose(text: &str) -> Cow<'_, str> {
+    match text.contains("e\u{301}") {
+        true => Cow::Owned(text.replace("e\u{301}", "é")),
+        false => Cow::Borrowed(text),
+    }
+}
+
+#[test]
+fn normalized_paths_match_the_same_route() {
+    let mut router = Router::new();
+    router.set_unicode_normalizer(compose);
+    router.insert(Method::GET, "/cafe\u{301}/$name", 1).unwrap();
+    router.insert(Method::GET, "/résumé", 2).unwrap();
+
+    let result = router.search(Method::GET, "/café/crème").unwrap();
+    assert_eq!((*result.value(), result.pattern()), (1, "/café/$name"));
+    let result = router.search(Method::GET, "/cafe\u{301}/cre\u{301}me").unwrap();
+    assert_eq!((*result.value(), result.params().get("name")), (1, Some("créme")));
+    assert_eq!(*router.search(Method::GET, "/re\u{301}sume\u{301}").unwrap().value(), 2);
+    assert!(router.search(Method::GET, "/cafe/x").is_none());
+}
+
+#[test]
+fn paths_are_not_normalized_without_a_normalizer() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/café", 1).unwrap();
+    assert!(router.search(Method::GET, "/cafe\u{301}").is_none());
+
+    // The routes registered before the normalizer keep their form.
+    router.insert(Method::GET, "/cre\u{301}me", 2).unwrap();
+    router.set_unicode_normalizer(compose);
+    assert_eq!(*router.search(Method::GET, "/cafe\u{301}").unwrap().value(), 1);
+    assert!(router.search(Method::GET, "/cre\u{301}me").is_none());
+    assert!(router.search(Method::GET, "/créme").is_none());
+}
@@ -0,0 +1,70 @@
+use radixtree::{Method, Node, Router, UpdateError};
+
+fn tree() -> Node<u32> {
+    let mut tree = Node::new();
+    tree.insert(Method::GET, "/users/$id", 1);
+    tree.insert(Method::POST, "/users/$id", 2);
+    tree.insert(Method::GET, "/users/$id/posts", 3);
+    tree
+}
+
+#[test]
+fn update_returns_the_old_value() {
+    let mut tree = tree();
+    assert_eq!(tree.update(Method::GET, "/users/$id", 10), Ok(1));
+    assert_eq!(tree.update(Method::GET, "/users/$id", 20), Ok(10));
+    assert_eq!(tree.search(Method::GET, "/users/1").unwrap().value(), &20);
+    assert_eq!(tree.search(Method::POST, "/users/1").unwrap().value(), &2);
+}
+
+#[test]
+fn update_returns_why_the_route_is_missing() {
+    let mut tree = tree();
+    assert_eq!(tree.update(Method::GET, "/orders", 0), Err(UpdateError::PathNotFound));
+    // `/users` is a node of the tree, without handlers.
+    assert_eq!(tree.update(Method::GET, "/users", 0), Err(UpdateError::PathNotFound));
+    assert_eq!(tree.update(Method::DELETE, "/users/$id", 0), Err(UpdateError::MethodNotFound(Method::DELETE)));
+    assert!(tree.search(Method::DELETE, "/users/1").is_none());
+}
+
+#[test]
+fn update_methods_changes_every_method_or_none() {
+    let mut tree = tree();
+    assert_eq!(tree.update_methods([Method::GET, Method::DELETE], "/users/$id", 0), Err(UpdateError::MethodNotFound(Method::DELETE)));
+    assert_eq!(tree.search(Method::GET, "/users/1").unwrap().value(), &1);
+
+    assert_eq!(tree.update_methods([Method::POST, Method::GET], "/users/$id", 0), Ok(vec![2, 1]));
+    assert_eq!(tree.search(Method::POST, "/users/1").unwrap().value(), &0);
+}
+
+#[test]
+fn remove_returns_the_removed_handlers() {
+    let mut tree = tree();
+    assert_eq!(tree.remove("/orders"), None);
+    assert_eq!(tree.remove("/users"), None);
+
+    let mut removed = tree.remove("/users/$id").unwrap();
+    removed.sort_by_key(|(_, value)| *value);
+    assert_eq!(removed, [(Method::GET, 1), (Method::POST, 2)]);
+    assert_eq!(tree.remove("/users/$id"), None);
+    assert_eq!(tree.search(Method::GET, "/users/1/posts").unwrap().value(), &3);
+}
+
+#[test]
+fn remove_methods_returns_the_registered_methods() {
+    let mut tree = tree();
+    assert_eq!(tree.remove_methods([Method::DELETE], "/users/$id"), None);
+    assert_eq!(tree.remove_methods([Method::POST, Method::DELETE], "/users/$id"), Some(vec![(Method::POST, 2)]));
+    assert_eq!(tree.search(Method::GET, "/users/1").unwrap().value(), &1);
+}
+
+#[test]
+fn routers_update_and_remove_routes() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/users", 1).unwrap();
+
+    assert_eq!(router.update(Method::GET, "/users", 2), Ok(1));
+    assert_eq!(router.update(Method::PUT, "/users", 3), Err(UpdateError::MethodNotFound(Method::PUT)));
+    assert_eq!(router.remove("/users"), Some(vec![(Method::GET, 2)]));
+    assert_eq!(router.remove("/users"), None);
+}
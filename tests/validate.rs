@@ -0,0 +1,60 @@
+use radixtree::{Method, Node, Violation};
+
+type Tree = Node<u32>;
+
+fn leaf(path: &str, value: u32, names: Option<&[&str]>) -> Tree {
+    Node::__from_parts(path, vec![], None, None, vec![(Method::GET, value)], names)
+}
+
+#[test]
+fn trees_changed_through_their_methods_are_valid() {
+    let mut tree = Tree::new();
+    assert_eq!(tree.validate(), Ok(()));
+
+    for (i, pattern) in ["/users", "/users/$id", "/user", "/u/$id/posts", "/static/*", "/files/$path*", "/"].iter().enumerate() {
+        tree.insert(Method::GET, pattern, i as u32);
+        assert_eq!(tree.validate(), Ok(()), "{}", pattern);
+    }
+
+    for path in ["/user", "/u/$id/posts", "/static/*"] {
+        tree.remove(path);
+        assert_eq!(tree.validate(), Ok(()), "{}", path);
+    }
+    tree.retain(|_, pattern, _| pattern != "/users");
+    assert_eq!(tree.validate(), Ok(()));
+}
+
+#[test]
+fn validate_reports_unmerged_and_unused_nodes() {
+    // `/a` has no handlers and a single static child, `b`.
+    let unmerged = Tree::__from_parts("/", vec![Node::__from_parts("a", vec![leaf("b", 1, Some(&[]))], None, None, vec![], None)], None, None, vec![], None);
+    assert_eq!(unmerged.validate(), Err(vec![Violation::UnmergedNode("/a".to_string())]));
+
+    let unused = Tree::__from_parts("/", vec![Node::__from_parts("a", vec![], None, None, vec![], None)], None, None, vec![], None);
+    assert_eq!(unused.validate(), Err(vec![Violation::UnusedNode("/a".to_string())]));
+}
+
+#[test]
+fn validate_reports_parameter_names_which_dont_match() {
+    let param = Node::__from_parts("id", vec![], None, None, vec![(Method::GET, 1)], Some(&["id", "extra"]));
+    let tree = Tree::__from_parts("/", vec![], Some(param), None, vec![], None);
+    assert_eq!(tree.validate(), Err(vec![Violation::ParamNames("/$id".to_string())]));
+
+    let param = Node::__from_parts("i-d", vec![], None, None, vec![(Method::GET, 1)], Some(&["id"]));
+    let tree = Tree::__from_parts("/", vec![], Some(param), None, vec![], None);
+    assert_eq!(tree.validate(), Err(vec![Violation::InvalidParamName("/".to_string())]));
+}
+
+#[test]
+fn validate_reports_misplaced_wildcards() {
+    let star = Node::__from_parts("", vec![], None, None, vec![(Method::GET, 1)], Some(&[]));
+    let prefix = Node::__from_parts("a", vec![], None, Some(star), vec![(Method::GET, 2)], Some(&[]));
+    let tree = Tree::__from_parts("/", vec![prefix], None, None, vec![], None);
+    assert_eq!(tree.validate(), Err(vec![Violation::InvalidWildcard("/a".to_string())]));
+}
+
+#[test]
+fn violations_are_displayed_with_their_pattern() {
+    let violation = Violation::UnmergedNode("/a".to_string());
+    assert!(violation.to_string().contains("\"/a\""), "{}", violation);
+}
@@ -0,0 +1,34 @@
+use radixtree::{Method, Router};
+
+fn matched(router: &Router<&'static str>, path: &str) -> Option<(&'static str, String)> {
+    let result = router.search(Method::GET, path)?;
+    Some((*result.value(), result.pattern().to_string()))
+}
+
+#[test]
+fn rejected_validators_fall_through_or_miss() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/orders/$id", "ORDER").unwrap();
+    router.insert(Method::GET, "/orders/$id/items/$item", "ITEM").unwrap();
+    router.insert(Method::GET, "/orders/$id/*", "REST").unwrap();
+    assert!(router.add_validator(Method::GET, "/orders/$id", "id", |id: &str| id.parse::<u64>().is_ok()));
+    assert!(router.add_validator(Method::GET, "/orders/$id/items/$item", "id", |id: &str| id.parse::<u64>().is_ok()));
+    assert!(router.add_validator(Method::GET, "/orders/$id/items/$item", "item", |item: &str| item.len() < 4));
+    assert!(!router.add_validator(Method::GET, "/orders/$id", "item", |_: &str| true));
+
+    assert_eq!(matched(&router, "/orders/7"), Some(("ORDER", "/orders/$id".to_string())));
+    assert_eq!(matched(&router, "/orders/latest"), None);
+    assert_eq!(matched(&router, "/orders/7/items/abc"), Some(("ITEM", "/orders/$id/items/$item".to_string())));
+    assert_eq!(matched(&router, "/orders/7/items/abcd"), Some(("REST", "/orders/$id/*".to_string())));
+    assert_eq!(matched(&router, "/orders/x/items/abc"), Some(("REST", "/orders/$id/*".to_string())));
+}
+
+#[test]
+fn validators_need_a_registered_route_and_param() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/orders/$id", "ORDER").unwrap();
+    assert!(!router.add_validator(Method::POST, "/orders/$id", "id", |_: &str| false));
+    assert!(!router.add_validator(Method::GET, "/orders", "id", |_: &str| false));
+    assert!(!router.add_validator(Method::GET, "/orders/$id", "name", |_: &str| false));
+    assert!(router.search(Method::GET, "/orders/1").is_some());
+}
@@ -0,0 +1,60 @@
+use radixtree::{Method, Router, Weighted};
+
+#[test]
+fn pick_splits_the_points_by_weight() {
+    let weighted = Weighted::new().with("STABLE", 90).with("CANARY", 10);
+    assert_eq!(weighted.total_weight(), 100);
+    assert_eq!(weighted.pick(0), Some(&"STABLE"));
+    assert_eq!(weighted.pick(89), Some(&"STABLE"));
+    assert_eq!(weighted.pick(90), Some(&"CANARY"));
+    assert_eq!(weighted.pick(99), Some(&"CANARY"));
+    assert_eq!(weighted.pick(100), Some(&"STABLE"));
+    assert_eq!(weighted.pick(u64::MAX), weighted.pick(u64::MAX % 100));
+
+    let canaries = (0..1000).filter(|&point| weighted.pick(point) == Some(&"CANARY")).count();
+    assert_eq!(canaries, 100);
+}
+
+#[test]
+fn values_without_weight_are_never_picked() {
+    let weighted: Weighted<&str> = [("OFF", 0), ("A", 1), ("ALSO OFF", 0), ("B", 2)].into_iter().collect();
+    assert_eq!(weighted.len(), 4);
+    let picks: Vec<&str> = (0..3).map(|point| *weighted.pick(point).unwrap()).collect();
+    assert_eq!(picks, ["A", "B", "B"]);
+
+    assert_eq!(Weighted::new().with("OFF", 0).pick(0), None);
+    assert_eq!(Weighted::<&str>::new().pick(0), None);
+    assert!(Weighted::<&str>::default().is_empty());
+}
+
+#[test]
+fn pick_by_key_is_sticky() {
+    let weighted = Weighted::new().with("A", 1).with("B", 1).with("C", 1);
+    for key in ["alice", "bob", "carol"] {
+        let picked = weighted.pick_by_key(key.as_bytes());
+        assert_eq!(picked, weighted.pick(Weighted::<&str>::hash_key(key.as_bytes())));
+        assert_eq!(picked, weighted.pick_by_key(key.as_bytes()));
+    }
+    assert_eq!(Weighted::<&str>::hash_key(b""), 0xcbf29ce484222325);
+}
+
+#[test]
+fn iter_keeps_the_order_of_the_values() {
+    let mut weighted = Weighted::new();
+    weighted.push("A", 3);
+    weighted.push("B", 0);
+    assert_eq!(weighted.iter().collect::<Vec<_>>(), [(&"A", 3), (&"B", 0)]);
+}
+
+#[test]
+fn search_weighted_picks_a_value_of_the_route() {
+    let mut router = Router::new();
+    router.insert(Method::GET, "/checkout/$id", Weighted::new().with("STABLE", 90).with("CANARY", 10)).unwrap();
+    router.insert(Method::GET, "/disabled", Weighted::new().with("OFF", 0)).unwrap();
+
+    let result = router.search_weighted(Method::GET, "/checkout/7", 95).unwrap();
+    assert_eq!((result.value(), &result.params()["id"], result.pattern()), (&"CANARY", "7", "/checkout/$id"));
+    assert_eq!(router.search_weighted(Method::GET, "/checkout/7", 5).unwrap().value(), &"STABLE");
+    assert!(router.search_weighted(Method::GET, "/disabled", 0).is_none());
+    assert!(router.search_weighted(Method::GET, "/missing", 0).is_none());
+}